@@ -12,6 +12,73 @@ var keyEventsActive = false;
 // to connect.
 var connectingToWebsocket = false;
 
+// Set to true if the player wants to use a joystick instead of the direction
+// buttons. The joystick sends analog input to the server.
+var joystickMode = false;
+
+// The latest analog values that was sent to the server. Used to only send new
+// analog events when the values actually changes.
+var joystickSent = { x: 0, y: 0 };
+
+// The directions that are currently "pressed" by the joystick. The joystick
+// also sends the digital direction events so that it works in menus etc.
+var joystickDirections = { up: false, right: false, down: false, left: false };
+
+// How far (between 0 and 1) the joystick must be pushed in a direction before
+// it is seen as the direction button being pressed.
+const JOYSTICK_DIGITAL_THRESHOLD = 0.5;
+
+// Set to true if connected to the server as a spectator.
+var spectating = false;
+
+// The version of the protocol used to talk to the server. Must match the
+// `PROTOCOL_VERSION` of the server.
+const PROTOCOL_VERSION = 2;
+
+// Bitflag of the features supported by this client.
+const FEATURE_ANALOG = 1 << 0;
+const FEATURE_SESSION_RESUME = 1 << 1;
+const SUPPORTED_FEATURES = FEATURE_ANALOG | FEATURE_SESSION_RESUME;
+
+// The features supported by both this client and the server. Set when the
+// server have accepted the handshake.
+var negotiatedFeatures = 0;
+
+// Set to a description of the reason if the server rejected the handshake.
+// Shown to the user when the connection is closed by the server.
+var rejectReason;
+const rejectReasons = [
+    "This client is outdated, please reload the page.",
+    "The server is outdated.",
+    "Invalid handshake.",
+    "The session have expired.",
+    "The name can't be empty.",
+    "The name is too long.",
+];
+
+// The session token received from the server. It is used to resume the session
+// (and keep score, color etc.) if the connection drops.
+var sessionToken;
+
+// The address of the server that we are connected to. Used when reconnecting.
+var serverAddr;
+
+// The amount of times that we have tried to reconnect since the connection
+// dropped. Reset when the server acknowledges the resumed session.
+var reconnectAttempts = 0;
+const MAX_RECONNECT_ATTEMPTS = 10;
+const RECONNECT_DELAY_MS = 2000;
+
+// The latest state received from the server. Displayed above the buttons.
+var serverState = {
+    gameState: undefined,
+    score: undefined,
+    team: undefined,
+    died: false,
+    reconnecting: false,
+    queuePosition: 0,
+};
+
 function navigateToConnect() {
     document.body.innerHTML = 
           '<div id="header">'
@@ -23,8 +90,12 @@ function navigateToConnect() {
         + '        <input class="login-checkbox" type="checkbox" id="tls" checked>'
         + '    </div>'
         + '    <div class="login-field">'
+        + '        <div class="login-label">Stick</div>'
+        + '        <input class="login-checkbox" type="checkbox" id="joystick"' + (joystickMode ? ' checked' : '') + '>'
+        + '    </div>'
+        + '    <div class="login-field">'
         + '        <div class="login-label">Name</div>'
-        + '        <input class="login-input" type="text" id="name">'
+        + '        <input class="login-input" type="text" id="name" maxlength="32">'
         + '    </div>'
         + '    <div class="login-field">'
         + '        <div class="login-label">Host</div>'
@@ -34,30 +105,74 @@ function navigateToConnect() {
         + '        <div class="login-label">Port</div>'
         + '        <input class="login-input" type="text" id="port">'
         + '    </div>'
-        + '    <button class="login-button" onclick="connect();">Connect</button>'
+        + '    <button class="login-button" onclick="connect(false);">Connect</button>'
+        + '    <button class="login-button login-button-secondary" onclick="connect(true);">Spectate</button>'
         + '</div>';
+    prefillConnectForm();
     keyEventsActive = false;
 }
 
-function navigateToButtons() {
+// The view shown to spectators. Spectators can't control anything, they only
+// see the state sent from the server.
+function navigateToSpectator() {
     document.body.innerHTML =
-          '<button class="game-button game-button-up" onpointerdown="mouseDown(upPressed)" onpointerup="mouseUp(upReleased)" onpointerout="mouseOut(upReleased)">Up</button>'
+          '<div class="status" id="status"></div>'
+        + '<div class="spectator-info">Spectating</div>'
+        + '<button class="login-button" onclick="disconnect();">Leave</button>';
+    keyEventsActive = false;
+    updateStatus();
+}
+
+// If this page is served by the game server itself, the server is located at
+// the same address as the page. Prefill the connect form with that address so
+// that the players only have to enter their names.
+function prefillConnectForm() {
+    if (typeof servedByGameServer === "undefined" || !servedByGameServer) {
+        return;
+    }
+
+    const tls = window.location.protocol === "https:";
+    const port = window.location.port || (tls ? "443" : "80");
+    document.getElementById("tls").checked = tls;
+    document.getElementById("host").value = window.location.hostname;
+    document.getElementById("port").value = port;
+}
+
+function navigateToButtons() {
+    const directionButtons = joystickMode
+        ? '<div class="joystick" id="joystick-area">'
+          + '    <div class="joystick-knob" id="joystick-knob"></div>'
+          + '</div>'
+        : '<button class="game-button game-button-up" onpointerdown="mouseDown(upPressed)" onpointerup="mouseUp(upReleased)" onpointerout="mouseOut(upReleased)">Up</button>'
         + '<div class="game-button-sides">'
         + '    <button class="game-button game-button-left" onpointerdown="mouseDown(leftPressed)" onpointerup="mouseUp(leftReleased)" onpointerout="mouseOut(leftReleased)">Left</button>'
         + '    <button class="game-button game-button-right" onpointerdown="mouseDown(rightPressed)" onpointerup="mouseUp(rightReleased)" onpointerout="mouseOut(rightReleased)">Right</button>'
         + '</div>'
-        + '<button class="game-button game-button-down" onpointerdown="mouseDown(downPressed)" onpointerup="mouseUp(downReleased)" onpointerout="mouseOut(downReleased)">Down</button>'
+        + '<button class="game-button game-button-down" onpointerdown="mouseDown(downPressed)" onpointerup="mouseUp(downReleased)" onpointerout="mouseOut(downReleased)">Down</button>';
+
+    document.body.innerHTML =
+          '<div class="status" id="status"></div>'
+        + directionButtons
         + '<div class="bottom">'
         + '    <button class="game-button game-button-a" onpointerdown="mouseDown(aPressed)" onpointerup="mouseUp(aReleased)" onpointerout="mouseOut(aReleased)">A</button>'
         + '    <button class="game-button game-button-b" onpointerdown="mouseDown(bPressed)" onpointerup="mouseUp(bReleased)" onpointerout="mouseOut(bReleased)">B</button>'
         + '</div>';
     keyEventsActive = true;
+    if (joystickMode) {
+        setupJoystick();
+    }
+    updateStatus();
 }
 
-function connect() {
+// Connects to the server as a player, or as a spectator if `spectate` is true.
+function connect(spectate) {
     if ("WebSocket" in window) {
         if (typeof webSocket !== "undefined") {
-            navigateToButtons();
+            if (spectating) {
+                navigateToSpectator();
+            } else {
+                navigateToButtons();
+            }
             alert("Already connected to server.");
             return;
         } else if (connectingToWebsocket) {
@@ -69,11 +184,12 @@ function connect() {
         const name = document.getElementById("name").value;
         const host = document.getElementById("host").value;
         const port = parseInt(document.getElementById("port").value);
+        joystickMode = document.getElementById("joystick").checked;
 
         if (isNaN(port)) {
             alert("Unable to parse port as number. Try again.");
             return;
-        } else if (!name) {
+        } else if (!name && !spectate) {
             alert("Need to specify a non-empty name.");
             return;
         } else if (!host) {
@@ -82,37 +198,98 @@ function connect() {
         }
 
         const protocol = tls ? "wss" : "ws";
-        const addr = protocol + "://" + host + ":" + port;
-        const localWebSocket = new WebSocket(addr);
-
-        connectingToWebsocket = true;
-        console.log("Connecting to address \"" + addr + "\" with name \"" + name + "\"");
-
-        localWebSocket.onopen = function(_) {
-            connectingToWebsocket = false;
-            navigateToButtons();
-            const connectMsg = [1].concat(stringToUTF8Array(name));
-            localWebSocket.send(new Uint8Array(connectMsg));
-            webSocket = localWebSocket;
+        serverAddr = protocol + "://" + host + ":" + port;
+        sessionToken = undefined;
+        reconnectAttempts = 0;
+        spectating = spectate;
+        serverState = {
+            gameState: undefined,
+            score: undefined,
+            team: undefined,
+            died: false,
+            reconnecting: false,
+            queuePosition: 0,
         };
 
-        localWebSocket.onclose = function(_) {
-            connectingToWebsocket = false;
-            if (typeof webSocket !== "undefined") {
-                alert("Connection to server closed.");
-                webSocket = undefined;
-                navigateToConnect();
-            } else {
-                alert("Unable to connect to server.");
-            }
-        };
+        if (spectate) {
+            console.log("Connecting to address \"" + serverAddr + "\" as spectator");
+            openWebSocket([4, PROTOCOL_VERSION, SUPPORTED_FEATURES, 2]);
+        } else {
+            console.log("Connecting to address \"" + serverAddr + "\" with name \"" + name + "\"");
+            const connectMsg = [4, PROTOCOL_VERSION, SUPPORTED_FEATURES, 0].concat(stringToUTF8Array(name));
+            openWebSocket(connectMsg);
+        }
     } else {
         alert("Websockets not supported in this browser.");
     }
 }
 
+// Opens a websocket to `serverAddr` and sends `firstMsg` when the connection
+// is established. The first message is either a connect or reconnect message.
+function openWebSocket(firstMsg) {
+    const localWebSocket = new WebSocket(serverAddr);
+    localWebSocket.binaryType = "arraybuffer";
+    connectingToWebsocket = true;
+    var opened = false;
+
+    localWebSocket.onopen = function(_) {
+        connectingToWebsocket = false;
+        opened = true;
+        if (spectating) {
+            navigateToSpectator();
+        } else {
+            navigateToButtons();
+        }
+        localWebSocket.send(new Uint8Array(firstMsg));
+        webSocket = localWebSocket;
+    };
+
+    localWebSocket.onmessage = function(event) {
+        handleServerMessage(event.data);
+    };
+
+    localWebSocket.onclose = function(_) {
+        connectingToWebsocket = false;
+        if (webSocket === localWebSocket) {
+            // The connection dropped, try to resume the session.
+            webSocket = undefined;
+            tryReconnect();
+        } else if (reconnectAttempts > 0) {
+            // The reconnect attempt failed, try again.
+            tryReconnect();
+        } else if (!opened) {
+            alert("Unable to connect to server.");
+        }
+    };
+}
+
+// Tries to resume the session with the server using the session token that
+// was received when connecting. Gives up after `MAX_RECONNECT_ATTEMPTS`.
+function tryReconnect() {
+    if (typeof sessionToken === "undefined" || reconnectAttempts >= MAX_RECONNECT_ATTEMPTS) {
+        sessionToken = undefined;
+        reconnectAttempts = 0;
+        alert(rejectReason || "Connection to server closed.");
+        rejectReason = undefined;
+        navigateToConnect();
+        return;
+    }
+
+    reconnectAttempts++;
+    serverState.reconnecting = true;
+    updateStatus();
+
+    console.log("Reconnecting to address \"" + serverAddr + "\", attempt " + reconnectAttempts);
+    const reconnectMsg = [4, PROTOCOL_VERSION, SUPPORTED_FEATURES, 1].concat(Array.from(sessionToken));
+    setTimeout(function() {
+        openWebSocket(reconnectMsg);
+    }, RECONNECT_DELAY_MS);
+}
+
 function disconnect() {
     if (typeof webSocket !== "undefined") {
+        sessionToken = undefined;
+        reconnectAttempts = 0;
         webSocket.close();
         webSocket = undefined;
         navigateToConnect();
@@ -125,6 +302,104 @@ function send(bytes) {
     }
 }
 
+// Handles a message sent from the server. See the top-level comment in
+// "plugins/network/src/event.rs" for the binary format of the messages.
+function handleServerMessage(data) {
+    const bytes = new Uint8Array(data);
+    if (bytes.length === 0) {
+        return;
+    }
+
+    switch (bytes[0]) {
+        case 0:
+            // The second byte is the kind of state (menu/game), only the name
+            // of the state is displayed.
+            serverState.gameState = new TextDecoder().decode(bytes.slice(2));
+            serverState.team = undefined;
+            serverState.died = false;
+            break;
+        case 1:
+            serverState.score = new DataView(data).getUint32(1);
+            break;
+        case 2:
+            serverState.died = true;
+            if (navigator.vibrate) {
+                navigator.vibrate(200);
+            }
+            setTimeout(function() {
+                serverState.died = false;
+                updateStatus();
+            }, 2000);
+            break;
+        case 3:
+            serverState.team = bytes[1] === 0 ? "Left" : "Right";
+            break;
+        case 4:
+            // Received when connecting and as an acknowledgement when the
+            // session have been resumed.
+            sessionToken = bytes.slice(1, 9);
+            reconnectAttempts = 0;
+            serverState.reconnecting = false;
+            break;
+        case 5:
+            negotiatedFeatures = bytes[2];
+            console.log("Handshake accepted, server version: " + bytes[1] + ", features: " + negotiatedFeatures);
+            break;
+        case 6:
+            // The server will close the connection after this message, don't
+            // try to reconnect.
+            rejectReason = rejectReasons[bytes[1]] || "Handshake rejected by server.";
+            console.log("Handshake rejected, server version: " + bytes[2]);
+            sessionToken = undefined;
+            reconnectAttempts = 0;
+            return;
+        case 7:
+            serverState.queuePosition = new DataView(data).getUint32(1);
+            break;
+        case 8:
+            serverState.name = new TextDecoder().decode(bytes.slice(1));
+            break;
+        default:
+            console.log("Received unknown message from server: " + bytes);
+            return;
+    }
+
+    updateStatus();
+}
+
+// Updates the status text displayed above the buttons with the latest state
+// received from the server.
+function updateStatus() {
+    const status = document.getElementById("status");
+    if (!status) {
+        return;
+    }
+
+    const parts = [];
+    if (typeof serverState.name !== "undefined") {
+        parts.push(serverState.name);
+    }
+    if (serverState.queuePosition > 0) {
+        parts.push("Server full, place in queue: " + serverState.queuePosition);
+    }
+    if (typeof serverState.gameState !== "undefined") {
+        parts.push(serverState.gameState);
+    }
+    if (typeof serverState.score !== "undefined") {
+        parts.push("Score: " + serverState.score);
+    }
+    if (typeof serverState.team !== "undefined") {
+        parts.push("Team: " + serverState.team);
+    }
+    if (serverState.died) {
+        parts.push("You died!");
+    }
+    if (serverState.reconnecting) {
+        parts.push("Reconnecting...");
+    }
+    status.textContent = parts.join(" | ");
+}
+
 function upPressed() { send([0, 0]); }
 function upReleased() { send([0, 1]); }
 function rightPressed() { send([0, 2]); }
@@ -138,6 +413,87 @@ function aReleased() { send([0, 9]); }
 function bPressed() { send([0, 10]); }
 function bReleased() { send([0, 11]); }
 
+// Sends the analog vector `x` & `y` (between -1 and 1, positive y is up) to
+// the server. The values are quantized to signed bytes.
+function sendAnalog(x, y) {
+    if ((negotiatedFeatures & FEATURE_ANALOG) === 0) {
+        return;
+    }
+    const qx = Math.round(Math.max(-1, Math.min(1, x)) * 127);
+    const qy = Math.round(Math.max(-1, Math.min(1, y)) * 127);
+    if (qx === joystickSent.x && qy === joystickSent.y) {
+        return;
+    }
+    joystickSent = { x: qx, y: qy };
+    send([3, qx & 0xFF, qy & 0xFF]);
+}
+
+// Sends digital press/release events for the directions that the joystick
+// have entered/left since the last call.
+function updateJoystickDirections(x, y) {
+    const directions = {
+        up: y > JOYSTICK_DIGITAL_THRESHOLD,
+        right: x > JOYSTICK_DIGITAL_THRESHOLD,
+        down: y < -JOYSTICK_DIGITAL_THRESHOLD,
+        left: x < -JOYSTICK_DIGITAL_THRESHOLD,
+    };
+    const actions = {
+        up: [upPressed, upReleased],
+        right: [rightPressed, rightReleased],
+        down: [downPressed, downReleased],
+        left: [leftPressed, leftReleased],
+    };
+    for (const dir in directions) {
+        if (directions[dir] !== joystickDirections[dir]) {
+            actions[dir][directions[dir] ? 0 : 1]();
+        }
+    }
+    joystickDirections = directions;
+}
+
+function setupJoystick() {
+    const area = document.getElementById("joystick-area");
+    const knob = document.getElementById("joystick-knob");
+    joystickSent = { x: 0, y: 0 };
+    joystickDirections = { up: false, right: false, down: false, left: false };
+
+    const move = function(event) {
+        const rect = area.getBoundingClientRect();
+        const radius = Math.min(rect.width, rect.height) / 2;
+        var dx = event.clientX - (rect.left + rect.width / 2);
+        var dy = event.clientY - (rect.top + rect.height / 2);
+        const len = Math.sqrt(dx * dx + dy * dy);
+        if (len > radius) {
+            dx = dx / len * radius;
+            dy = dy / len * radius;
+        }
+        knob.style.transform = "translate(" + dx + "px, " + dy + "px)";
+
+        const x = dx / radius;
+        const y = -dy / radius;
+        sendAnalog(x, y);
+        updateJoystickDirections(x, y);
+    };
+
+    const release = function(_) {
+        knob.style.transform = "";
+        sendAnalog(0, 0);
+        updateJoystickDirections(0, 0);
+    };
+
+    area.onpointerdown = function(event) {
+        area.setPointerCapture(event.pointerId);
+        move(event);
+    };
+    area.onpointermove = function(event) {
+        if (area.hasPointerCapture(event.pointerId)) {
+            move(event);
+        }
+    };
+    area.onpointerup = release;
+    area.onpointercancel = release;
+}
+
 function mouseDown(f) {
     buttonPressed = true;
     f();
@@ -1,3 +1,5 @@
+use std::collections::VecDeque;
+
 /// Contains the current action performed by a specific player.
 #[derive(Debug, Clone, Default)]
 pub struct PlayerAction {
@@ -8,12 +10,27 @@ pub struct PlayerAction {
     pub a_pressed: bool,
     pub b_pressed: bool,
 
-    /// Contains the previous/latest action that was performed on this `PlayerAction`.
-    pub prev_action: ActionEvent,
+    /// The latest analog input sent by the client. This will only be set for
+    /// clients that sends analog input (ex. a joystick), clients that only
+    /// sends digital button presses will have this set to None.
+    pub analog: Option<AnalogAction>,
+
+    /// Contains all actions (presses/releases) that have been performed on this
+    /// `PlayerAction` since the last time that they were read. The actions are
+    /// stored in the order that they were performed.
+    pub new_actions: VecDeque<ActionEvent>,
+}
 
-    /// If this is set to true, there have been a new actions since we checked
-    /// the current value(s) of this `PlayerAction`.
-    pub new_action_since_last_read: bool,
+impl PlayerAction {
+    /// Returns true if the same buttons are pressed in both `self` and `other`.
+    pub fn same_buttons(&self, other: &PlayerAction) -> bool {
+        self.up_pressed == other.up_pressed
+            && self.right_pressed == other.right_pressed
+            && self.down_pressed == other.down_pressed
+            && self.left_pressed == other.left_pressed
+            && self.a_pressed == other.a_pressed
+            && self.b_pressed == other.b_pressed
+    }
 }
 
 /// An event that represents the action sent from a specfic client.
@@ -31,6 +48,7 @@ pub enum ActionEvent {
     AReleased,
     BPressed,
     BReleased,
+    Analog(AnalogAction),
     None,
 }
 
@@ -39,3 +57,47 @@ impl Default for ActionEvent {
         ActionEvent::None
     }
 }
+
+/// Analog input sent from a client. The values are quantized to make them
+/// cheap to send over the wire.
+///
+/// `x` & `y` represents a 2D vector where -127 & 127 are the max values in
+/// each direction (positive `y` is up). `trigger` is a value between 0
+/// (released) and 255 (fully pressed).
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash, Default)]
+pub struct AnalogAction {
+    pub x: i8,
+    pub y: i8,
+    pub trigger: u8,
+}
+
+impl AnalogAction {
+    /// Creates an analog action from floats. `x` & `y` are expected to be
+    /// between -1.0 & 1.0 and `trigger` between 0.0 & 1.0, any values outside
+    /// these ranges are clamped.
+    pub fn new(x: f32, y: f32, trigger: f32) -> Self {
+        Self {
+            x: (x.clamp(-1.0, 1.0) * i8::MAX as f32).round() as i8,
+            y: (y.clamp(-1.0, 1.0) * i8::MAX as f32).round() as i8,
+            trigger: (trigger.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8,
+        }
+    }
+
+    /// Returns the analog vector as floats between -1.0 & 1.0. The length of
+    /// the returned vector will never be greater than 1.0.
+    pub fn vec(&self) -> (f32, f32) {
+        let x = (self.x as f32 / i8::MAX as f32).clamp(-1.0, 1.0);
+        let y = (self.y as f32 / i8::MAX as f32).clamp(-1.0, 1.0);
+        let len = (x * x + y * y).sqrt();
+        if len > 1.0 {
+            (x / len, y / len)
+        } else {
+            (x, y)
+        }
+    }
+
+    /// Returns the trigger value as a float between 0.0 & 1.0.
+    pub fn trigger(&self) -> f32 {
+        self.trigger as f32 / u8::MAX as f32
+    }
+}
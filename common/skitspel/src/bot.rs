@@ -0,0 +1,47 @@
+/// How far (between 0.0 & 1.0) the movement of a bot needs to point in a
+/// direction for the corresponding direction button to count as pressed.
+pub(crate) const BOT_BUTTON_THRESHOLD: f32 = 0.5;
+
+/// The input that a bot wants to give during the current tick. This is decided
+/// by the bot AI of the game that is currently being played.
+///
+/// The input is turned into the same `ActionEvent`s that a client with analog
+/// input would have sent (see `Player::bot_actions`), so the games don't need
+/// to treat bots any different from other players.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct BotInput {
+    /// The direction that the bot wants to move in. Both values are between
+    /// -1.0 & 1.0 (positive y is up).
+    pub movement: (f32, f32),
+    pub a: bool,
+    pub b: bool,
+}
+
+impl BotInput {
+    /// Creates input that moves the bot from `from` towards `to`. The bot moves
+    /// at full speed until it is within `slow_distance` of `to`, after which the
+    /// speed is lowered linearly to make it stop at the target.
+    pub fn towards(from: (f32, f32), to: (f32, f32), slow_distance: f32) -> Self {
+        let (dx, dy) = (to.0 - from.0, to.1 - from.1);
+        let distance = (dx * dx + dy * dy).sqrt();
+        let movement = if distance > f32::EPSILON {
+            let speed = (distance / slow_distance).min(1.0);
+            (dx / distance * speed, dy / distance * speed)
+        } else {
+            (0.0, 0.0)
+        };
+        Self {
+            movement,
+            ..Default::default()
+        }
+    }
+}
+
+/// Event used to add or remove bots. Bots are given a `PlayerId` and are
+/// added to `Players` just like any other player.
+#[derive(Debug, Clone, Copy)]
+pub enum BotEvent {
+    Add,
+    /// Removes the most recently added bot (if any).
+    Remove,
+}
@@ -0,0 +1,64 @@
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+use crate::PlayerId;
+
+/// The latency between the server and a specific client.
+///
+/// Both values are smoothed so that a single slow ping doesn't make the values
+/// jump around. The jitter is calculated in the same way as in RTP (RFC 3550).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Latency {
+    /// Smoothed round-trip time.
+    rtt: Duration,
+    /// Smoothed variation of the round-trip time between two measurements.
+    jitter: Duration,
+    /// The latest round-trip time that was measured.
+    last_rtt: Option<Duration>,
+}
+
+impl Latency {
+    /// Updates the latency with a new `rtt` measurement.
+    pub fn update(&mut self, rtt: Duration) {
+        if let Some(last_rtt) = self.last_rtt {
+            let rtt_secs = self.rtt.as_secs_f32();
+            self.rtt = Duration::from_secs_f32(rtt_secs + (rtt.as_secs_f32() - rtt_secs) / 8.0);
+
+            let diff = (rtt.as_secs_f32() - last_rtt.as_secs_f32()).abs();
+            let jitter_secs = self.jitter.as_secs_f32();
+            self.jitter = Duration::from_secs_f32(jitter_secs + (diff - jitter_secs) / 16.0);
+        } else {
+            self.rtt = rtt;
+        }
+        self.last_rtt = Some(rtt);
+    }
+
+    pub fn rtt(&self) -> Duration {
+        self.rtt
+    }
+
+    pub fn jitter(&self) -> Duration {
+        self.jitter
+    }
+}
+
+/// Will be a resource in bevy that contains the latency of all players.
+#[derive(Debug, Default)]
+pub struct Latencies(HashMap<PlayerId, Latency>);
+
+impl Deref for Latencies {
+    type Target = HashMap<PlayerId, Latency>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Latencies {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
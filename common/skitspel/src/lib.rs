@@ -1,14 +1,27 @@
-pub use action::{ActionEvent, PlayerAction};
+pub use action::{ActionEvent, AnalogAction, PlayerAction};
+pub use bot::{BotEvent, BotInput};
 pub use color::Color;
-pub use network::{Port, TLSCertificate};
+pub use latency::{Latencies, Latency};
+pub use network::{
+    HandshakeRejectReason, JoinUrl, Port, ServerMessage, ServerMessageEvent, TLSCertificate,
+};
 pub use player::{
-    ConnectedPlayers, DisconnectedPlayers, Player, PlayerId, PlayerIdGenerator, Players,
+    ConnectedPlayers, ConnectedSpectators, DisconnectedPlayers, Player, PlayerId,
+    PlayerIdGenerator, Players, ReconnectedPlayers,
 };
+pub use score::ScoreBook;
+pub use stats::{GameStats, PlayerStats, Profiles, DEFAULT_RATING};
+pub use team::Team;
 
 mod action;
+mod bot;
 mod color;
+mod latency;
 mod network;
 mod player;
+mod score;
+mod stats;
+mod team;
 
 /// All possible states in the game.
 #[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
@@ -17,13 +30,19 @@ pub enum GameState {
     StartMenu,
     /// State between games when selecting a new game to play.
     GameSelectionMenu,
+    /// State between the games of a tournament, shows the tournament standings.
+    TournamentStandings,
+    /// State after the last game of a tournament, shows the final podium.
+    TournamentPodium,
+    /// State after a match of a game have ended, shows the results of the match.
+    MatchResults,
+    /// State showing the all-time & session rankings of the players in every game.
+    Leaderboard,
 
-    /// The currently active game.
-    PushGame,
-    HockeyGame,
-    VolleyBallGame,
-    AchtungGame,
-    PongGame,
+    /// The game with the given ID is being played. The games register
+    /// themselves with their ID when their plugins are built, see the `Games`
+    /// resource in `util_bevy`.
+    Game(&'static str),
 }
 
 /// The units used in the the rapier is recommended to represent SI units.
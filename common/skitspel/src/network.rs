@@ -1,3 +1,5 @@
+use crate::{GameState, PlayerId, Team};
+
 /// Represents a port. This will be used as an resource to indicate which port
 /// the server is listening on.
 pub struct Port(pub u16);
@@ -9,3 +11,77 @@ pub struct TLSCertificate {
     pub path: String,
     pub password: String,
 }
+
+/// The URL that the players should open in their browsers to join the game.
+/// The web client is served by the server on the same port as the websocket.
+/// It will be used as a bevy resource.
+pub struct JoinUrl(pub String);
+
+/// Messages that can be sent from the server to the clients. These are used to
+/// push state (that is otherwise only visible on the shared screen) to the
+/// phones of the players.
+///
+/// See the top-level comment in the `event.rs` file of the network plugin for
+/// the binary format of these messages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerMessage {
+    /// The current state of the game, ex. which game that is being played. The
+    /// string is the name of the state that is shown to the players (ex. the
+    /// name of the game).
+    GameState(GameState, &'static str),
+    /// The current score of the receiving player.
+    Score(usize),
+    /// The receiving player died/got knocked out in the current round.
+    Died,
+    /// The team that the receiving player belongs to.
+    Team(Team),
+    /// The token of the session of the receiving player. It can be used to
+    /// resume the session (keep the same `PlayerId`) if the connection drops.
+    SessionToken(u64),
+    /// The position of the receiving client in the queue of clients waiting
+    /// for a free player slot. A position of 0 means that the client have been
+    /// promoted from the queue and is now an active player.
+    QueuePosition(usize),
+    /// The name of the receiving player. This might differ from the name that
+    /// the client specified if ex. another player already used the same name.
+    Name(String),
+    /// The handshake of the receiving client was accepted. Contains the
+    /// protocol version of the server and the features that both the client
+    /// and server supports.
+    HandshakeAccepted { version: u8, features: u8 },
+    /// The handshake of the receiving client was rejected. Contains the reason
+    /// and the protocol version of the server. The connection is closed by the
+    /// server after this message have been sent.
+    HandshakeRejected {
+        reason: HandshakeRejectReason,
+        version: u8,
+    },
+}
+
+/// The reason that the handshake of a client was rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandshakeRejectReason {
+    /// The client uses an older protocol version than the server.
+    OutdatedClient,
+    /// The client uses a newer protocol version than the server.
+    OutdatedServer,
+    /// The first message sent by the client wasn't a valid handshake.
+    InvalidHandshake,
+    /// The client tried to resume a session that doesn't exist or have expired.
+    SessionExpired,
+    /// The name specified by the client was empty (after removing whitespace
+    /// and invisible characters).
+    EmptyName,
+    /// The name specified by the client was too long.
+    NameTooLong,
+}
+
+/// Event used by systems to send `ServerMessage`s to the clients. The network
+/// plugin reads these events and writes the messages to the websockets.
+#[derive(Debug, Clone)]
+pub enum ServerMessageEvent {
+    /// Sends the message to the client of the player with the given ID.
+    Player(PlayerId, ServerMessage),
+    /// Sends the message to all connected clients.
+    Broadcast(ServerMessage),
+}
@@ -4,7 +4,17 @@ use std::{
     ops::{Deref, DerefMut},
 };
 
-use crate::{ActionEvent, Color, PlayerAction};
+use crate::{
+    bot::BOT_BUTTON_THRESHOLD, ActionEvent, AnalogAction, BotInput, Color, GameStats, PlayerAction,
+    PlayerStats, ScoreBook,
+};
+
+/// The maximum amount of actions that are stored until they are read with
+/// `Player::previous_actions_once`. Every game state reads (or ignores) the
+/// actions every frame, so this is only reached if the actions stops being
+/// read. If more actions are performed, the oldest ones are dropped and an
+/// error is logged.
+const MAX_PREVIOUS_ACTIONS: usize = 1024;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PlayerId(u64);
@@ -51,7 +61,7 @@ impl Default for PlayerIdGenerator {
 #[derive(Debug, Clone)]
 pub struct Player {
     id: PlayerId,
-    score: usize,
+    scores: ScoreBook,
 
     /// Name specified by the client when it connected to the server.
     name: String,
@@ -63,20 +73,118 @@ pub struct Player {
     /// Indicates the current buttons that this player is currently pushing/
     /// not pushing.
     action: PlayerAction,
+
+    /// Set to true if this player is a bot controlled by the server.
+    bot: bool,
+
+    /// The name of the profile that the all-time statistics of the player are
+    /// stored in (see `Profiles`). This is usually the name that the client
+    /// specified, which might differ from `name` if that name was already
+    /// taken. If the profile of the specified name is already used by another
+    /// player, `name` is used instead. Set to None for bots.
+    profile_name: Option<String>,
+
+    /// The all-time statistics of the player. Loaded from the profile of the
+    /// player when it connects.
+    stats: PlayerStats,
+    /// The statistics of the player since it connected.
+    session_stats: PlayerStats,
 }
 
 impl Player {
     pub fn new(id: PlayerId, name: String, color: Color) -> Self {
         Self {
             id,
-            score: 0,
+            scores: Default::default(),
             name,
             color,
             action: Default::default(),
+            bot: false,
+            profile_name: None,
+            stats: Default::default(),
+            session_stats: Default::default(),
         }
     }
 
+    /// Creates a new player that is controlled by the bot AI of the games.
+    pub fn new_bot(id: PlayerId, name: String, color: Color) -> Self {
+        Self {
+            bot: true,
+            ..Self::new(id, name, color)
+        }
+    }
+
+    pub fn is_bot(&self) -> bool {
+        self.bot
+    }
+
+    /// Returns the `ActionEvent`s that needs to be applied to this player (with
+    /// `update_action`) for its current action to match the given bot `input`.
+    /// An empty vector is returned if the input is already up to date.
+    ///
+    /// Both the digital buttons and the analog input are updated. The digital
+    /// buttons allows bots to be used in games that only looks at the button
+    /// presses, the analog input gives bots proportional control.
+    pub fn bot_actions(&self, input: &BotInput) -> Vec<ActionEvent> {
+        let (x, y) = input.movement;
+        let buttons = [
+            (
+                y > BOT_BUTTON_THRESHOLD,
+                self.action.up_pressed,
+                ActionEvent::UpPressed,
+                ActionEvent::UpReleased,
+            ),
+            (
+                x > BOT_BUTTON_THRESHOLD,
+                self.action.right_pressed,
+                ActionEvent::RightPressed,
+                ActionEvent::RightReleased,
+            ),
+            (
+                y < -BOT_BUTTON_THRESHOLD,
+                self.action.down_pressed,
+                ActionEvent::DownPressed,
+                ActionEvent::DownReleased,
+            ),
+            (
+                x < -BOT_BUTTON_THRESHOLD,
+                self.action.left_pressed,
+                ActionEvent::LeftPressed,
+                ActionEvent::LeftReleased,
+            ),
+            (
+                input.a,
+                self.action.a_pressed,
+                ActionEvent::APressed,
+                ActionEvent::AReleased,
+            ),
+            (
+                input.b,
+                self.action.b_pressed,
+                ActionEvent::BPressed,
+                ActionEvent::BReleased,
+            ),
+        ];
+
+        let mut actions = buttons
+            .iter()
+            .filter(|(pressed, was_pressed, ..)| pressed != was_pressed)
+            .map(|(pressed, _, press, release)| if *pressed { *press } else { *release })
+            .collect::<Vec<_>>();
+
+        let analog = AnalogAction::new(x, y, 0.0);
+        if self.action.analog != Some(analog) {
+            actions.push(ActionEvent::Analog(analog));
+        }
+
+        actions
+    }
+
+    /// Updates the current state of the buttons according to the `action_event`.
+    /// If the button state changed, the event is also stored so that it can be
+    /// read once with `previous_actions_once`.
     pub fn update_action(&mut self, action_event: &ActionEvent) {
+        let prev_action = self.action.clone();
         match action_event {
             ActionEvent::UpPressed => self.action.up_pressed = true,
             ActionEvent::UpReleased => self.action.up_pressed = false,
@@ -90,11 +198,21 @@ impl Player {
             ActionEvent::AReleased => self.action.a_pressed = false,
             ActionEvent::BPressed => self.action.b_pressed = true,
             ActionEvent::BReleased => self.action.b_pressed = false,
+            ActionEvent::Analog(analog) => self.action.analog = Some(*analog),
             ActionEvent::None => (),
         }
 
-        self.action.prev_action = *action_event;
-        self.action.new_action_since_last_read = true;
+        if !self.action.same_buttons(&prev_action) {
+            if self.action.new_actions.len() >= MAX_PREVIOUS_ACTIONS {
+                if let Some(dropped_action) = self.action.new_actions.pop_front() {
+                    eprintln!(
+                        "Too many unread actions for player with ID {}, dropped: {:?}",
+                        self.id, dropped_action
+                    );
+                }
+            }
+            self.action.new_actions.push_back(*action_event);
+        }
     }
 
     pub fn reset_action(&mut self) {
@@ -104,6 +222,8 @@ impl Player {
         self.action.left_pressed = false;
         self.action.a_pressed = false;
         self.action.b_pressed = false;
+        self.action.analog = None;
+        self.action.new_actions.clear();
     }
 
     pub fn has_no_action(&self) -> bool {
@@ -115,18 +235,55 @@ impl Player {
             && !self.action.b_pressed
     }
 
-    /// Returns the previous/latest action only once. When this function has
-    /// been called, it will return None until a new action have been set.
-    pub fn previous_action_once(&mut self) -> Option<ActionEvent> {
-        if self.action.new_action_since_last_read {
-            self.action.new_action_since_last_read = false;
-            Some(self.action.prev_action)
-        } else {
-            None
+    /// Returns all actions performed since the last call, in the order that they
+    /// were performed. Every action is only returned once.
+    pub fn previous_actions_once(&mut self) -> impl Iterator<Item = ActionEvent> {
+        std::mem::take(&mut self.action.new_actions).into_iter()
+    }
+
+    /// Returns true if there are actions that haven't been read with
+    /// `previous_actions_once`.
+    pub fn has_previous_actions(&self) -> bool {
+        !self.action.new_actions.is_empty()
+    }
+
+    /// Removes all actions that haven't been read with `previous_actions_once`.
+    /// Used when the game state changes so that actions performed in one state
+    /// aren't handled in the next state.
+    pub fn clear_previous_actions(&mut self) {
+        self.action.new_actions.clear();
+    }
+
+    /// Returns the movement vector of the player. If the player have sent analog
+    /// input that isn't centered, the analog vector is returned (with a length
+    /// of at most 1.0). Otherwise the vector is created from the pressed
+    /// buttons, so every component is either -1.0, 0.0 or 1.0.
+    pub fn movement_vec(&self) -> (f32, f32) {
+        match self.action.analog {
+            Some(analog) if analog.x != 0 || analog.y != 0 => analog.vec(),
+            _ => (self.digital_movement_x(), self.digital_movement_y()),
         }
     }
 
     pub fn movement_x(&self) -> f32 {
+        self.movement_vec().0
+    }
+
+    pub fn movement_y(&self) -> f32 {
+        self.movement_vec().1
+    }
+
+    /// Returns how much the trigger is pressed as a value between 0.0 & 1.0.
+    /// For clients without an analog trigger, this will be 1.0 if A is pressed.
+    pub fn trigger(&self) -> f32 {
+        match self.action.analog {
+            Some(analog) if analog.trigger != 0 => analog.trigger(),
+            _ if self.action.a_pressed => 1.0,
+            _ => 0.0,
+        }
+    }
+
+    fn digital_movement_x(&self) -> f32 {
         if self.action.right_pressed && self.action.left_pressed {
             0.0
         } else if self.action.right_pressed {
@@ -138,7 +295,7 @@ impl Player {
         }
     }
 
-    pub fn movement_y(&self) -> f32 {
+    fn digital_movement_y(&self) -> f32 {
         if self.action.up_pressed && self.action.down_pressed {
             0.0
         } else if self.action.up_pressed {
@@ -174,16 +331,62 @@ impl Player {
         self.color = color;
     }
 
-    pub fn score(&self) -> usize {
-        self.score
+    pub fn scores(&self) -> &ScoreBook {
+        &self.scores
+    }
+
+    /// Returns the points scored in the game with ID `game_id` this session.
+    pub fn game_score(&self, game_id: &str) -> usize {
+        self.scores.game(game_id)
+    }
+
+    /// Returns the points scored in all games this session.
+    pub fn total_score(&self) -> usize {
+        self.scores.total()
+    }
+
+    pub fn increment_score(&mut self, game_id: &'static str) {
+        self.scores.increment(game_id);
     }
 
-    pub fn increment_score(&mut self) {
-        self.score += 1;
+    pub fn reset_scores(&mut self) {
+        self.scores.clear();
     }
 
-    pub fn reset_score(&mut self) {
-        self.score = 0;
+    pub fn profile_name(&self) -> Option<&str> {
+        self.profile_name.as_deref()
+    }
+
+    pub fn set_profile_name(&mut self, profile_name: Option<String>) {
+        self.profile_name = profile_name;
+    }
+
+    pub fn stats(&self) -> &PlayerStats {
+        &self.stats
+    }
+
+    pub fn session_stats(&self) -> &PlayerStats {
+        &self.session_stats
+    }
+
+    pub fn set_stats(&mut self, stats: PlayerStats) {
+        self.stats = stats;
+    }
+
+    /// Returns the skill rating of the player, see `PlayerStats::rating`.
+    pub fn rating(&self) -> f32 {
+        self.stats.rating
+    }
+
+    pub fn set_rating(&mut self, rating: f32) {
+        self.stats.rating = rating;
+    }
+
+    /// Updates the statistics of the game with ID `game_id` by calling `f` with
+    /// both the all-time & the session statistics of the player.
+    pub fn record_stats<F: Fn(&mut GameStats)>(&mut self, game_id: &str, f: F) {
+        f(self.stats.game_mut(game_id));
+        f(self.session_stats.game_mut(game_id));
     }
 }
 
@@ -191,6 +394,14 @@ impl Player {
 #[derive(Debug, Default)]
 pub struct Players(HashMap<PlayerId, Player>);
 
+impl Players {
+    /// Returns the amount of players that aren't bots. Only these players are
+    /// allowed to take part in votes.
+    pub fn human_count(&self) -> usize {
+        self.0.values().filter(|player| !player.is_bot()).count()
+    }
+}
+
 impl Deref for Players {
     type Target = HashMap<PlayerId, Player>;
 
@@ -244,3 +455,44 @@ impl DerefMut for DisconnectedPlayers {
         &mut self.0
     }
 }
+
+/// Will be a resource in bevy which gets populated when players reconnect and
+/// resume their old session. The players will have been kept in `Players` while
+/// they were gone, so this is NOT a new player. It can be checked inside running
+/// games if they ex. need to re-send state to the clients.
+#[derive(Debug, Default)]
+pub struct ReconnectedPlayers(HashSet<PlayerId>);
+
+impl Deref for ReconnectedPlayers {
+    type Target = HashSet<PlayerId>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ReconnectedPlayers {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Will be a resource in bevy which gets populated when spectators connect.
+/// Spectators aren't players, so they are never added to `Players`. This can
+/// be used to send the current state to the newly connected spectators.
+#[derive(Debug, Default)]
+pub struct ConnectedSpectators(HashSet<PlayerId>);
+
+impl Deref for ConnectedSpectators {
+    type Target = HashSet<PlayerId>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for ConnectedSpectators {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
@@ -0,0 +1,33 @@
+use std::collections::BTreeMap;
+
+/// The points that a player have scored during the session, kept separately
+/// for every game so that points scored in one game doesn't show up in the
+/// scoreboard of another game.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreBook(BTreeMap<&'static str, usize>);
+
+impl ScoreBook {
+    /// Returns the points scored in the game with ID `game_id`.
+    pub fn game(&self, game_id: &str) -> usize {
+        self.0.get(game_id).cloned().unwrap_or(0)
+    }
+
+    pub fn increment(&mut self, game_id: &'static str) {
+        *self.0.entry(game_id).or_default() += 1;
+    }
+
+    /// Returns the points scored in all games during the session.
+    pub fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+
+    /// Returns the IDs of the games that the player have scored in together
+    /// with the points scored in them, sorted by the game ID.
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, usize)> + '_ {
+        self.0.iter().map(|(game_id, score)| (*game_id, *score))
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+}
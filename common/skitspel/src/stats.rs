@@ -0,0 +1,99 @@
+use std::{
+    collections::BTreeMap,
+    ops::{Deref, DerefMut},
+};
+
+use serde::{Deserialize, Serialize};
+
+/// Will be a resource in bevy that contains the all-time statistics of every
+/// player that have played on the server, keyed by the name that the player
+/// specified when connecting (see `Player::profile_name`). The statistics of the
+/// connected players are copied into it when they change.
+#[derive(Debug, Default)]
+pub struct Profiles(BTreeMap<String, PlayerStats>);
+
+impl Deref for Profiles {
+    type Target = BTreeMap<String, PlayerStats>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Profiles {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl From<BTreeMap<String, PlayerStats>> for Profiles {
+    fn from(profiles: BTreeMap<String, PlayerStats>) -> Self {
+        Self(profiles)
+    }
+}
+
+/// The skill rating that new players starts with.
+pub const DEFAULT_RATING: f32 = 1000.0;
+
+/// The all-time statistics of a player. The statistics are stored in the
+/// persistent profile of the player, so they are kept between sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PlayerStats {
+    /// The statistics of every game that the player have played, keyed by the
+    /// ID of the game (ex. "push").
+    pub games: BTreeMap<String, GameStats>,
+    /// The skill rating of the player, updated from the outcome of the matches
+    /// in the team games. Only used in the all-time statistics.
+    pub rating: f32,
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            games: BTreeMap::default(),
+            rating: DEFAULT_RATING,
+        }
+    }
+}
+
+/// The all-time statistics of a player in a specific game. Not all values are
+/// used by all games.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GameStats {
+    /// The amount of rounds won (i.e. the amount of points scored).
+    pub round_wins: usize,
+    pub matches_played: usize,
+    pub match_wins: usize,
+    /// The amount of goals scored by the team of the player (hockey & volleyball).
+    pub goals: usize,
+    /// The longest time that the player have survived in a round in seconds
+    /// (push & achtung).
+    pub longest_survival: f32,
+    /// The amount of times that the player have died (pong).
+    pub deaths: usize,
+}
+
+impl PlayerStats {
+    /// Returns the statistics of the game with ID `game_id`, the statistics
+    /// are created if this is the first time that the game is played.
+    pub fn game_mut(&mut self, game_id: &str) -> &mut GameStats {
+        self.games.entry(game_id.into()).or_default()
+    }
+
+    /// Returns the total amount of rounds won in all games.
+    pub fn round_wins(&self) -> usize {
+        self.games.values().map(|stats| stats.round_wins).sum()
+    }
+
+    /// Returns the total amount of matches played in all games.
+    pub fn matches_played(&self) -> usize {
+        self.games.values().map(|stats| stats.matches_played).sum()
+    }
+
+    /// Returns the total amount of matches won in all games.
+    pub fn match_wins(&self) -> usize {
+        self.games.values().map(|stats| stats.match_wins).sum()
+    }
+}
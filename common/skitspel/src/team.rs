@@ -0,0 +1,6 @@
+/// Used to tag which team a player belongs to in the team based games.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Team {
+    Left,
+    Right,
+}
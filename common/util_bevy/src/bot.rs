@@ -0,0 +1,23 @@
+use bevy::prelude::ResMut;
+
+use skitspel::{BotInput, PlayerId, Players};
+
+/// Applies the `input` of the bot with ID `player_id` by updating its action
+/// with the same `ActionEvent`s that a client would have sent.
+///
+/// The `Players` are only accessed mutably if the input changes anything. This
+/// prevents the bots from triggering change detection of `Players` every tick.
+pub fn apply_bot_input(players: &mut ResMut<Players>, player_id: PlayerId, input: &BotInput) {
+    let actions = match players.get(&player_id) {
+        Some(player) => player.bot_actions(input),
+        None => return,
+    };
+
+    if !actions.is_empty() {
+        if let Some(player) = players.get_mut(&player_id) {
+            for action in actions.iter() {
+                player.update_action(action);
+            }
+        }
+    }
+}
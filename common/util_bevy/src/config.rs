@@ -0,0 +1,108 @@
+use std::fmt::Display;
+
+/// Implemented by the config resources that can be specified in the config
+/// file of the server. Every config has its own section in the config file
+/// and any value that isn't specified in the file gets its default value.
+pub trait Config: Default + Send + Sync + 'static {
+    /// The name of the section in the config file (ex. `[pong]`).
+    const SECTION: &'static str;
+
+    /// Returns an error if any of the values in the config is invalid.
+    fn validate(&self) -> Result<(), ConfigError>;
+}
+
+/// Implemented by the configs of the games that uses a `StartTimer`.
+pub trait StartTimerConfig: Config {
+    /// How long the timer between rounds are in seconds.
+    fn start_timer_time(&self) -> usize;
+}
+
+/// A config that has been reloaded while the server is running but that hasn't
+/// been applied yet. The games applies the pending config when a round is
+/// reset, so the values never change in the middle of a round.
+pub struct PendingConfig<C: Config>(Option<C>);
+
+impl<C: Config> Default for PendingConfig<C> {
+    fn default() -> Self {
+        Self(None)
+    }
+}
+
+impl<C: Config> PendingConfig<C> {
+    /// Sets `config` as the pending config. Any previous pending config that
+    /// hasn't been applied yet will be replaced.
+    pub fn set(&mut self, config: C) {
+        self.0 = Some(config);
+    }
+
+    /// Removes and returns the pending config, if any.
+    pub fn take(&mut self) -> Option<C> {
+        self.0.take()
+    }
+
+    /// Replaces `config` with the pending config if there is one.
+    pub fn apply(&mut self, config: &mut C) {
+        if let Some(pending_config) = self.0.take() {
+            *config = pending_config;
+        }
+    }
+}
+
+/// Error describing an invalid value in a config.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub section: &'static str,
+    pub key: &'static str,
+    pub reason: String,
+}
+
+impl ConfigError {
+    pub fn new(section: &'static str, key: &'static str, reason: String) -> Self {
+        Self {
+            section,
+            key,
+            reason,
+        }
+    }
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Invalid value for `{}.{}`: {}",
+            self.section, self.key, self.reason
+        )
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Returns an error if the given `value` isn't a finite number greater than zero.
+pub fn ensure_positive(
+    section: &'static str,
+    key: &'static str,
+    value: f32,
+) -> Result<(), ConfigError> {
+    if value.is_finite() && value > 0.0 {
+        Ok(())
+    } else {
+        let reason = format!("must be greater than 0, got {}", value);
+        Err(ConfigError::new(section, key, reason))
+    }
+}
+
+/// Returns an error if the given `value` isn't a finite number greater than or
+/// equal to zero.
+pub fn ensure_non_negative(
+    section: &'static str,
+    key: &'static str,
+    value: f32,
+) -> Result<(), ConfigError> {
+    if value.is_finite() && value >= 0.0 {
+        Ok(())
+    } else {
+        let reason = format!("must be greater than or equal to 0, got {}", value);
+        Err(ConfigError::new(section, key, reason))
+    }
+}
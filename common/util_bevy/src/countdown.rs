@@ -0,0 +1,104 @@
+use std::ops::{Deref, DerefMut};
+
+use bevy::{
+    core::{Time, Timer},
+    ecs::component::Component,
+    prelude::{Color, Commands, Handle, HorizontalAlign, ResMut, Transform, VerticalAlign},
+    text::{Font, Text, Text2dBundle, TextAlignment, TextStyle},
+};
+
+use skitspel::{Player, Players, GAME_HEIGHT};
+
+/// Timer used by the screens that are shown a fixed amount of time before
+/// moving on automatically (ex. the tournament standings).
+pub struct Countdown(Timer);
+
+impl Deref for Countdown {
+    type Target = Timer;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for Countdown {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Tag used on the text showing the time remaining of the `Countdown`.
+pub struct CountdownText;
+
+/// Spawns a `Countdown` running for `secs` seconds together with the text
+/// showing it at the bottom of the screen.
+///
+/// The `T` type is used to tag the entities so that they are removed when
+/// running the `despawn_system::<T>()` system.
+pub fn spawn_countdown<T: Component + Default>(
+    commands: &mut Commands,
+    secs: usize,
+    font: Handle<Font>,
+) {
+    commands
+        .spawn()
+        .insert(Countdown(Timer::from_seconds(secs as f32, false)))
+        .insert(T::default());
+
+    // The text is set when the countdown is ticked (see `tick_countdown`).
+    let countdown_text_bundle = Text2dBundle {
+        text: Text::with_section(
+            "",
+            TextStyle {
+                font,
+                font_size: 24.0,
+                color: Color::WHITE,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform::from_xyz(0.0, -GAME_HEIGHT * 0.35, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(countdown_text_bundle)
+        .insert(CountdownText)
+        .insert(T::default());
+}
+
+/// Ticks the given `countdown` and updates the `countdown_text` with the
+/// remaining seconds, prefixed with `prefix` (ex. "Starting in"). Returns true
+/// if the countdown finished during this tick.
+pub fn tick_countdown(
+    time: &Time,
+    countdown: &mut Countdown,
+    countdown_text: &mut Text,
+    prefix: &str,
+) -> bool {
+    countdown.tick(time.delta());
+
+    let secs_remaining = (countdown.duration() - countdown.elapsed())
+        .as_secs_f32()
+        .ceil();
+    if let Some(text_section) = countdown_text.sections.first_mut() {
+        text_section.value = format!("{} {}", prefix, secs_remaining);
+    }
+
+    countdown.just_finished()
+}
+
+/// System used by the screens with a `Countdown` that doesn't handle any input
+/// from the players. The actions of the players are read & ignored so that
+/// they aren't handled by the next state.
+pub fn ignore_player_input(mut players: ResMut<Players>) {
+    // Accessing the players mutably triggers a change event, so only do it if
+    // there are any actions to ignore.
+    if players.values().any(Player::has_previous_actions) {
+        for player in players.values_mut() {
+            player.previous_actions_once().for_each(drop);
+        }
+    }
+}
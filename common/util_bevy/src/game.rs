@@ -1,9 +1,14 @@
 use std::ops::{Deref, DerefMut};
 
 use bevy::{prelude::Handle, sprite::ColorMaterial};
+use serde::Deserialize;
 use skitspel::GameState;
 
-/// Resource that stores the games that are playable.
+use crate::{Config, ConfigError};
+
+/// Resource that stores the games that are playable. The games add themselves
+/// to this registry when their plugins are built (see `MiniGamePlugin`), so
+/// they are stored in the same order that the plugins were added.
 #[derive(Debug, Default)]
 pub struct Games(pub Vec<Game>);
 
@@ -21,10 +26,56 @@ impl DerefMut for Games {
     }
 }
 
+impl Games {
+    /// Returns the registered game with the ID `id`.
+    pub fn get_by_id(&self, id: &str) -> Option<&Game> {
+        self.iter().find(|game| game.id == id)
+    }
+}
+
 /// Represents a playable game.
 #[derive(Debug)]
 pub struct Game {
+    /// Unique ID of the game, used to refer to the game in ex. the config file.
+    pub id: &'static str,
     pub name: &'static str,
+    pub description: &'static str,
     pub game_state: GameState,
     pub screenshot: Handle<ColorMaterial>,
+    /// The minimum & maximum amount of players (inclusive) that the game
+    /// supports.
+    pub min_players: usize,
+    pub max_players: usize,
+}
+
+impl Game {
+    /// Returns true if the game can be played by `player_count` players.
+    pub fn supports_player_count(&self, player_count: usize) -> bool {
+        (self.min_players..=self.max_players).contains(&player_count)
+    }
+}
+
+/// Config containing which of the registered games that can be selected in
+/// the game selection menu.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GamesConfig {
+    /// The IDs of the games that should be disabled (ex. `["pong"]`).
+    pub disabled: Vec<String>,
+}
+
+impl GamesConfig {
+    pub fn is_enabled(&self, game: &Game) -> bool {
+        !self.disabled.iter().any(|id| id == game.id)
+    }
+}
+
+impl Config for GamesConfig {
+    const SECTION: &'static str = "games";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        // The registered games aren't known when the config is loaded, unknown
+        // IDs are reported when the config is inserted instead.
+        Ok(())
+    }
 }
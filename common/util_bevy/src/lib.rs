@@ -1,17 +1,39 @@
+pub use bot::apply_bot_input;
+pub use config::{
+    ensure_non_negative, ensure_positive, Config, ConfigError, PendingConfig, StartTimerConfig,
+};
+pub use countdown::{
+    ignore_player_input, spawn_countdown, tick_countdown, Countdown, CountdownText,
+};
 pub use despawn::{despawn_entity, despawn_system};
 pub use fonts::Fonts;
-pub use game::{Game, Games};
+pub use game::{Game, Games, GamesConfig};
+pub use match_rules::{EndMatchEvent, MatchConfig, MatchResult, MatchRules, MatchWins};
+pub use mini_game::{ActionPressedEvent, MiniGame, MiniGamePlugin, Scoring};
+pub use scoreboard::ScoreCount;
 
 pub use shape::Shape;
 pub use start::{handle_start_timer, setup_start_timer, StartEntity, StartTimer};
+pub use team_selection::{ReshuffleTeamsEvent, SwitchTeamEvent, TeamSelection};
+pub use teams::{balanced_teams, reshuffled_teams, team_for_new_player};
 pub use vote::create_vote_text_sections;
 pub use vote::{PlayerVote, VoteEvent};
 
+mod bot;
+mod config;
+mod countdown;
 mod despawn;
 mod fonts;
 mod game;
+mod match_rules;
+mod mini_game;
+mod rating;
+mod scoreboard;
 mod shape;
 mod start;
+mod survival;
+mod team_selection;
+mod teams;
 mod vote;
 
 pub trait AsBevyColor {
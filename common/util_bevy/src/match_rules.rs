@@ -0,0 +1,303 @@
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+};
+
+use bevy::{
+    core::{Time, Timer},
+    prelude::{Commands, EventReader, Query, Res, ResMut, State, With},
+};
+use serde::Deserialize;
+
+use skitspel::{GameState, PlayerId, Players, Team};
+
+use crate::{
+    rating::update_ratings, Config, ConfigError, MiniGame, ScoreCount, Scoring, StartTimer,
+    TeamSelection,
+};
+
+/// The rules deciding when a match of a game is over. Read from the `match`
+/// table in the section of the game in the config file (ex. `[push.match]`).
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MatchRules {
+    /// The match is won by the first player (or team) that reaches this
+    /// amount of points.
+    pub points: Option<usize>,
+    /// The length of the match in seconds. When the time is up, the player (or
+    /// team) with the most points wins. If the score is tied, the match goes on
+    /// until someone takes the lead.
+    pub time_limit: Option<usize>,
+    /// The amount of points that the winner must lead with to win on `points`
+    /// (ex. 2 for "win by two").
+    pub win_by: usize,
+}
+
+impl MatchRules {
+    /// Rules where the first player (or team) to reach `points` wins.
+    pub fn first_to(points: usize) -> Self {
+        Self {
+            points: Some(points),
+            ..Default::default()
+        }
+    }
+
+    /// Validates the rules found in the section `section` of the config file.
+    pub fn validate(&self, section: &'static str) -> Result<(), ConfigError> {
+        let reason = "must be greater than 0, got 0".to_string();
+        if self.points == Some(0) {
+            Err(ConfigError::new(section, "match.points", reason))
+        } else if self.time_limit == Some(0) {
+            Err(ConfigError::new(section, "match.time_limit", reason))
+        } else if self.win_by == 0 {
+            Err(ConfigError::new(section, "match.win_by", reason))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Returns true if the match is over when the leading player (or team) have
+    /// `leader` points & the runner-up have `runner_up` points.
+    pub fn is_over(&self, leader: usize, runner_up: usize, time_is_up: bool) -> bool {
+        let lead = leader.saturating_sub(runner_up);
+        let points_reached = matches!(self.points, Some(points) if leader >= points);
+        (points_reached && lead >= self.win_by) || (time_is_up && lead > 0)
+    }
+}
+
+/// The default rules never ends the match, every game specifies its own.
+impl Default for MatchRules {
+    fn default() -> Self {
+        Self {
+            points: None,
+            time_limit: None,
+            win_by: 1,
+        }
+    }
+}
+
+/// Implemented by the configs of the games, see `MiniGame::Config`.
+pub trait MatchConfig: Config {
+    fn match_rules(&self) -> &MatchRules;
+}
+
+/// Resource containing the amount of matches won by every player. The match
+/// wins are kept separately from the points that the players gets during the
+/// rounds of a match (see `Player::game_score`).
+#[derive(Debug, Default)]
+pub struct MatchWins(HashMap<PlayerId, usize>);
+
+impl Deref for MatchWins {
+    type Target = HashMap<PlayerId, usize>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for MatchWins {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl MatchWins {
+    pub fn wins(&self, player_id: &PlayerId) -> usize {
+        self.get(player_id).cloned().unwrap_or(0)
+    }
+}
+
+/// Resource containing the result of the last match that was played.
+#[derive(Debug, Clone)]
+pub struct MatchResult {
+    pub game_name: &'static str,
+    pub game_state: GameState,
+    /// The players that won the match. Empty if the match ended in a draw.
+    pub winners: Vec<PlayerId>,
+    /// The winning team and the final score of the left & right team. Only set
+    /// for games using `Scoring::Team`.
+    pub winning_team: Option<Team>,
+    pub team_score: Option<(usize, usize)>,
+    /// The points that the players got during the match, sorted with the
+    /// highest amount of points first.
+    pub scores: Vec<(PlayerId, usize)>,
+}
+
+/// Event that can be sent to end the running match immediately, the leading
+/// player (or team) wins. Used ex. when the target of a tournament game have
+/// been reached.
+pub struct EndMatchEvent;
+
+/// Component keeping track of the running match.
+pub(crate) struct Match {
+    /// The scores of the players in the game when the match started. The game
+    /// scores are accumulated across all matches of the game played during the
+    /// session, so these are used to calculate the points scored in this match.
+    start_scores: HashMap<PlayerId, usize>,
+    time_limit_timer: Option<Timer>,
+}
+
+impl Match {
+    /// Returns the points that the players have got during the match of the
+    /// game with ID `game_id`, sorted with the highest amount of points first.
+    fn scores(&self, players: &Players, game_id: &str) -> Vec<(PlayerId, usize)> {
+        let mut scores = players
+            .values()
+            .map(|player| {
+                let start_score = self.start_scores.get(&player.id()).cloned().unwrap_or(0);
+                let score = player.game_score(game_id);
+                (player.id(), score.saturating_sub(start_score))
+            })
+            .collect::<Vec<_>>();
+        scores.sort_unstable_by_key(|(player_id, score)| (Reverse(*score), *player_id));
+        scores
+    }
+}
+
+pub(crate) fn setup_match<G: MiniGame>(
+    mut commands: Commands,
+    config: Res<G::Config>,
+    players: Res<Players>,
+) {
+    let start_scores = players
+        .values()
+        .map(|player| (player.id(), player.game_score(G::ID)))
+        .collect();
+    let time_limit_timer = config
+        .match_rules()
+        .time_limit
+        .map(|time_limit| Timer::from_seconds(time_limit as f32, false));
+
+    commands
+        .spawn()
+        .insert(Match {
+            start_scores,
+            time_limit_timer,
+        })
+        .insert(G::default());
+}
+
+/// Ends the match if the `MatchRules` of the game says that it is over or if
+/// an `EndMatchEvent` have been received. The result of the match is stored
+/// in the `MatchResult` and the match results are shown.
+///
+/// The time limit of the match only runs while the rounds are played, not
+/// during the `StartTimer` before every round. Nothing is checked during the
+/// team selection of team games (see `TeamSelection`).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn check_match_end<G: MiniGame>(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<G::Config>,
+    mut players: ResMut<Players>,
+    mut match_wins: ResMut<MatchWins>,
+    mut game_state: ResMut<State<GameState>>,
+    mut match_query: Query<&mut Match, With<G>>,
+    score_count_query: Query<&ScoreCount, With<G>>,
+    start_timer_query: Query<&StartTimer, With<G>>,
+    team_selection_query: Query<&TeamSelection>,
+    players_playing: Query<(&PlayerId, &Team)>,
+    mut end_match_event_reader: EventReader<EndMatchEvent>,
+) {
+    if team_selection_query.iter().next().is_some() {
+        return;
+    }
+
+    let mut game_match = match_query.single_mut().unwrap();
+    let round_is_running = start_timer_query
+        .single()
+        .map_or(true, |start_timer| start_timer.finished());
+    if let Some(timer) = &mut game_match.time_limit_timer {
+        if round_is_running {
+            timer.tick(time.delta());
+        }
+    }
+    let time_is_up = game_match
+        .time_limit_timer
+        .as_ref()
+        .map_or(false, Timer::finished);
+
+    let scores = game_match.scores(&players, G::ID);
+    let team_score = if let Scoring::Team = G::SCORING {
+        score_count_query
+            .single()
+            .ok()
+            .map(|ScoreCount(left_score, right_score)| (*left_score, *right_score))
+    } else {
+        None
+    };
+
+    let (leader, runner_up) = if let Some((left_score, right_score)) = team_score {
+        (left_score.max(right_score), left_score.min(right_score))
+    } else {
+        let score_at = |idx: usize| scores.get(idx).map_or(0, |(_, score)| *score);
+        (score_at(0), score_at(1))
+    };
+
+    let end_requested = end_match_event_reader.iter().count() > 0;
+    if !end_requested && !config.match_rules().is_over(leader, runner_up, time_is_up) {
+        return;
+    }
+
+    // A majority of the players might have voted to exit the game during the
+    // same frame, the exit have precedence over the end of the match.
+    if game_state.set(GameState::MatchResults).is_err() {
+        return;
+    }
+
+    let winning_team = match team_score {
+        Some((left_score, right_score)) if left_score > right_score => Some(Team::Left),
+        Some((left_score, right_score)) if right_score > left_score => Some(Team::Right),
+        _ => None,
+    };
+
+    let winners = if team_score.is_some() {
+        players_playing
+            .iter()
+            .filter(|(_, team)| Some(**team) == winning_team)
+            .map(|(player_id, _)| *player_id)
+            .collect::<Vec<_>>()
+    } else if leader > runner_up {
+        scores
+            .first()
+            .map(|(player_id, _)| *player_id)
+            .into_iter()
+            .collect::<Vec<_>>()
+    } else {
+        Vec::default()
+    };
+
+    for player_id in &winners {
+        *match_wins.entry(*player_id).or_default() += 1;
+    }
+
+    for (player_id, _) in &scores {
+        if let Some(player) = players.get_mut(player_id) {
+            let is_winner = winners.contains(player_id);
+            player.record_stats(G::ID, |stats| {
+                stats.matches_played += 1;
+                if is_winner {
+                    stats.match_wins += 1;
+                }
+            });
+        }
+    }
+
+    if team_score.is_some() {
+        let teams = players_playing
+            .iter()
+            .map(|(player_id, team)| (*player_id, *team))
+            .collect::<Vec<_>>();
+        update_ratings(&mut players, &teams, winning_team);
+    }
+
+    commands.insert_resource(MatchResult {
+        game_name: G::NAME,
+        game_state: G::GAME_STATE,
+        winners,
+        winning_team,
+        team_score,
+        scores,
+    });
+}
@@ -0,0 +1,393 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    asset::Assets,
+    ecs::component::Component,
+    prelude::{
+        AppBuilder, Color, Commands, Entity, EventReader, EventWriter, HorizontalAlign, IntoSystem,
+        Local, ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, State, SystemSet,
+        Transform, VerticalAlign, With, World,
+    },
+    render::texture::{ImageType, Texture},
+    sprite::ColorMaterial,
+    text::{Text, Text2dBundle, TextAlignment, TextStyle},
+};
+
+use skitspel::{
+    ActionEvent, DisconnectedPlayers, GameState, PlayerId, Players, Team, GAME_HEIGHT, MAX_PLAYERS,
+};
+
+use crate::{
+    create_vote_text_sections, despawn_entity, despawn_system,
+    match_rules::{check_match_end, setup_match},
+    scoreboard::{
+        handle_winner, send_team, setup_team_score, update_scoreboard, update_team_scoreboard,
+    },
+    survival::track_survival,
+    team_selection::{
+        handle_team_selection, setup_team_selection, ReshuffleTeamsEvent, SwitchTeamEvent,
+        TeamSelectionInput, TeamSelectionInputEvent,
+    },
+    Fonts, Game, Games, MatchConfig, MatchWins, PlayerVote, VoteEvent,
+};
+
+const EXIT_TEXT: &str = "Press B to go back to main menu";
+
+/// Tag used on the exit text.
+struct ExitText;
+
+/// How the score is counted and displayed in a mini-game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    /// The last player alive gets a point. The players are despawned when they
+    /// die and the point is given when only one player entity is left.
+    LastSurvivor,
+    /// Every player has its own score that is updated by the game itself.
+    Individual,
+    /// The players are divided into two teams. The game updates the score of
+    /// the teams in the `ScoreCount` component and the score of the players
+    /// in the team that scored.
+    Team,
+}
+
+/// Implemented by the tag component of a mini-game (ex. `PushGamePlugin`).
+///
+/// The `MiniGamePlugin` uses the information in this trait to add the systems
+/// that are common for all mini-games: voting to exit, handling disconnected
+/// players, the texts on the screen, the scoreboard, recording the statistics
+/// of the players & despawning everything tagged with the component when the
+/// game exits.
+///
+/// The game itself only needs to spawn its map & players and decide when
+/// players should get points. The match is ended according to the
+/// `MatchRules` found in the config of the game.
+pub trait MiniGame: Component + Default {
+    /// The config of the game, contains the `MatchRules` of the game.
+    type Config: MatchConfig;
+
+    /// Unique ID of the game. This is used to refer to the game in the config
+    /// file, so it should be short & lowercase (ex. "push").
+    const ID: &'static str;
+
+    /// The name of the game shown in the game selection menu.
+    const NAME: &'static str;
+
+    /// Short description of the game shown in the game selection menu.
+    const DESCRIPTION: &'static str;
+
+    /// A screenshot of the game in png format shown in the game selection menu.
+    const SCREENSHOT: &'static [u8];
+
+    /// The minimum & maximum amount of players that can play the game. The
+    /// game can't be selected in the game selection menu if the current amount
+    /// of players is outside of this range.
+    const MIN_PLAYERS: usize = 2;
+    const MAX_PLAYERS: usize = MAX_PLAYERS;
+
+    /// The state that the game is played in.
+    const GAME_STATE: GameState = GameState::Game(Self::ID);
+
+    const SCORING: Scoring;
+
+    /// Text explaining what the A button does (ex. "Press A to spin").
+    const ACTION_TEXT: Option<&'static str> = None;
+
+    /// The y-coordinate of the action text. The exit text is shown under it.
+    const TEXT_Y: f32 = GAME_HEIGHT / 4.0;
+
+    /// The distance between the top of the screen and the scoreboard. Only used
+    /// for the `LastSurvivor` & `Individual` scorings.
+    const SCOREBOARD_MARGIN: f32 = 15.0;
+}
+
+/// Event sent when a player presses the A button while the mini-game `G` is
+/// running.
+pub struct ActionPressedEvent<G> {
+    pub player_id: PlayerId,
+    marker: PhantomData<G>,
+}
+
+impl<G> ActionPressedEvent<G> {
+    fn new(player_id: PlayerId) -> Self {
+        Self {
+            player_id,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Plugin adding the systems that are common for all mini-games. See `MiniGame`.
+///
+/// The shared systems that handles player input & disconnects are labeled
+/// with "vote", so any system of the game that depends on them should run
+/// `.after("vote")`. The system that handles the exit vote is labeled with
+/// "exit". For games using `Scoring::Team`, the system that handles the team
+/// selection is labeled with "team_selection".
+pub struct MiniGamePlugin<G>(PhantomData<G>);
+
+impl<G> Default for MiniGamePlugin<G> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<G: MiniGame> Plugin for MiniGamePlugin<G> {
+    fn build(&self, app: &mut AppBuilder) {
+        register_game::<G>(app.world_mut());
+
+        app.init_resource::<MatchWins>()
+            .add_event::<ActionPressedEvent<G>>()
+            .add_event::<TeamSelectionInputEvent<G>>()
+            .add_system_set(
+                SystemSet::on_enter(G::GAME_STATE)
+                    .with_system(reset_votes.system())
+                    .with_system(setup_screen_text::<G>.system())
+                    .with_system(setup_match::<G>.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(G::GAME_STATE)
+                    .with_system(handle_disconnect.system().label("vote"))
+                    .with_system(handle_player_input::<G>.system().label("vote"))
+                    .with_system(handle_exit_event.system().label("exit").after("vote"))
+                    .with_system(check_match_end::<G>.system().after("exit")),
+            )
+            .add_system_set(
+                SystemSet::on_exit(G::GAME_STATE).with_system(despawn_system::<G>.system()),
+            );
+
+        match G::SCORING {
+            Scoring::LastSurvivor => {
+                app.add_system_set(
+                    SystemSet::on_update(G::GAME_STATE)
+                        .with_system(handle_winner::<G>.system().label("winner"))
+                        .with_system(update_scoreboard::<G>.system())
+                        .with_system(track_survival::<G>.system()),
+                );
+            }
+            Scoring::Individual => {
+                app.add_system_set(
+                    SystemSet::on_update(G::GAME_STATE)
+                        .with_system(update_scoreboard::<G>.system()),
+                );
+            }
+            Scoring::Team => {
+                app.add_event::<SwitchTeamEvent<G>>()
+                    .add_event::<ReshuffleTeamsEvent<G>>()
+                    .add_system_set(
+                        SystemSet::on_enter(G::GAME_STATE)
+                            .with_system(setup_team_score::<G>.system())
+                            .with_system(setup_team_selection::<G>.system()),
+                    )
+                    .add_system_set(
+                        SystemSet::on_update(G::GAME_STATE)
+                            .with_system(
+                                handle_team_selection::<G>
+                                    .system()
+                                    .label("team_selection")
+                                    .after("vote"),
+                            )
+                            .with_system(send_team.system())
+                            .with_system(update_team_scoreboard::<G>.system()),
+                    );
+            }
+        }
+    }
+}
+
+/// Adds the game `G` to the `Games` so that it can be selected in the game
+/// selection menu. The games are shown in the order that they are registered.
+fn register_game<G: MiniGame>(world: &mut World) {
+    let games = world.get_resource_or_insert_with(Games::default);
+    if games.get_by_id(G::ID).is_some() {
+        panic!("Game with ID \"{}\" registered multiple times.", G::ID);
+    }
+
+    let texture = Texture::from_buffer(G::SCREENSHOT, ImageType::Extension("png")).unwrap();
+    let texture = world
+        .get_resource_mut::<Assets<Texture>>()
+        .unwrap()
+        .add(texture);
+    let screenshot = world
+        .get_resource_mut::<Assets<ColorMaterial>>()
+        .unwrap()
+        .add(texture.into());
+
+    world.get_resource_mut::<Games>().unwrap().push(Game {
+        id: G::ID,
+        name: G::NAME,
+        description: G::DESCRIPTION,
+        game_state: G::GAME_STATE,
+        screenshot,
+        min_players: G::MIN_PLAYERS,
+        max_players: G::MAX_PLAYERS,
+    });
+}
+
+fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
+    exit_event_writer.send(VoteEvent::Reset);
+}
+
+fn handle_disconnect(
+    mut commands: Commands,
+    disconnected_players: Res<DisconnectedPlayers>,
+    players_playing: Query<(Entity, &PlayerId)>,
+    mut exit_event_writer: EventWriter<VoteEvent>,
+) {
+    if !disconnected_players.is_empty() {
+        for (entity, player_id) in players_playing.iter() {
+            if disconnected_players.contains(player_id) {
+                exit_event_writer.send(VoteEvent::Value(*player_id, false));
+                despawn_entity(&mut commands, entity);
+            }
+        }
+    }
+}
+
+/// Handles the button presses of the players. Pressing A sends a
+/// `ActionPressedEvent` and pressing B flips the exit vote of the player. In
+/// team games, pressing left/right/down sends a `TeamSelectionInputEvent` that
+/// is used during the team selection.
+fn handle_player_input<G: MiniGame>(
+    mut players: ResMut<Players>,
+    mut action_event_writer: EventWriter<ActionPressedEvent<G>>,
+    mut team_selection_event_writer: EventWriter<TeamSelectionInputEvent<G>>,
+    mut exit_event_writer: EventWriter<VoteEvent>,
+) {
+    let is_team_game = G::SCORING == Scoring::Team;
+
+    if players.is_changed() {
+        for player in players.values_mut() {
+            for prev_action in player.previous_actions_once() {
+                match prev_action {
+                    ActionEvent::APressed => {
+                        action_event_writer.send(ActionPressedEvent::new(player.id()));
+                    }
+
+                    ActionEvent::BPressed => {
+                        exit_event_writer.send(VoteEvent::Flip(player.id()));
+                    }
+
+                    ActionEvent::LeftPressed
+                    | ActionEvent::RightPressed
+                    | ActionEvent::DownPressed
+                        if is_team_game =>
+                    {
+                        let input = match prev_action {
+                            ActionEvent::LeftPressed => TeamSelectionInput::Pick(Team::Left),
+                            ActionEvent::RightPressed => TeamSelectionInput::Pick(Team::Right),
+                            _ => TeamSelectionInput::Reshuffle,
+                        };
+                        team_selection_event_writer
+                            .send(TeamSelectionInputEvent::new(player.id(), input));
+                    }
+
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// If a majority of the players wants to exit, we should return back to the menu.
+fn handle_exit_event(
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+    mut game_state: ResMut<State<GameState>>,
+    mut exit_text: Query<&mut Text, With<ExitText>>,
+    mut player_exit_vote: Local<PlayerVote>,
+    mut exit_event_reader: EventReader<VoteEvent>,
+) {
+    let voted_amount_before = player_exit_vote.voted_amount();
+    let total_amount_before = player_exit_vote.total_amount();
+
+    exit_event_reader
+        .iter()
+        .for_each(|vote| player_exit_vote.register_vote(vote));
+
+    let voted_amount_after = player_exit_vote.len();
+    let total_amount_after = players.human_count();
+
+    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
+        player_exit_vote.set_total_amount(total_amount_after);
+
+        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
+        if voted_amount_after >= required_amount {
+            game_state.set(GameState::StartMenu).unwrap();
+        } else {
+            let font = fonts.regular.clone();
+            let font_size = 24.0;
+            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
+                EXIT_TEXT.into(),
+                &players,
+                &player_exit_vote,
+                required_amount,
+                font,
+                font_size,
+            );
+        }
+    }
+}
+
+fn setup_screen_text<G: MiniGame>(
+    mut commands: Commands,
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+) {
+    let font = fonts.regular.clone();
+    let font_size = 24.0;
+    let font_color = Color::WHITE;
+
+    let empty_player_vote = PlayerVote::default();
+    let required_amount = (players.human_count() / 2) + 1;
+
+    if let Some(action_text) = G::ACTION_TEXT {
+        let action_text = Text::with_section(
+            format!("{}\n", action_text),
+            TextStyle {
+                font: font.clone(),
+                font_size,
+                color: font_color,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        );
+
+        let action_text_bundle = Text2dBundle {
+            text: action_text,
+            transform: Transform::from_xyz(0.0, G::TEXT_Y, 0.0),
+            ..Default::default()
+        };
+
+        commands
+            .spawn_bundle(action_text_bundle)
+            .insert(G::default());
+    }
+
+    let exit_text = Text {
+        sections: create_vote_text_sections(
+            EXIT_TEXT.into(),
+            &players,
+            &empty_player_vote,
+            required_amount,
+            font,
+            font_size,
+        ),
+        alignment: TextAlignment {
+            vertical: VerticalAlign::Bottom,
+            horizontal: HorizontalAlign::Center,
+        },
+    };
+
+    let exit_text_bundle = Text2dBundle {
+        text: exit_text,
+        transform: Transform::from_xyz(0.0, G::TEXT_Y - font_size, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(exit_text_bundle)
+        .insert(ExitText)
+        .insert(G::default());
+}
@@ -0,0 +1,156 @@
+use skitspel::{PlayerId, Players, Team};
+
+/// The maximum change of the rating of a player after a match. A player in a
+/// team that wins against a team with the same rating gains half of this.
+const RATING_K: f32 = 32.0;
+
+/// Updates the skill ratings of the players after a match between the two
+/// teams in `teams`. The rating of a team is the average rating of its players
+/// and every player in a team gets the same rating change (Elo).
+pub(crate) fn update_ratings(
+    players: &mut Players,
+    teams: &[(PlayerId, Team)],
+    winning_team: Option<Team>,
+) {
+    let (left_rating, right_rating) = match (
+        average_rating(players, teams, Team::Left),
+        average_rating(players, teams, Team::Right),
+    ) {
+        (Some(left_rating), Some(right_rating)) => (left_rating, right_rating),
+        _ => return,
+    };
+
+    let expected_left = 1.0 / (1.0 + 10f32.powf((right_rating - left_rating) / 400.0));
+    let actual_left = match winning_team {
+        Some(Team::Left) => 1.0,
+        Some(Team::Right) => 0.0,
+        None => 0.5,
+    };
+    let left_change = RATING_K * (actual_left - expected_left);
+
+    for (player_id, team) in teams {
+        if let Some(player) = players.get_mut(player_id) {
+            let change = match team {
+                Team::Left => left_change,
+                Team::Right => -left_change,
+            };
+            player.set_rating(player.rating() + change);
+        }
+    }
+}
+
+/// Returns the average rating of the players in team `team`, or None if the
+/// team has no players.
+pub(crate) fn average_rating(
+    players: &Players,
+    teams: &[(PlayerId, Team)],
+    team: Team,
+) -> Option<f32> {
+    let ratings = teams
+        .iter()
+        .filter(|(_, player_team)| *player_team == team)
+        .filter_map(|(player_id, _)| players.get(player_id))
+        .map(|player| player.rating())
+        .collect::<Vec<_>>();
+    if ratings.is_empty() {
+        None
+    } else {
+        Some(ratings.iter().sum::<f32>() / ratings.len() as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use skitspel::{Color, Player, DEFAULT_RATING};
+
+    use super::*;
+
+    fn players_with_ratings(ratings: &[f32]) -> (Players, Vec<PlayerId>) {
+        let mut players = Players::default();
+        let mut player_ids = Vec::default();
+        for (idx, rating) in ratings.iter().enumerate() {
+            let player_id = PlayerId::from(idx as u64 + 1);
+            let mut player = Player::new(player_id, idx.to_string(), Color::new(0.0, 0.0, 0.0));
+            player.set_rating(*rating);
+            players.insert(player_id, player);
+            player_ids.push(player_id);
+        }
+        (players, player_ids)
+    }
+
+    fn rating(players: &Players, player_id: PlayerId) -> f32 {
+        players.get(&player_id).unwrap().rating()
+    }
+
+    fn assert_close(a: f32, b: f32) {
+        assert!((a - b).abs() < 0.001, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn average_rating_of_team() {
+        let (players, ids) = players_with_ratings(&[1000.0, 1200.0, 900.0]);
+        let teams = [
+            (ids[0], Team::Left),
+            (ids[1], Team::Left),
+            (ids[2], Team::Right),
+        ];
+        assert_eq!(average_rating(&players, &teams, Team::Left), Some(1100.0));
+        assert_eq!(average_rating(&players, &teams, Team::Right), Some(900.0));
+    }
+
+    #[test]
+    fn average_rating_of_empty_team() {
+        let (players, ids) = players_with_ratings(&[DEFAULT_RATING]);
+        let teams = [(ids[0], Team::Left)];
+        assert_eq!(average_rating(&players, &teams, Team::Right), None);
+    }
+
+    #[test]
+    fn even_teams_gains_half_of_k() {
+        let (mut players, ids) = players_with_ratings(&[DEFAULT_RATING; 4]);
+        let teams = [
+            (ids[0], Team::Left),
+            (ids[1], Team::Left),
+            (ids[2], Team::Right),
+            (ids[3], Team::Right),
+        ];
+        update_ratings(&mut players, &teams, Some(Team::Left));
+
+        assert_close(rating(&players, ids[0]), DEFAULT_RATING + RATING_K / 2.0);
+        assert_close(rating(&players, ids[1]), DEFAULT_RATING + RATING_K / 2.0);
+        assert_close(rating(&players, ids[2]), DEFAULT_RATING - RATING_K / 2.0);
+        assert_close(rating(&players, ids[3]), DEFAULT_RATING - RATING_K / 2.0);
+    }
+
+    #[test]
+    fn draw_between_even_teams_keeps_ratings() {
+        let (mut players, ids) = players_with_ratings(&[DEFAULT_RATING; 2]);
+        let teams = [(ids[0], Team::Left), (ids[1], Team::Right)];
+        update_ratings(&mut players, &teams, None);
+
+        assert_close(rating(&players, ids[0]), DEFAULT_RATING);
+        assert_close(rating(&players, ids[1]), DEFAULT_RATING);
+    }
+
+    #[test]
+    fn upset_gives_larger_change() {
+        // A team rated 400 points lower is expected to win 1/11 of the time.
+        let (mut players, ids) = players_with_ratings(&[1000.0, 1400.0]);
+        let teams = [(ids[0], Team::Left), (ids[1], Team::Right)];
+        update_ratings(&mut players, &teams, Some(Team::Left));
+
+        let change = RATING_K * (1.0 - 1.0 / 11.0);
+        assert_close(rating(&players, ids[0]), 1000.0 + change);
+        assert_close(rating(&players, ids[1]), 1400.0 - change);
+    }
+
+    #[test]
+    fn one_sided_match_keeps_ratings() {
+        let (mut players, ids) = players_with_ratings(&[DEFAULT_RATING; 2]);
+        let teams = [(ids[0], Team::Left), (ids[1], Team::Left)];
+        update_ratings(&mut players, &teams, Some(Team::Left));
+
+        assert_close(rating(&players, ids[0]), DEFAULT_RATING);
+        assert_close(rating(&players, ids[1]), DEFAULT_RATING);
+    }
+}
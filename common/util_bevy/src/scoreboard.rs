@@ -0,0 +1,305 @@
+use std::cmp::Reverse;
+
+use bevy::{
+    prelude::{
+        Added, ChangeTrackers, Changed, Color, Commands, Entity, EventWriter, Handle,
+        HorizontalAlign, Local, Query, QuerySet, Res, ResMut, Transform, VerticalAlign, With,
+    },
+    text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
+};
+
+use skitspel::{
+    PlayerId, Players, ReconnectedPlayers, ServerMessage, ServerMessageEvent, Team, GAME_HEIGHT,
+    GAME_WIDTH,
+};
+
+use crate::{despawn_entity, AsBevyColor, Fonts, MiniGame};
+
+/// Component used to keep track of the current score of games using the
+/// `Scoring::Team`. The left usize is the score of the left team and the right
+/// usize is the score of the right team.
+pub struct ScoreCount(pub usize, pub usize);
+
+/// Component used to tag the text containing the score.
+struct ScoreText;
+
+/// Component used to tag the texts containing the team scores.
+struct LeftScoreText;
+struct RightScoreText;
+
+/// Component used to tag the text containing which players belongs to which
+/// team.
+struct TeamText;
+
+/// Gives a point to the last player alive.
+pub(crate) fn handle_winner<G: MiniGame>(
+    mut players: ResMut<Players>,
+    players_alive_query: Query<&PlayerId>,
+) {
+    if players_alive_query.iter().len() == 1 {
+        let player_id = players_alive_query.iter().next().unwrap();
+        if let Some(winning_player) = players.get_mut(player_id) {
+            winning_player.increment_score(G::ID);
+            winning_player.record_stats(G::ID, |stats| stats.round_wins += 1);
+        }
+    }
+}
+
+/// Shows the score of all players in the game `G` sorted at the top of the
+/// screen.
+pub(crate) fn update_scoreboard<G: MiniGame>(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    players: Res<Players>,
+    score_text_query: Query<Entity, With<ScoreText>>,
+) {
+    if players.is_changed() {
+        for entity in score_text_query.iter() {
+            despawn_entity(&mut commands, entity);
+        }
+
+        let font = fonts.regular.clone();
+
+        let mut sorted_players = players.values().collect::<Vec<_>>();
+        sorted_players.sort_unstable_by_key(|p| Reverse(p.game_score(G::ID)));
+
+        let mut text_sections = Vec::default();
+        text_sections.push(TextSection {
+            value: "SCORE".into(),
+            style: TextStyle {
+                font: font.clone(),
+                font_size: 32.0,
+                color: Color::WHITE,
+            },
+        });
+
+        for (idx, player) in sorted_players.iter().enumerate() {
+            let color = player.color().as_bevy();
+            text_sections.push(TextSection {
+                value: format!("\n{}. {}", idx + 1, player.game_score(G::ID)),
+                style: TextStyle {
+                    font: font.clone(),
+                    font_size: 24.0,
+                    color,
+                },
+            });
+        }
+
+        let text_bundle = Text2dBundle {
+            text: Text {
+                sections: text_sections,
+                alignment: TextAlignment {
+                    vertical: VerticalAlign::Bottom,
+                    horizontal: HorizontalAlign::Center,
+                },
+            },
+            transform: Transform::from_xyz(0.0, (GAME_HEIGHT / 2.0) - G::SCOREBOARD_MARGIN, 0.0),
+            ..Default::default()
+        };
+
+        commands
+            .spawn_bundle(text_bundle)
+            .insert(G::default())
+            .insert(ScoreText);
+    }
+}
+
+/// Spawns the `ScoreCount` and the texts showing the score of the two teams.
+pub(crate) fn setup_team_score<G: MiniGame>(mut commands: Commands, fonts: Res<Fonts>) {
+    commands
+        .spawn()
+        .insert(ScoreCount(0, 0))
+        .insert(G::default());
+
+    let zero_score_text = Text::with_section(
+        "0",
+        TextStyle {
+            font: fonts.bold.clone(),
+            font_size: 128.0,
+            color: Color::WHITE,
+        },
+        TextAlignment {
+            vertical: VerticalAlign::Bottom,
+            horizontal: HorizontalAlign::Center,
+        },
+    );
+
+    let left_score_text_bundle = Text2dBundle {
+        text: zero_score_text.clone(),
+        transform: Transform::from_xyz(-GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(left_score_text_bundle)
+        .insert(LeftScoreText)
+        .insert(G::default());
+
+    let right_score_text_bundle = Text2dBundle {
+        text: zero_score_text,
+        transform: Transform::from_xyz(GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(right_score_text_bundle)
+        .insert(RightScoreText)
+        .insert(G::default());
+}
+
+/// Sends the team of a player to its client when the player is added to a
+/// team or when the player reconnects.
+pub(crate) fn send_team(
+    reconnected_players: Res<ReconnectedPlayers>,
+    players_playing: Query<(&PlayerId, &Team, ChangeTrackers<Team>)>,
+    mut server_message_writer: EventWriter<ServerMessageEvent>,
+) {
+    for (player_id, team, team_tracker) in players_playing.iter() {
+        if team_tracker.is_added() || reconnected_players.contains(player_id) {
+            server_message_writer.send(ServerMessageEvent::Player(
+                *player_id,
+                ServerMessage::Team(*team),
+            ));
+        }
+    }
+}
+
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn update_team_scoreboard<G: MiniGame>(
+    mut commands: Commands,
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+    score_change: Query<&ScoreCount, Changed<ScoreCount>>,
+    mut score_text_query: QuerySet<(
+        Query<&mut Text, With<LeftScoreText>>,
+        Query<&mut Text, With<RightScoreText>>,
+    )>,
+    players_playing: Query<(&PlayerId, &Team)>,
+    new_teams: Query<&Team, Added<Team>>,
+    old_team_text: Query<Entity, With<TeamText>>,
+    mut players_playing_count: Local<usize>,
+) {
+    for new_score_count in score_change.iter() {
+        let ScoreCount(left_score, right_score) = new_score_count;
+
+        let mut left_score_text = score_text_query.q0_mut().single_mut().unwrap();
+        if let Some(text_section) = left_score_text.sections.first_mut() {
+            text_section.value = left_score.to_string();
+        }
+
+        let mut right_score_text = score_text_query.q1_mut().single_mut().unwrap();
+        if let Some(text_section) = right_score_text.sections.first_mut() {
+            text_section.value = right_score.to_string();
+        }
+    }
+
+    let cur_players_playing_count = players_playing.iter().count();
+    // The players are respawned with new teams when they pick teams during the
+    // team selection, the amount of players playing might be the same as before.
+    if *players_playing_count != cur_players_playing_count
+        || score_change.iter().count() > 0
+        || new_teams.iter().count() > 0
+    {
+        *players_playing_count = cur_players_playing_count;
+
+        let font = fonts.bold.clone();
+        let font_size = 128.0;
+        update_team_text::<G>(
+            &mut commands,
+            &players,
+            players_playing,
+            old_team_text,
+            font,
+            font_size,
+        );
+    }
+}
+
+fn update_team_text<G: MiniGame>(
+    commands: &mut Commands,
+    players: &Players,
+    players_playing: Query<(&PlayerId, &Team)>,
+    old_team_text: Query<Entity, With<TeamText>>,
+    font: Handle<Font>,
+    font_size: f32,
+) {
+    for entity in old_team_text.iter() {
+        despawn_entity(commands, entity);
+    }
+
+    let mut left_team_player_ids = players_playing
+        .iter()
+        .filter(|(_, team)| matches!(team, Team::Left))
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+    left_team_player_ids.sort_unstable();
+
+    let left_team_text_sections =
+        create_team_text_sections(&left_team_player_ids, players, font.clone(), font_size);
+
+    let left_team_text_bundle = Text2dBundle {
+        text: Text {
+            sections: left_team_text_sections,
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(-GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0 - font_size / 2.0, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(left_team_text_bundle)
+        .insert(TeamText)
+        .insert(G::default());
+
+    let mut right_team_player_ids = players_playing
+        .iter()
+        .filter(|(_, team)| matches!(team, Team::Right))
+        .map(|(id, _)| *id)
+        .collect::<Vec<_>>();
+    right_team_player_ids.sort_unstable();
+
+    let right_team_text_sections =
+        create_team_text_sections(&right_team_player_ids, players, font, font_size);
+
+    let right_team_text_bundle = Text2dBundle {
+        text: Text {
+            sections: right_team_text_sections,
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0 - font_size / 2.0, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(right_team_text_bundle)
+        .insert(TeamText)
+        .insert(G::default());
+}
+
+fn create_team_text_sections(
+    team_player_ids: &[PlayerId],
+    players: &Players,
+    font: Handle<Font>,
+    font_size: f32,
+) -> Vec<TextSection> {
+    let mut text_sections = Vec::with_capacity(team_player_ids.len());
+    for player_id in team_player_ids {
+        if let Some(player) = players.get(player_id) {
+            text_sections.push(TextSection {
+                value: "•".into(),
+                style: TextStyle {
+                    font: font.clone(),
+                    font_size,
+                    color: player.color().as_bevy(),
+                },
+            });
+        }
+    }
+    text_sections
+}
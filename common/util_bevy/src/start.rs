@@ -1,4 +1,7 @@
-use std::ops::{Deref, DerefMut};
+use std::{
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 
 use bevy::{
     core::{Time, Timer},
@@ -10,7 +13,7 @@ use bevy::{
     text::{Text, Text2dBundle, TextAlignment, TextStyle},
 };
 
-use crate::{despawn_entity, Fonts};
+use crate::{despawn_entity, Fonts, StartTimerConfig, TeamSelection};
 
 /// Tag used on entities that should only exists during the start time.
 /// All entities with this tag will be removed when the game starts.
@@ -25,6 +28,14 @@ impl StartTimer {
     pub fn new(start_time: usize) -> Self {
         Self(Timer::from_seconds(start_time as f32, false))
     }
+
+    /// Resets the timer and sets its duration to `start_time` seconds. Used
+    /// between rounds since the start time might have been changed by a
+    /// reloaded config.
+    pub fn restart(&mut self, start_time: usize) {
+        self.0.set_duration(Duration::from_secs(start_time as u64));
+        self.0.reset();
+    }
 }
 
 impl Deref for StartTimer {
@@ -46,31 +57,39 @@ impl DerefMut for StartTimer {
 /// The `T` type is used to tag the entity so that it can be automatically
 /// removed when running the `despawn_system::<T>()` system.
 ///
-/// The `N` is how long the start timer should run in seconds.
+/// The `C` is the config of the game, it decides how long the start timer
+/// should run.
 ///
 /// As long as the game uses the `despawn_system`, there is no need to have a
 /// special teardown system for the StartTimer.
-pub fn setup_start_timer<T, const N: usize>(mut commands: Commands)
+pub fn setup_start_timer<T, C>(mut commands: Commands, config: Res<C>)
 where
     T: Component + Default,
+    C: StartTimerConfig,
 {
     commands
         .spawn()
-        .insert(StartTimer::new(N))
+        .insert(StartTimer::new(config.start_timer_time()))
         .insert(T::default());
 }
 
 /// System that handles the start timer.
 ///
 /// The countdown text is shown in the center of the screen. Any entities tagged
-/// with `StartEntity` will be removed when the game starts.
+/// with `StartEntity` will be removed when the game starts. The timer isn't
+/// ticked during the team selection of team games (see `TeamSelection`).
 pub fn handle_start_timer(
     mut commands: Commands,
     time: Res<Time>,
     fonts: Res<Fonts>,
     mut start_timer_query: Query<(Entity, &mut StartTimer, Option<&Children>)>,
     start_entities_query: Query<Entity, With<StartEntity>>,
+    team_selection_query: Query<&TeamSelection>,
 ) {
+    if team_selection_query.iter().next().is_some() {
+        return;
+    }
+
     let (entity, mut start_timer, children) = start_timer_query.single_mut().unwrap();
 
     let elapsed_before = start_timer.elapsed_secs();
@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+
+use bevy::{
+    core::Time,
+    prelude::{Local, Query, Res, ResMut, With},
+};
+
+use skitspel::{PlayerId, Players};
+
+use crate::{MiniGame, StartTimer};
+
+/// Keeps track of the players that are alive in the current round of a game
+/// using the `Scoring::LastSurvivor` and how long the round have been running.
+#[derive(Default)]
+pub(crate) struct SurvivalTracker {
+    round_time: f32,
+    players_alive: Vec<PlayerId>,
+}
+
+/// Records how long the players survives in the rounds of the game `G`. The
+/// time is stored in `GameStats::longest_survival` of the player if it is
+/// longer than the previous record of the session. The last player alive is recorded when
+/// the round ends, so it gets the time of the whole round.
+pub(crate) fn track_survival<G: MiniGame>(
+    time: Res<Time>,
+    mut players: ResMut<Players>,
+    start_timer_query: Query<&StartTimer, With<G>>,
+    players_alive_query: Query<&PlayerId>,
+    mut tracker: Local<SurvivalTracker>,
+) {
+    let start_timer = match start_timer_query.single() {
+        Ok(start_timer) => start_timer,
+        Err(_) => return,
+    };
+
+    if !start_timer.finished() {
+        // The round haven't started yet, the players that are spawned during
+        // the start time are the ones participating in the round.
+        tracker.round_time = 0.0;
+        tracker.players_alive = players_alive_query.iter().cloned().collect();
+        return;
+    }
+
+    tracker.round_time += time.delta_seconds();
+
+    let mut survival_times = HashMap::new();
+    let round_time = tracker.round_time;
+    tracker.players_alive.retain(|player_id| {
+        let is_alive = players_alive_query.iter().any(|id| id == player_id);
+        if !is_alive {
+            survival_times.insert(*player_id, round_time);
+        }
+        is_alive
+    });
+    if tracker.players_alive.len() <= 1 {
+        for player_id in tracker.players_alive.drain(..) {
+            survival_times.insert(player_id, round_time);
+        }
+    }
+
+    // Only borrow the players mutably if there is a new record, so that the
+    // `Players` aren't marked as changed every frame.
+    for (player_id, survival_time) in survival_times {
+        let is_record = players.get(&player_id).map_or(false, |player| {
+            let longest_survival = player
+                .session_stats()
+                .games
+                .get(G::ID)
+                .map_or(0.0, |stats| stats.longest_survival);
+            survival_time > longest_survival
+        });
+        if is_record {
+            if let Some(player) = players.get_mut(&player_id) {
+                player.record_stats(G::ID, |stats| {
+                    stats.longest_survival = stats.longest_survival.max(survival_time);
+                });
+            }
+        }
+    }
+}
@@ -0,0 +1,273 @@
+use std::marker::PhantomData;
+
+use bevy::{
+    prelude::{
+        Color, Commands, Entity, EventReader, EventWriter, Handle, HorizontalAlign, Query, Res,
+        Transform, VerticalAlign, With,
+    },
+    text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
+};
+
+use skitspel::{DisconnectedPlayers, PlayerId, Players, Team};
+
+use crate::{
+    create_vote_text_sections, despawn_entity, ActionPressedEvent, Fonts, MiniGame, PlayerVote,
+    VoteEvent,
+};
+
+const PICK_TEXT: &str = "Press left/right to pick a team\n";
+const RESHUFFLE_TEXT: &str = "Press down to reshuffle teams";
+const READY_TEXT: &str = "Press A when ready";
+const UNEVEN_TEXT: &str = "\nThe teams must be within one player of each other";
+
+/// Component added to the text shown during the team selection of the
+/// mini-games using `Scoring::Team`. The team selection takes place before the
+/// start timer of the first round; the start timer isn't started as long as
+/// this component exists (see `handle_start_timer`).
+///
+/// The players picks their teams with left/right and can vote to reshuffle the
+/// teams. The team selection is over when the teams are within one player of
+/// each other and a majority of the players are ready.
+#[derive(Default)]
+pub struct TeamSelection {
+    ready_vote: PlayerVote,
+    reshuffle_vote: PlayerVote,
+    is_uneven: bool,
+}
+
+/// The inputs of a player that are used during the team selection, the A
+/// button is read from the `ActionPressedEvent`s.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum TeamSelectionInput {
+    Pick(Team),
+    Reshuffle,
+}
+
+/// Event sent by the `handle_player_input` system of the mini-game `G`.
+pub(crate) struct TeamSelectionInputEvent<G> {
+    player_id: PlayerId,
+    input: TeamSelectionInput,
+    marker: PhantomData<G>,
+}
+
+impl<G> TeamSelectionInputEvent<G> {
+    pub(crate) fn new(player_id: PlayerId, input: TeamSelectionInput) -> Self {
+        Self {
+            player_id,
+            input,
+            marker: PhantomData,
+        }
+    }
+}
+
+/// Event sent when a player picks the other team during the team selection of
+/// the mini-game `G`. The game should respawn the player on the side of `team`.
+pub struct SwitchTeamEvent<G> {
+    pub player_id: PlayerId,
+    pub team: Team,
+    marker: PhantomData<G>,
+}
+
+/// Event sent when a majority of the players have voted to reshuffle the teams
+/// during the team selection of the mini-game `G`. The game should split the
+/// players into new teams using `reshuffled_teams` and respawn them.
+pub struct ReshuffleTeamsEvent<G>(PhantomData<G>);
+
+pub(crate) fn setup_team_selection<G: MiniGame>(
+    mut commands: Commands,
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+) {
+    let font_size = 24.0;
+    let team_selection = TeamSelection::default();
+    let required_amount = (players.human_count() / 2) + 1;
+
+    let team_selection_text_bundle = Text2dBundle {
+        text: Text {
+            sections: create_team_selection_text_sections(
+                &players,
+                &team_selection,
+                required_amount,
+                fonts.regular.clone(),
+                font_size,
+            ),
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(0.0, G::TEXT_Y - 2.0 * font_size, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(team_selection_text_bundle)
+        .insert(team_selection)
+        .insert(G::default());
+}
+
+/// Handles the inputs of the players during the team selection. Pressing
+/// left/right sends a `SwitchTeamEvent` if the player isn't already in that
+/// team, pressing down flips the reshuffle vote and pressing A flips the ready
+/// vote of the player. Bots keeps the teams that they were given.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub(crate) fn handle_team_selection<G: MiniGame>(
+    mut commands: Commands,
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+    disconnected_players: Res<DisconnectedPlayers>,
+    mut team_selection_query: Query<(Entity, &mut TeamSelection, &mut Text), With<G>>,
+    players_playing: Query<(&PlayerId, &Team)>,
+    mut action_event_reader: EventReader<ActionPressedEvent<G>>,
+    mut input_event_reader: EventReader<TeamSelectionInputEvent<G>>,
+    mut switch_team_event_writer: EventWriter<SwitchTeamEvent<G>>,
+    mut reshuffle_event_writer: EventWriter<ReshuffleTeamsEvent<G>>,
+) {
+    let (entity, mut team_selection, mut team_selection_text) =
+        match team_selection_query.single_mut() {
+            Ok(team_selection) => team_selection,
+            Err(_) => return,
+        };
+
+    let is_human = |player_id: &PlayerId| {
+        players
+            .get(player_id)
+            .map_or(false, |player| !player.is_bot())
+    };
+
+    let ready_amount_before = team_selection.ready_vote.voted_amount();
+    let reshuffle_amount_before = team_selection.reshuffle_vote.voted_amount();
+    let total_amount_before = team_selection.ready_vote.total_amount();
+    let is_uneven_before = team_selection.is_uneven;
+
+    // The switches are applied by the game, so keep track of the teams that
+    // the players will have after this frame.
+    let mut teams = players_playing
+        .iter()
+        .map(|(player_id, team)| (*player_id, *team))
+        .collect::<Vec<_>>();
+    let mut teams_changed = false;
+
+    for input_event in input_event_reader.iter() {
+        let player_id = input_event.player_id;
+        if !is_human(&player_id) {
+            continue;
+        }
+
+        match input_event.input {
+            TeamSelectionInput::Pick(team) => {
+                let cur_team = teams
+                    .iter_mut()
+                    .find(|(id, _)| *id == player_id)
+                    .map(|(_, cur_team)| cur_team);
+                if let Some(cur_team) = cur_team {
+                    if *cur_team != team {
+                        *cur_team = team;
+                        teams_changed = true;
+                        switch_team_event_writer.send(SwitchTeamEvent {
+                            player_id,
+                            team,
+                            marker: PhantomData,
+                        });
+                    }
+                }
+            }
+
+            TeamSelectionInput::Reshuffle => {
+                let vote_event = VoteEvent::Flip(player_id);
+                team_selection.reshuffle_vote.register_vote(&vote_event);
+            }
+        }
+    }
+
+    for action_event in action_event_reader.iter() {
+        if is_human(&action_event.player_id) {
+            let vote_event = VoteEvent::Flip(action_event.player_id);
+            team_selection.ready_vote.register_vote(&vote_event);
+        }
+    }
+
+    for player_id in disconnected_players.iter() {
+        let vote_event = VoteEvent::Value(*player_id, false);
+        team_selection.ready_vote.register_vote(&vote_event);
+        team_selection.reshuffle_vote.register_vote(&vote_event);
+    }
+
+    let total_amount = players.human_count();
+    team_selection.ready_vote.set_total_amount(total_amount);
+    team_selection.reshuffle_vote.set_total_amount(total_amount);
+    let required_amount = (total_amount / 2) + 1;
+
+    if team_selection.reshuffle_vote.voted_amount() >= required_amount {
+        reshuffle_event_writer.send(ReshuffleTeamsEvent(PhantomData));
+        team_selection.reshuffle_vote.clear();
+        teams_changed = true;
+    }
+
+    let team_size = |team: Team| teams.iter().filter(|(_, t)| *t == team).count();
+    let (left_size, right_size) = (team_size(Team::Left), team_size(Team::Right));
+    team_selection.is_uneven = left_size.max(right_size) - left_size.min(right_size) > 1;
+
+    // Games with only bots starts immediately. The selection is never ended in
+    // the same frame as the teams are changed since the changes haven't been
+    // applied by the game yet.
+    let is_ready = team_selection.ready_vote.voted_amount() >= required_amount || total_amount == 0;
+    if is_ready && !team_selection.is_uneven && !teams_changed {
+        despawn_entity(&mut commands, entity);
+        return;
+    }
+
+    if ready_amount_before != team_selection.ready_vote.voted_amount()
+        || reshuffle_amount_before != team_selection.reshuffle_vote.voted_amount()
+        || total_amount_before != total_amount
+        || is_uneven_before != team_selection.is_uneven
+    {
+        team_selection_text.sections = create_team_selection_text_sections(
+            &players,
+            &team_selection,
+            required_amount,
+            fonts.regular.clone(),
+            24.0,
+        );
+    }
+}
+
+fn create_team_selection_text_sections(
+    players: &Players,
+    team_selection: &TeamSelection,
+    required_amount: usize,
+    font: Handle<Font>,
+    font_size: f32,
+) -> Vec<TextSection> {
+    let text_section = |value: &str, color: Color| TextSection {
+        value: value.into(),
+        style: TextStyle {
+            font: font.clone(),
+            font_size,
+            color,
+        },
+    };
+
+    let mut text_sections = vec![text_section(PICK_TEXT, Color::WHITE)];
+    text_sections.extend(create_vote_text_sections(
+        RESHUFFLE_TEXT.into(),
+        players,
+        &team_selection.reshuffle_vote,
+        required_amount,
+        font.clone(),
+        font_size,
+    ));
+    text_sections.push(text_section("\n", Color::WHITE));
+    text_sections.extend(create_vote_text_sections(
+        READY_TEXT.into(),
+        players,
+        &team_selection.ready_vote,
+        required_amount,
+        font.clone(),
+        font_size,
+    ));
+    if team_selection.is_uneven {
+        text_sections.push(text_section(UNEVEN_TEXT, Color::rgb(1.0, 0.1, 0.1)));
+    }
+    text_sections
+}
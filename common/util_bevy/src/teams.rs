@@ -0,0 +1,258 @@
+use rand::prelude::SliceRandom;
+
+use skitspel::{PlayerId, Players, Team};
+
+use crate::rating::average_rating;
+
+/// The amount of the most even splits of the players that a reshuffle chooses
+/// between.
+const RESHUFFLE_CANDIDATES: usize = 5;
+
+/// Splits the players with the IDs `player_ids` into two teams so that the
+/// average ratings of the teams are as even as possible. If multiple splits are
+/// equally even, one of them is chosen at random.
+pub fn balanced_teams(players: &Players, player_ids: &[PlayerId]) -> Vec<(PlayerId, Team)> {
+    let splits = team_splits(players, player_ids);
+    let min_diff = splits.first().map_or(0.0, |(diff, _)| *diff);
+    let best_splits = splits
+        .into_iter()
+        .take_while(|(diff, _)| *diff <= min_diff + f32::EPSILON)
+        .map(|(_, teams)| teams)
+        .collect::<Vec<_>>();
+    choose_split(best_splits)
+}
+
+/// Splits the players in `current_teams` into new teams. The new teams are
+/// chosen at random among the most even splits that differs from the current
+/// one.
+pub fn reshuffled_teams(
+    players: &Players,
+    current_teams: &[(PlayerId, Team)],
+) -> Vec<(PlayerId, Team)> {
+    let player_ids = current_teams
+        .iter()
+        .map(|(player_id, _)| *player_id)
+        .collect::<Vec<_>>();
+    let candidates = team_splits(players, &player_ids)
+        .into_iter()
+        .map(|(_, teams)| teams)
+        .filter(|teams| !is_same_split(teams, current_teams))
+        .take(RESHUFFLE_CANDIDATES)
+        .collect::<Vec<_>>();
+
+    if candidates.is_empty() {
+        // Only possible if there are less than two players.
+        current_teams.to_vec()
+    } else {
+        choose_split(candidates)
+    }
+}
+
+/// Returns the team that a player joining a game with the players in `teams`
+/// should be added to. The player is added to the smallest team, or the team
+/// with the lowest average rating if the teams are equally large.
+pub fn team_for_new_player(players: &Players, teams: &[(PlayerId, Team)]) -> Team {
+    let team_size = |team: Team| teams.iter().filter(|(_, t)| *t == team).count();
+    let left_size = team_size(Team::Left);
+    let right_size = team_size(Team::Right);
+    if left_size != right_size {
+        return if left_size < right_size {
+            Team::Left
+        } else {
+            Team::Right
+        };
+    }
+
+    match (
+        average_rating(players, teams, Team::Left),
+        average_rating(players, teams, Team::Right),
+    ) {
+        (Some(left_rating), Some(right_rating)) if left_rating < right_rating => Team::Left,
+        _ => Team::Right,
+    }
+}
+
+/// Returns all possible splits of the players into two teams together with the
+/// difference between the average ratings of the teams, sorted with the most
+/// even split first. If the amount of players is odd, the left team gets the
+/// fewest players.
+fn team_splits(players: &Players, player_ids: &[PlayerId]) -> Vec<(f32, Vec<(PlayerId, Team)>)> {
+    let left_size = player_ids.len() / 2;
+    let is_even = player_ids.len() % 2 == 0;
+
+    let mut splits = Vec::default();
+    for mask in 0..(1u32 << player_ids.len()) {
+        // With an even amount of players, every split also exists with the
+        // sides swapped. Only keep the one with the first player in the right
+        // team, the sides are randomized in `choose_split`.
+        if mask.count_ones() as usize != left_size || (is_even && mask & 1 != 0) {
+            continue;
+        }
+
+        let teams = player_ids
+            .iter()
+            .enumerate()
+            .map(|(idx, player_id)| {
+                let team = if mask & (1 << idx) != 0 {
+                    Team::Left
+                } else {
+                    Team::Right
+                };
+                (*player_id, team)
+            })
+            .collect::<Vec<_>>();
+
+        let diff = match (
+            average_rating(players, &teams, Team::Left),
+            average_rating(players, &teams, Team::Right),
+        ) {
+            (Some(left_rating), Some(right_rating)) => (left_rating - right_rating).abs(),
+            _ => 0.0,
+        };
+        splits.push((diff, teams));
+    }
+
+    splits.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+    splits
+}
+
+/// Chooses one of the given splits at random. If the teams are equally large,
+/// the sides of the teams are randomized as well.
+fn choose_split(splits: Vec<Vec<(PlayerId, Team)>>) -> Vec<(PlayerId, Team)> {
+    let mut rng = rand::thread_rng();
+    let mut teams = splits.choose(&mut rng).cloned().unwrap_or_default();
+
+    let left_size = teams.iter().filter(|(_, team)| *team == Team::Left).count();
+    if left_size * 2 == teams.len() && rand::random() {
+        for (_, team) in teams.iter_mut() {
+            *team = match team {
+                Team::Left => Team::Right,
+                Team::Right => Team::Left,
+            };
+        }
+    }
+    teams
+}
+
+/// Returns true if the two splits contains the same teams, the sides of the
+/// teams doesn't matter.
+fn is_same_split(a: &[(PlayerId, Team)], b: &[(PlayerId, Team)]) -> bool {
+    let team_of = |teams: &[(PlayerId, Team)], player_id: &PlayerId| {
+        teams
+            .iter()
+            .find(|(id, _)| id == player_id)
+            .map(|(_, team)| *team)
+    };
+    let same_sides = a.iter().all(|(id, team)| team_of(b, id) == Some(*team));
+    let swapped_sides = a.iter().all(|(id, team)| team_of(b, id) != Some(*team));
+    same_sides || swapped_sides
+}
+
+#[cfg(test)]
+mod tests {
+    use skitspel::{Color, Player, DEFAULT_RATING};
+
+    use super::*;
+
+    fn players_with_ratings(ratings: &[f32]) -> (Players, Vec<PlayerId>) {
+        let mut players = Players::default();
+        let mut player_ids = Vec::default();
+        for (idx, rating) in ratings.iter().enumerate() {
+            let player_id = PlayerId::from(idx as u64 + 1);
+            let mut player = Player::new(player_id, idx.to_string(), Color::new(0.0, 0.0, 0.0));
+            player.set_rating(*rating);
+            players.insert(player_id, player);
+            player_ids.push(player_id);
+        }
+        (players, player_ids)
+    }
+
+    fn team_size(teams: &[(PlayerId, Team)], team: Team) -> usize {
+        teams.iter().filter(|(_, t)| *t == team).count()
+    }
+
+    #[test]
+    fn balanced_teams_contains_every_player_once() {
+        let (players, ids) = players_with_ratings(&[DEFAULT_RATING; 6]);
+        let teams = balanced_teams(&players, &ids);
+
+        assert_eq!(teams.len(), ids.len());
+        for player_id in &ids {
+            assert_eq!(teams.iter().filter(|(id, _)| id == player_id).count(), 1);
+        }
+        assert_eq!(team_size(&teams, Team::Left), 3);
+        assert_eq!(team_size(&teams, Team::Right), 3);
+    }
+
+    #[test]
+    fn balanced_teams_with_odd_player_count() {
+        let (players, ids) = players_with_ratings(&[DEFAULT_RATING; 5]);
+        for _ in 0..10 {
+            let teams = balanced_teams(&players, &ids);
+            assert_eq!(team_size(&teams, Team::Left), 2);
+            assert_eq!(team_size(&teams, Team::Right), 3);
+        }
+    }
+
+    #[test]
+    fn balanced_teams_evens_out_ratings() {
+        let (players, ids) = players_with_ratings(&[1000.0, 1000.0, 2000.0, 2000.0]);
+        for _ in 0..10 {
+            let teams = balanced_teams(&players, &ids);
+            assert_eq!(average_rating(&players, &teams, Team::Left), Some(1500.0));
+            assert_eq!(average_rating(&players, &teams, Team::Right), Some(1500.0));
+        }
+    }
+
+    #[test]
+    fn reshuffled_teams_differs_from_current() {
+        let (players, ids) = players_with_ratings(&[DEFAULT_RATING; 4]);
+        let current_teams = [
+            (ids[0], Team::Left),
+            (ids[1], Team::Left),
+            (ids[2], Team::Right),
+            (ids[3], Team::Right),
+        ];
+        for _ in 0..10 {
+            let teams = reshuffled_teams(&players, &current_teams);
+            assert_eq!(teams.len(), current_teams.len());
+            assert!(!is_same_split(&teams, &current_teams));
+        }
+    }
+
+    #[test]
+    fn reshuffled_teams_with_single_player() {
+        let (players, ids) = players_with_ratings(&[DEFAULT_RATING]);
+        let current_teams = [(ids[0], Team::Right)];
+        assert_eq!(reshuffled_teams(&players, &current_teams), current_teams);
+    }
+
+    #[test]
+    fn is_same_split_ignores_sides() {
+        let ids = [PlayerId::from(1u64), PlayerId::from(2u64)];
+        let teams = [(ids[0], Team::Left), (ids[1], Team::Right)];
+        let swapped_teams = [(ids[0], Team::Right), (ids[1], Team::Left)];
+        assert!(is_same_split(&teams, &swapped_teams));
+    }
+
+    #[test]
+    fn new_player_joins_smallest_team() {
+        let (players, ids) = players_with_ratings(&[DEFAULT_RATING; 3]);
+        let teams = [
+            (ids[0], Team::Left),
+            (ids[1], Team::Right),
+            (ids[2], Team::Right),
+        ];
+        assert_eq!(team_for_new_player(&players, &teams), Team::Left);
+    }
+
+    #[test]
+    fn new_player_joins_weakest_of_equal_teams() {
+        let (players, ids) = players_with_ratings(&[1200.0, 800.0]);
+        let teams = [(ids[0], Team::Left), (ids[1], Team::Right)];
+        assert_eq!(team_for_new_player(&players, &teams), Team::Right);
+
+        let swapped_teams = [(ids[0], Team::Right), (ids[1], Team::Left)];
+        assert_eq!(team_for_new_player(&players, &swapped_teams), Team::Left);
+    }
+}
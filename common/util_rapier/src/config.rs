@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+use skitspel::ACCEL_AMOUNT;
+use util_bevy::{ensure_positive, Config, ConfigError};
+
+/// Config used by the games where the players are moved with `move_players`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MovementConfig {
+    /// The amount of acceleration that is applied to the players every tick
+    /// when moving.
+    pub accel_amount: f32,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            accel_amount: ACCEL_AMOUNT,
+        }
+    }
+}
+
+impl Config for MovementConfig {
+    const SECTION: &'static str = "movement";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure_positive(Self::SECTION, "accel_amount", self.accel_amount)
+    }
+}
@@ -1,3 +1,4 @@
+pub use config::MovementConfig;
 pub use draw::{
     create_circle_points, create_path_with_thickness, create_polygon_points,
     create_polygon_points_with_angle, indices_from_vertices, vertices_with_thickness,
@@ -5,6 +6,7 @@ pub use draw::{
 pub use player::{move_players, spawn_player, spawn_player_with_vertex_amount};
 pub use wall::spawn_border_walls;
 
+mod config;
 mod draw;
 mod player;
 mod wall;
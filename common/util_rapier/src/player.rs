@@ -15,15 +15,16 @@ use bevy_rapier2d::{
 };
 use rand::Rng;
 
-use skitspel::{PlayerId, Players, ACCEL_AMOUNT, RAPIER_SCALE_FACTOR, VERTEX_AMOUNT};
+use skitspel::{PlayerId, Players, RAPIER_SCALE_FACTOR, VERTEX_AMOUNT};
 use util_bevy::{Shape, StartTimer};
 
-use crate::create_polygon_points;
+use crate::{create_polygon_points, MovementConfig};
 
 /// System that moves players according to the current inputs inside `Players`.
-/// The default `ACCEL_AMOUNT` is used.
+/// The acceleration is taken from the `MovementConfig`.
 pub fn move_players(
     time: Res<Time>,
+    config: Res<MovementConfig>,
     players: Res<Players>,
     mut player_query: Query<(&PlayerId, &mut RigidBodyVelocity, &RigidBodyMassProps)>,
     start_timer_query: Query<Option<&StartTimer>>,
@@ -37,9 +38,8 @@ pub fn move_players(
     let delta_tick = time.delta_seconds();
     for (player_id, mut velocity, mass) in player_query.iter_mut() {
         if let Some(player) = players.get(player_id) {
-            let movement_x = player.movement_x();
-            let movement_y = player.movement_y();
-            let movement_vec = Vec2::new(movement_x, movement_y) * ACCEL_AMOUNT * delta_tick;
+            let (movement_x, movement_y) = player.movement_vec();
+            let movement_vec = Vec2::new(movement_x, movement_y) * config.accel_amount * delta_tick;
             velocity.apply_impulse(mass, movement_vec.into());
         }
     }
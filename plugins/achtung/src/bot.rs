@@ -0,0 +1,113 @@
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::{
+    math::Vec2,
+    prelude::{Query, Res, ResMut},
+};
+use bevy_rapier2d::prelude::RigidBodyPosition;
+
+use skitspel::{BotInput, PlayerId, Players, RAPIER_SCALE_FACTOR};
+use util_bevy::apply_bot_input;
+
+use crate::{colliders::Colliders, AchtungConfig, JumpTimer, PLAYER_THICKNESS};
+
+/// The distance (in pixels) between the points that are checked for collisions
+/// when a bot looks ahead.
+const PROBE_STEP: f32 = 10.0;
+
+/// How far (in pixels) a bot looks ahead.
+const PROBE_DISTANCE: f32 = 400.0;
+
+/// If a bot can't find a path that is free for at least this distance (in
+/// pixels), it tries to jump over the obstacle.
+const JUMP_DISTANCE: f32 = 40.0;
+
+/// Steers the bots. Every bot looks ahead along the paths it would take when
+/// going straight, turning left and turning right. It picks the path that is
+/// free of walls and tails for the longest distance.
+pub(crate) fn handle_bots(
+    config: Res<AchtungConfig>,
+    mut players: ResMut<Players>,
+    player_query: Query<(&PlayerId, &RigidBodyPosition, &JumpTimer)>,
+    colliders_query: Query<&Colliders>,
+) {
+    let colliders = colliders_query.single().unwrap();
+
+    // The radius (in pixels) of the circle that a player travels in when
+    // turning as much as possible.
+    let turn_radius = config.speed * RAPIER_SCALE_FACTOR / config.torque;
+
+    for (player_id, pos, jump_timer) in player_query.iter() {
+        if !players.get(player_id).map_or(false, |p| p.is_bot()) {
+            continue;
+        }
+
+        let input = if jump_timer.finished() {
+            bot_input(colliders, pos, turn_radius)
+        } else {
+            // Can't collide with tails while jumping, keep going straight.
+            BotInput::default()
+        };
+        apply_bot_input(&mut players, *player_id, &input);
+    }
+}
+
+fn bot_input(colliders: &Colliders, pos: &RigidBodyPosition, turn_radius: f32) -> BotInput {
+    let start = Vec2::new(
+        pos.position.translation.x * RAPIER_SCALE_FACTOR,
+        pos.position.translation.y * RAPIER_SCALE_FACTOR,
+    );
+    let heading = pos.position.rotation.angle() + FRAC_PI_2;
+
+    // A positive x movement turns the player right (clockwise). Going straight
+    // is checked first so that it is preferred if all paths are equally good.
+    let (movement_x, free_distance) = [0.0, 1.0, -1.0]
+        .iter()
+        .map(|movement_x| {
+            let distance = free_distance(colliders, start, heading, *movement_x, turn_radius);
+            (*movement_x, distance)
+        })
+        .fold(
+            (0.0, f32::MIN),
+            |best, cur| {
+                if cur.1 > best.1 {
+                    cur
+                } else {
+                    best
+                }
+            },
+        );
+
+    BotInput {
+        movement: (movement_x, 0.0),
+        a: free_distance < JUMP_DISTANCE,
+        ..Default::default()
+    }
+}
+
+/// Returns how far (in pixels) a player at position `start` with the heading
+/// `heading` can travel when holding `movement_x` before it collides with
+/// something. The `turn_radius` is the radius of the tightest turn possible.
+fn free_distance(
+    colliders: &Colliders,
+    start: Vec2,
+    mut heading: f32,
+    movement_x: f32,
+    turn_radius: f32,
+) -> f32 {
+    let delta_heading = -movement_x * PROBE_STEP / turn_radius;
+
+    // Start at the front of the player to not collide with its own tail.
+    let mut p = start + Vec2::new(heading.cos(), heading.sin()) * PLAYER_THICKNESS;
+    let mut distance = PLAYER_THICKNESS;
+    while distance < PROBE_DISTANCE {
+        if colliders.is_collision(p, false) {
+            return distance;
+        }
+
+        heading += delta_heading;
+        p += Vec2::new(heading.cos(), heading.sin()) * PROBE_STEP;
+        distance += PROBE_STEP;
+    }
+    distance
+}
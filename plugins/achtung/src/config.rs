@@ -0,0 +1,89 @@
+use serde::Deserialize;
+
+use util_bevy::{ensure_positive, Config, ConfigError, MatchConfig, MatchRules, StartTimerConfig};
+
+use crate::{
+    ACHTUNG_CONSTANT_SPEED, ACHTUNG_CONSTANT_TORQUE, JUMP_COOLDOWN_TIME, JUMP_TIME, MATCH_POINTS,
+    START_TIMER_TIME, TAIL_SPAWN_TIME,
+};
+
+/// The tuning of the "Achtung die kurve" game. Read from the `[achtung]`
+/// section of the config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AchtungConfig {
+    /// How long the timer between rounds are in seconds.
+    pub start_timer_time: usize,
+    /// The rules deciding when a match is over (the `[achtung.match]` table).
+    #[serde(rename = "match")]
+    pub match_rules: MatchRules,
+    /// The constant speed that the players travels in.
+    pub speed: f32,
+    /// The torque applied to the players when turning.
+    pub torque: f32,
+    /// How long time a jump takes (seconds).
+    pub jump_time: f32,
+    /// The time it takes for the jump cooldown to reset (seconds). This
+    /// includes the time of the jump itself.
+    pub jump_cooldown_time: f32,
+    /// How often a tail is spawned (seconds).
+    pub tail_spawn_time: f32,
+}
+
+impl Default for AchtungConfig {
+    fn default() -> Self {
+        Self {
+            start_timer_time: START_TIMER_TIME,
+            match_rules: MatchRules::first_to(MATCH_POINTS),
+            speed: ACHTUNG_CONSTANT_SPEED,
+            torque: ACHTUNG_CONSTANT_TORQUE,
+            jump_time: JUMP_TIME,
+            jump_cooldown_time: JUMP_COOLDOWN_TIME,
+            tail_spawn_time: TAIL_SPAWN_TIME,
+        }
+    }
+}
+
+impl Config for AchtungConfig {
+    const SECTION: &'static str = "achtung";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure_positive(
+            Self::SECTION,
+            "start_timer_time",
+            self.start_timer_time as f32,
+        )?;
+        self.match_rules.validate(Self::SECTION)?;
+        ensure_positive(Self::SECTION, "speed", self.speed)?;
+        ensure_positive(Self::SECTION, "torque", self.torque)?;
+        ensure_positive(Self::SECTION, "jump_time", self.jump_time)?;
+        ensure_positive(Self::SECTION, "jump_cooldown_time", self.jump_cooldown_time)?;
+        ensure_positive(Self::SECTION, "tail_spawn_time", self.tail_spawn_time)?;
+
+        if self.jump_cooldown_time < self.jump_time {
+            let reason = format!(
+                "must be greater than or equal to `jump_time` ({}), got {}",
+                self.jump_time, self.jump_cooldown_time
+            );
+            Err(ConfigError::new(
+                Self::SECTION,
+                "jump_cooldown_time",
+                reason,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl StartTimerConfig for AchtungConfig {
+    fn start_timer_time(&self) -> usize {
+        self.start_timer_time
+    }
+}
+
+impl MatchConfig for AchtungConfig {
+    fn match_rules(&self) -> &MatchRules {
+        &self.match_rules
+    }
+}
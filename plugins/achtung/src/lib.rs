@@ -1,5 +1,4 @@
 use std::{
-    cmp::Reverse,
     collections::HashSet,
     f32::consts::{PI, TAU},
     ops::{Deref, DerefMut},
@@ -11,12 +10,11 @@ use bevy::{
     math::{Quat, Vec2, Vec3},
     prelude::{
         AppBuilder, Assets, BuildChildren, Children, Color, Commands, CoreStage, Entity,
-        EventReader, EventWriter, GlobalTransform, HorizontalAlign, IntoSystem, Local, Mesh,
-        MeshBundle, ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines, Res, ResMut,
-        State, SystemSet, SystemStage, Transform, VerticalAlign, With,
+        EventReader, EventWriter, GlobalTransform, IntoSystem, Mesh, MeshBundle,
+        ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines, Res, ResMut, SystemSet,
+        SystemStage, Transform, With,
     },
     render::{mesh::Indices, pipeline::PrimitiveTopology},
-    text::{Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
 use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
 use bevy_rapier2d::{
@@ -31,20 +29,24 @@ use rand::{prelude::SliceRandom, Rng};
 
 use colliders::Colliders;
 use skitspel::{
-    ActionEvent, DisconnectedPlayers, GameState, Player, PlayerId, Players, GAME_HEIGHT,
-    GAME_WIDTH, RAPIER_SCALE_FACTOR,
+    DisconnectedPlayers, GameState, Player, PlayerId, Players, ServerMessage, ServerMessageEvent,
+    GAME_HEIGHT, GAME_WIDTH, RAPIER_SCALE_FACTOR,
 };
 use util_bevy::{
-    create_vote_text_sections, despawn_entity, despawn_system, handle_start_timer,
-    setup_start_timer, AsBevyColor, Fonts, PlayerVote, Shape, StartEntity, StartTimer, VoteEvent,
+    despawn_entity, handle_start_timer, setup_start_timer, ActionPressedEvent, AsBevyColor,
+    MiniGame, MiniGamePlugin, PendingConfig, Scoring, Shape, StartEntity, StartTimer,
 };
 use util_rapier::{
     create_circle_points, indices_from_vertices, spawn_border_walls, vertices_with_thickness,
 };
 
+pub use config::AchtungConfig;
+
+mod bot;
 mod colliders;
+mod config;
 
-const GAME_STATE: GameState = GameState::AchtungGame;
+const GAME_STATE: GameState = AchtungGamePlugin::GAME_STATE;
 
 const SPAWN_POSITIONS: [(f32, f32); 12] = [
     (-GAME_WIDTH * 0.3, GAME_HEIGHT * 0.25),
@@ -61,15 +63,13 @@ const SPAWN_POSITIONS: [(f32, f32); 12] = [
     (GAME_WIDTH * 0.3, -GAME_HEIGHT * 0.25),
 ];
 
-const JUMP_TEXT: &str = "Press A to jump\n";
-const EXIT_TEXT: &str = "Press B to go back to main menu";
-
 /// The thickness/size of the player character.
 const PLAYER_THICKNESS: f32 = 20.0;
 
 /// The thickness of the tails.
 const TAIL_THICKNESS: f32 = 10.0;
 
+/// The default speed and torque of the players.
 const ACHTUNG_CONSTANT_SPEED: f32 = 400.0;
 const ACHTUNG_CONSTANT_TORQUE: f32 = 150.0;
 
@@ -77,20 +77,20 @@ const ACHTUNG_CONSTANT_TORQUE: f32 = 150.0;
 const JUMP_COOLDOWN_WIDTH: f32 = PLAYER_THICKNESS * 1.5;
 const JUMP_COOLDOWN_HEIGHT: f32 = 7.0;
 
-/// How long time a jump takes (seconds).
+/// The default time that a jump takes (seconds).
 const JUMP_TIME: f32 = 0.75;
 
-/// The time it takes for the jump cooldown to reset.
+/// The default time it takes for the jump cooldown to reset.
 const JUMP_COOLDOWN_TIME: f32 = 8.0 + JUMP_TIME;
 
-/// How often a tail is spawned (seconds).
+/// The default time between tail spawns (seconds).
 const TAIL_SPAWN_TIME: f32 = 1.0 / 5.0;
 
-/// How long the timer between rounds are in seconds.
+/// The default time of the timer between rounds in seconds.
 const START_TIMER_TIME: usize = 3;
 
-/// Tag used on the exit text.
-struct ExitText;
+/// The default amount of points needed to win a match.
+const MATCH_POINTS: usize = 10;
 
 /// Tag used on the player tails.
 struct Tail;
@@ -103,11 +103,8 @@ struct CurrentTail;
 #[derive(Clone)]
 struct Wall;
 
-/// Component used to tag the text containing the score.
-struct ScoreText;
-
-/// Event created when player with ID `PlayerId` jumps.
-struct JumpEvent(PlayerId);
+/// Event created when player with ID `player_id` jumps.
+type JumpEvent = ActionPressedEvent<AchtungGamePlugin>;
 
 /// Event created when a player dies and its character is removed. This is used
 /// to synchronize the removal of the player character and the cooldown UI for
@@ -136,9 +133,9 @@ impl DerefMut for JumpCooldownTimer {
     }
 }
 
-impl Default for JumpCooldownTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(JUMP_COOLDOWN_TIME, false))
+impl JumpCooldownTimer {
+    fn new(cooldown_time: f32) -> Self {
+        Self(Timer::from_seconds(cooldown_time, false))
     }
 }
 
@@ -160,11 +157,11 @@ impl DerefMut for JumpTimer {
     }
 }
 
-impl Default for JumpTimer {
-    fn default() -> Self {
+impl JumpTimer {
+    fn new(jump_time: f32) -> Self {
         // The timer should start as finished.
-        let mut timer = Timer::from_seconds(JUMP_TIME, false);
-        timer.tick(Duration::from_secs_f32(JUMP_TIME));
+        let mut timer = Timer::from_seconds(jump_time, false);
+        timer.tick(Duration::from_secs_f32(jump_time));
         Self(timer)
     }
 }
@@ -184,9 +181,9 @@ struct TailSpawn {
 }
 
 impl TailSpawn {
-    fn new(x: f32, y: f32) -> Self {
+    fn new(x: f32, y: f32, spawn_time: f32) -> Self {
         Self {
-            timer: Timer::from_seconds(TAIL_SPAWN_TIME, true),
+            timer: Timer::from_seconds(spawn_time, true),
             prev_x: x,
             prev_y: y,
         }
@@ -212,32 +209,35 @@ pub struct AchtungGamePlugin;
 
 impl Plugin for AchtungGamePlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_event::<JumpEvent>()
+        app.add_plugin(MiniGamePlugin::<AchtungGamePlugin>::default())
             .add_event::<DeathEvent>()
+            .init_resource::<AchtungConfig>()
+            .init_resource::<PendingConfig<AchtungConfig>>()
             .add_system_set(
                 SystemSet::on_enter(GAME_STATE)
-                    .with_system(reset_votes.system())
                     .with_system(setup_colliders.system())
-                    .with_system(setup_start_timer::<AchtungGamePlugin, START_TIMER_TIME>.system())
-                    .with_system(setup_map.system())
-                    .with_system(setup_screen_text.system()),
+                    .with_system(setup_start_timer::<AchtungGamePlugin, AchtungConfig>.system())
+                    .with_system(setup_map.system()),
             )
             .add_system_set(
                 SystemSet::on_update(GAME_STATE)
                     .with_system(handle_disconnect.system().label("vote"))
-                    .with_system(handle_player_input.system().label("vote").label("jump"))
-                    .with_system(handle_exit_event.system().after("vote"))
-                    .with_system(handle_winner.system().before("reset"))
-                    .with_system(update_scoreboard.system())
-                    .with_system(move_achtung_players.system())
+                    .with_system(bot::handle_bots.system().label("bots").after("vote"))
+                    .with_system(move_achtung_players.system().after("bots"))
                     .with_system(handle_death.system().label("death").after("vote"))
-                    .with_system(reset_game.system().label("reset").after("death"))
+                    .with_system(
+                        reset_game
+                            .system()
+                            .label("reset")
+                            .after("death")
+                            .after("winner"),
+                    )
                     .with_system(handle_start_timer.system().label("start").after("reset"))
                     .with_system(
                         update_jump_timers
                             .system()
                             .label("timer")
-                            .after("jump")
+                            .after("vote")
                             .after("reset"),
                     )
                     .with_system(handle_player_jump.system().after("timer").before("jump_ui"))
@@ -252,10 +252,6 @@ impl Plugin for AchtungGamePlugin {
                     )
                     .with_system(handle_current_tails.system().label("current_tail")),
             )
-            .add_system_set(
-                SystemSet::on_exit(GAME_STATE)
-                    .with_system(despawn_system::<AchtungGamePlugin>.system()),
-            )
             .add_stage_after(
                 CoreStage::PostUpdate,
                 "cooldown_ui_rotation",
@@ -268,6 +264,18 @@ impl Plugin for AchtungGamePlugin {
     }
 }
 
+impl MiniGame for AchtungGamePlugin {
+    type Config = AchtungConfig;
+
+    const ID: &'static str = "achtung";
+    const NAME: &'static str = "Achtung die Kurve";
+    const DESCRIPTION: &'static str =
+        "Steer your snake and avoid all tails & walls. The last player alive wins the round.";
+    const SCREENSHOT: &'static [u8] = include_bytes!("..\\assets\\achtung.png");
+    const SCORING: Scoring = Scoring::LastSurvivor;
+    const ACTION_TEXT: Option<&'static str> = Some("Press A to jump");
+}
+
 // There is currently no good way to handle rotation of a child entity relative
 // to its parent. This is a hack to make it work (see also "cooldown_ui_rotation" stage).
 // See: https://github.com/bevyengine/bevy/issues/1780#issuecomment-939385391
@@ -286,35 +294,27 @@ fn update_cooldown_ui_transform(
     }
 }
 
+/// The disconnected players are despawned by the `MiniGamePlugin`, a
+/// `DeathEvent` is sent as well so that their cooldown UI is removed.
 fn handle_disconnect(
-    mut commands: Commands,
     disconnected_players: Res<DisconnectedPlayers>,
-    players_playing: Query<(Entity, &PlayerId)>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
+    players_playing: Query<&PlayerId>,
     mut death_event_writer: EventWriter<DeathEvent>,
 ) {
     if !disconnected_players.is_empty() {
-        for (entity, player_id) in players_playing.iter() {
+        for player_id in players_playing.iter() {
             if disconnected_players.contains(player_id) {
-                exit_event_writer.send(VoteEvent::Value(*player_id, false));
                 death_event_writer.send(DeathEvent(*player_id));
-                despawn_entity(&mut commands, entity);
             }
         }
     }
 }
 
-fn handle_winner(mut players: ResMut<Players>, players_alive_query: Query<&PlayerId>) {
-    if players_alive_query.iter().len() == 1 {
-        let player_id = players_alive_query.iter().next().unwrap();
-        if let Some(winning_player) = players.get_mut(player_id) {
-            winning_player.increment_score();
-        }
-    }
-}
-
+#[allow(clippy::too_many_arguments)]
 fn reset_game(
     mut commands: Commands,
+    mut config: ResMut<AchtungConfig>,
+    mut pending_config: ResMut<PendingConfig<AchtungConfig>>,
     mut players: ResMut<Players>,
     players_alive_query: Query<Entity, With<PlayerId>>,
     tail_query: Query<Entity, With<Tail>>,
@@ -322,6 +322,8 @@ fn reset_game(
     mut start_timer_query: Query<&mut StartTimer>,
 ) {
     if players_alive_query.iter().count() <= 1 {
+        pending_config.apply(&mut config);
+
         // Remove any players that are still alive and all tails.
         for entity in players_alive_query.iter().chain(tail_query.iter()) {
             despawn_entity(&mut commands, entity);
@@ -339,68 +341,14 @@ fn reset_game(
             player.reset_action();
 
             let rotation = rand::thread_rng().gen_range(0.0..TAU);
-            spawn_achtung_player(&mut commands, player, spawn_positions[idx].into(), rotation);
+            let pos = spawn_positions[idx].into();
+            spawn_achtung_player(&mut commands, &config, player, pos, rotation);
         }
 
-        start_timer_query.single_mut().unwrap().reset();
-    }
-}
-
-fn update_scoreboard(
-    mut commands: Commands,
-    fonts: Res<Fonts>,
-    players: Res<Players>,
-    score_text_query: Query<Entity, With<ScoreText>>,
-) {
-    if players.is_changed() {
-        for entity in score_text_query.iter() {
-            despawn_entity(&mut commands, entity);
-        }
-
-        let font = fonts.regular.clone();
-
-        let mut sorted_players = players.values().collect::<Vec<_>>();
-        sorted_players.sort_unstable_by_key(|p| Reverse(p.score()));
-
-        let mut text_sections = Vec::default();
-        text_sections.push(TextSection {
-            value: "SCORE".into(),
-            style: TextStyle {
-                font: font.clone(),
-                font_size: 32.0,
-                color: Color::WHITE,
-            },
-        });
-
-        for (idx, player) in sorted_players.iter().enumerate() {
-            let color = player.color().as_bevy();
-            text_sections.push(TextSection {
-                value: format!("\n{}. {}", idx + 1, player.score()),
-                style: TextStyle {
-                    font: font.clone(),
-                    font_size: 24.0,
-                    color,
-                },
-            });
-        }
-
-        let top_margin = 15.0;
-        let text_bundle = Text2dBundle {
-            text: Text {
-                sections: text_sections,
-                alignment: TextAlignment {
-                    vertical: VerticalAlign::Bottom,
-                    horizontal: HorizontalAlign::Center,
-                },
-            },
-            transform: Transform::from_xyz(0.0, (GAME_HEIGHT / 2.0) - top_margin, 0.0),
-            ..Default::default()
-        };
-
-        commands
-            .spawn_bundle(text_bundle)
-            .insert(AchtungGamePlugin)
-            .insert(ScoreText);
+        start_timer_query
+            .single_mut()
+            .unwrap()
+            .restart(config.start_timer_time);
     }
 }
 
@@ -436,7 +384,11 @@ fn handle_player_jump(
     )>,
     mut jump_event_reader: EventReader<JumpEvent>,
 ) {
-    for JumpEvent(event_player_id) in jump_event_reader.iter() {
+    for JumpEvent {
+        player_id: event_player_id,
+        ..
+    } in jump_event_reader.iter()
+    {
         // TODO: More performant way to get player_query from player_id?
         for (player_id, mut jump_timer, mut cooldown_timer, ..) in player_query.iter_mut() {
             if event_player_id == player_id && cooldown_timer.finished() {
@@ -707,6 +659,7 @@ fn handle_death(
     colliders: Query<&Colliders>,
     start_timer_query: Query<&StartTimer>,
     mut death_event_writer: EventWriter<DeathEvent>,
+    mut server_message_writer: EventWriter<ServerMessageEvent>,
 ) {
     if !start_timer_query.single().unwrap().finished() {
         return;
@@ -737,6 +690,8 @@ fn handle_death(
         for point in points {
             if colliders.is_collision(point.into(), player_is_jumping) {
                 death_event_writer.send(DeathEvent(*player_id));
+                server_message_writer
+                    .send(ServerMessageEvent::Player(*player_id, ServerMessage::Died));
                 despawn_entity(&mut commands, entity);
                 continue;
             }
@@ -744,72 +699,9 @@ fn handle_death(
     }
 }
 
-fn handle_player_input(
-    mut players: ResMut<Players>,
-    mut jump_event_writer: EventWriter<JumpEvent>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
-) {
-    if players.is_changed() {
-        for player in players.values_mut() {
-            if let Some(prev_action) = player.previous_action_once() {
-                match prev_action {
-                    ActionEvent::APressed => {
-                        jump_event_writer.send(JumpEvent(player.id()));
-                    }
-
-                    ActionEvent::BPressed => {
-                        exit_event_writer.send(VoteEvent::Flip(player.id()));
-                    }
-
-                    _ => (),
-                }
-            }
-        }
-    }
-}
-
-/// If a majority of the players wants to exit, we should return back to the menu.
-fn handle_exit_event(
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    mut game_state: ResMut<State<GameState>>,
-    mut exit_text: Query<&mut Text, With<ExitText>>,
-    mut player_exit_vote: Local<PlayerVote>,
-    mut exit_event_reader: EventReader<VoteEvent>,
-) {
-    let voted_amount_before = player_exit_vote.voted_amount();
-    let total_amount_before = player_exit_vote.total_amount();
-
-    exit_event_reader
-        .iter()
-        .for_each(|vote| player_exit_vote.register_vote(vote));
-
-    let voted_amount_after = player_exit_vote.len();
-    let total_amount_after = players.len();
-
-    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
-        player_exit_vote.set_total_amount(total_amount_after);
-
-        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount {
-            game_state.set(GameState::StartMenu).unwrap();
-        } else {
-            let font = fonts.regular.clone();
-            let font_size = 24.0;
-            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
-                EXIT_TEXT.into(),
-                &players,
-                &player_exit_vote,
-                required_amount,
-                font,
-                font_size,
-            );
-        }
-    }
-}
-
 fn move_achtung_players(
     time: Res<Time>,
+    config: Res<AchtungConfig>,
     players: Res<Players>,
     mut player_query: Query<(&PlayerId, &mut RigidBodyVelocity, &RigidBodyPosition)>,
     start_timer_query: Query<&StartTimer>,
@@ -818,36 +710,32 @@ fn move_achtung_players(
     for (player_id, mut velocity, pos) in player_query.iter_mut() {
         if let Some(player) = players.get(player_id) {
             // The rotation can still be changed even when the `start_timer` hasn't
-            // finished yet.
-            let movement_x = player.movement_x();
-            if movement_x > 0.0 {
-                velocity.angvel = -ACHTUNG_CONSTANT_TORQUE * delta_tick;
-            } else if movement_x < 0.0 {
-                velocity.angvel = ACHTUNG_CONSTANT_TORQUE * delta_tick;
-            } else {
-                velocity.angvel = 0.0;
-            }
+            // finished yet. Analog input gives a turn rate proportional to how
+            // far the stick is pushed.
+            velocity.angvel = -player.movement_x() * config.torque * delta_tick;
 
             if start_timer_query.single().unwrap().finished() {
                 let player_angle = pos.position.rotation.angle() + std::f32::consts::FRAC_PI_2;
                 let player_heading_vec = Vec2::new(player_angle.cos(), player_angle.sin());
 
-                velocity.linvel = (player_heading_vec * ACHTUNG_CONSTANT_SPEED * delta_tick).into();
+                velocity.linvel = (player_heading_vec * config.speed * delta_tick).into();
             }
         }
     }
 }
 
-fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
-    exit_event_writer.send(VoteEvent::Reset);
-}
-
-fn spawn_achtung_player(commands: &mut Commands, player: &Player, mut pos: Vec2, rotation: f32) {
+fn spawn_achtung_player(
+    commands: &mut Commands,
+    config: &AchtungConfig,
+    player: &Player,
+    mut pos: Vec2,
+    rotation: f32,
+) {
     let white_color = Color::rgb(1.0, 1.0, 1.0);
     let center = Vec2::ZERO;
     let mut radius = PLAYER_THICKNESS / 2.0;
 
-    let tail_spawn = TailSpawn::new(pos.x, pos.y);
+    let tail_spawn = TailSpawn::new(pos.x, pos.y, config.tail_spawn_time);
 
     let player_shape_bundle = GeometryBuilder::build_as(
         &Shape::circle(radius, center),
@@ -894,8 +782,8 @@ fn spawn_achtung_player(commands: &mut Commands, player: &Player, mut pos: Vec2,
         .insert_bundle(collider)
         .insert(player.id())
         .insert(tail_spawn)
-        .insert(JumpTimer::default())
-        .insert(JumpCooldownTimer::default())
+        .insert(JumpTimer::new(config.jump_time))
+        .insert(JumpCooldownTimer::new(config.jump_cooldown_time))
         .insert(AchtungGamePlugin)
         .insert(ColliderPositionSync::Discrete)
         .with_children(|parent| {
@@ -903,64 +791,6 @@ fn spawn_achtung_player(commands: &mut Commands, player: &Player, mut pos: Vec2,
         });
 }
 
-fn setup_screen_text(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>) {
-    let font = fonts.regular.clone();
-    let font_size = 24.0;
-    let font_color = Color::WHITE;
-
-    let empty_player_vote = PlayerVote::default();
-    let required_amount = (players.len() / 2) + 1;
-
-    let explotion_text = Text::with_section(
-        JUMP_TEXT,
-        TextStyle {
-            font: font.clone(),
-            font_size,
-            color: font_color,
-        },
-        TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    );
-
-    let explotion_text_bundle = Text2dBundle {
-        text: explotion_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(explotion_text_bundle)
-        .insert(AchtungGamePlugin);
-
-    let exit_text = Text {
-        sections: create_vote_text_sections(
-            EXIT_TEXT.into(),
-            &players,
-            &empty_player_vote,
-            required_amount,
-            font,
-            font_size,
-        ),
-        alignment: TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    };
-
-    let exit_text_bundle = Text2dBundle {
-        text: exit_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0 - font_size, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(exit_text_bundle)
-        .insert(ExitText)
-        .insert(AchtungGamePlugin);
-}
-
 fn setup_colliders(mut commands: Commands) {
     commands
         .spawn()
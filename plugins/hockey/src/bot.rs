@@ -0,0 +1,97 @@
+use bevy::{
+    math::Vec2,
+    prelude::{Query, ResMut, With},
+};
+use bevy_rapier2d::prelude::RigidBodyPosition;
+
+use skitspel::{BotInput, PlayerId, Players, Team, GAME_WIDTH, PLAYER_RADIUS, RAPIER_SCALE_FACTOR};
+use util_bevy::apply_bot_input;
+
+use crate::{DashTimer, Puck, PUCK_RADIUS};
+
+/// The distance (in pixels) between the puck and the point behind the puck
+/// that the bots move to before shooting.
+const BEHIND_PUCK_DISTANCE: f32 = PLAYER_RADIUS + PUCK_RADIUS + 20.0;
+
+/// The bots start to slow down when they are closer than this (in pixels) to
+/// the point that they are moving towards.
+const SLOW_DISTANCE: f32 = 100.0;
+
+/// How well (between 0.0 & 1.0) a bot needs to be lined up with the puck and
+/// the goal before it starts to shoot.
+const SHOOT_ALIGNMENT: f32 = 0.9;
+
+/// A bot dashes if it is lined up and closer than this (in pixels) to the puck.
+const DASH_DISTANCE: f32 = PLAYER_RADIUS + PUCK_RADIUS + 80.0;
+
+/// Moves the bots so that they get behind the puck (seen from the goal that
+/// they are attacking) and then shoots it towards the goal.
+pub(crate) fn handle_bots(
+    mut players: ResMut<Players>,
+    player_query: Query<(&PlayerId, &Team, &RigidBodyPosition, &DashTimer)>,
+    puck_query: Query<&RigidBodyPosition, With<Puck>>,
+) {
+    let puck_pos = if let Ok(puck_pos) = puck_query.single() {
+        rapier_to_pixels(puck_pos)
+    } else {
+        return;
+    };
+
+    for (player_id, team, pos, dash_timer) in player_query.iter() {
+        if !players.get(player_id).map_or(false, |p| p.is_bot()) {
+            continue;
+        }
+
+        let input = bot_input(
+            rapier_to_pixels(pos),
+            puck_pos,
+            *team,
+            dash_timer.finished(),
+        );
+        apply_bot_input(&mut players, *player_id, &input);
+    }
+}
+
+fn bot_input(bot_pos: Vec2, puck_pos: Vec2, team: Team, can_dash: bool) -> BotInput {
+    // The left team scores in the goal to the right and vice versa.
+    let goal_pos = match team {
+        Team::Left => Vec2::new(GAME_WIDTH / 2.0, 0.0),
+        Team::Right => Vec2::new(-GAME_WIDTH / 2.0, 0.0),
+    };
+
+    let shoot_dir = (goal_pos - puck_pos).normalize();
+    let to_puck = puck_pos - bot_pos;
+    let puck_distance = to_puck.length();
+
+    if puck_distance > f32::EPSILON && to_puck.dot(shoot_dir) / puck_distance > SHOOT_ALIGNMENT {
+        // Lined up behind the puck, go straight through it.
+        let target = puck_pos + shoot_dir * BEHIND_PUCK_DISTANCE;
+        let mut input = BotInput::towards(bot_pos.into(), target.into(), SLOW_DISTANCE);
+        input.a = can_dash && puck_distance < DASH_DISTANCE;
+        return input;
+    }
+
+    let behind_puck = puck_pos - shoot_dir * BEHIND_PUCK_DISTANCE;
+    let target = if (bot_pos - puck_pos).dot(shoot_dir) > 0.0 {
+        // The bot is between the puck and the goal. Go around the puck to not
+        // push it the wrong way.
+        let perpendicular = Vec2::new(-shoot_dir.y, shoot_dir.x);
+        let side = if (bot_pos - puck_pos).dot(perpendicular) >= 0.0 {
+            1.0
+        } else {
+            -1.0
+        };
+        behind_puck + perpendicular * side * BEHIND_PUCK_DISTANCE
+    } else {
+        behind_puck
+    };
+
+    BotInput::towards(bot_pos.into(), target.into(), SLOW_DISTANCE)
+}
+
+fn rapier_to_pixels(pos: &RigidBodyPosition) -> Vec2 {
+    Vec2::new(
+        pos.position.translation.x * RAPIER_SCALE_FACTOR,
+        pos.position.translation.y * RAPIER_SCALE_FACTOR,
+    )
+}
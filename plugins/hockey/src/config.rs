@@ -0,0 +1,60 @@
+use serde::Deserialize;
+
+use skitspel::ACCEL_AMOUNT;
+use util_bevy::{ensure_positive, Config, ConfigError, MatchConfig, MatchRules, StartTimerConfig};
+
+use crate::{DASH_COOLDOWN_TIME, MATCH_POINTS, START_TIMER_TIME};
+
+/// The tuning of the "Hockey" game. Read from the `[hockey]` section of the
+/// config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HockeyConfig {
+    /// How long the timer between rounds are in seconds.
+    pub start_timer_time: usize,
+    /// The rules deciding when a match is over (the `[hockey.match]` table).
+    #[serde(rename = "match")]
+    pub match_rules: MatchRules,
+    /// How long a player has to wait between dashes (seconds).
+    pub dash_cooldown_time: f32,
+    /// The impulse applied to a player when dashing.
+    pub dash_impulse: f32,
+}
+
+impl Default for HockeyConfig {
+    fn default() -> Self {
+        Self {
+            start_timer_time: START_TIMER_TIME,
+            match_rules: MatchRules::first_to(MATCH_POINTS),
+            dash_cooldown_time: DASH_COOLDOWN_TIME,
+            dash_impulse: ACCEL_AMOUNT,
+        }
+    }
+}
+
+impl Config for HockeyConfig {
+    const SECTION: &'static str = "hockey";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure_positive(
+            Self::SECTION,
+            "start_timer_time",
+            self.start_timer_time as f32,
+        )?;
+        self.match_rules.validate(Self::SECTION)?;
+        ensure_positive(Self::SECTION, "dash_cooldown_time", self.dash_cooldown_time)?;
+        ensure_positive(Self::SECTION, "dash_impulse", self.dash_impulse)
+    }
+}
+
+impl StartTimerConfig for HockeyConfig {
+    fn start_timer_time(&self) -> usize {
+        self.start_timer_time
+    }
+}
+
+impl MatchConfig for HockeyConfig {
+    fn match_rules(&self) -> &MatchRules {
+        &self.match_rules
+    }
+}
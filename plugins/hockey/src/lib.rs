@@ -7,13 +7,11 @@ use bevy::{
     core::{Time, Timer},
     math::{Quat, Vec2},
     prelude::{
-        AppBuilder, Assets, BuildChildren, Changed, Children, Color, Commands, CoreStage, Entity,
-        EventReader, EventWriter, GlobalTransform, Handle, HorizontalAlign, IntoSystem, Local,
-        Mesh, MeshBundle, ParallelSystemDescriptorCoercion, Plugin, Query, QuerySet,
-        RenderPipelines, Res, ResMut, State, SystemSet, SystemStage, Transform, VerticalAlign,
-        With,
+        AppBuilder, Assets, BuildChildren, Children, Color, Commands, CoreStage, Entity,
+        EventReader, GlobalTransform, IntoSystem, Mesh, MeshBundle,
+        ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines, Res, ResMut, SystemSet,
+        SystemStage, Transform, With,
     },
-    text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
 use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
 use bevy_rapier2d::{
@@ -27,16 +25,24 @@ use bevy_rapier2d::{
 use rand::{prelude::SliceRandom, Rng};
 
 use skitspel::{
-    ActionEvent, ConnectedPlayers, DisconnectedPlayers, GameState, Player, PlayerId, Players,
-    ACCEL_AMOUNT, GAME_HEIGHT, GAME_WIDTH, PLAYER_RADIUS, RAPIER_SCALE_FACTOR,
+    ConnectedPlayers, GameState, Player, PlayerId, Players, Team, GAME_HEIGHT, GAME_WIDTH,
+    PLAYER_RADIUS, RAPIER_SCALE_FACTOR,
 };
 use util_bevy::{
-    create_vote_text_sections, despawn_entity, despawn_system, handle_start_timer,
-    setup_start_timer, AsBevyColor, Fonts, PlayerVote, Shape, StartTimer, VoteEvent,
+    balanced_teams, despawn_entity, handle_start_timer, reshuffled_teams, setup_start_timer,
+    team_for_new_player, ActionPressedEvent, MiniGame, MiniGamePlugin, PendingConfig,
+    ReshuffleTeamsEvent, ScoreCount, Scoring, Shape, StartTimer, SwitchTeamEvent,
 };
-use util_rapier::{create_circle_points, create_path_with_thickness, move_players, spawn_player};
+use util_rapier::{
+    create_circle_points, create_path_with_thickness, move_players, spawn_player, MovementConfig,
+};
+
+pub use config::HockeyConfig;
+
+mod bot;
+mod config;
 
-const GAME_STATE: GameState = GameState::HockeyGame;
+const GAME_STATE: GameState = HockeyGamePlugin::GAME_STATE;
 
 const SPAWN_POSITIONS_LEFT: [(f32, f32); 5] = [
     (-GAME_WIDTH * 0.375, GAME_HEIGHT * 0.25),
@@ -54,33 +60,21 @@ const SPAWN_POSITIONS_RIGHT: [(f32, f32); 5] = [
     (GAME_WIDTH * 0.25, 0.0),
 ];
 
-/// Component used to tag the text containing the score.
-struct LeftScoreText;
-struct RightScoreText;
-
-const DASH_TEXT: &str = "Press A to dash\n";
-const EXIT_TEXT: &str = "Press B to go back to main menu";
-
 /// The height and width of the dash cooldown UI under the players.
 const DASH_COOLDOWN_WIDTH: f32 = 100.0;
 const DASH_COOLDOWN_HEIGHT: f32 = 10.0;
 
-/// How long the timer between rounds are in seconds.
-const START_TIMER_TIME: usize = 3;
+/// The radius of the puck.
+const PUCK_RADIUS: f32 = 20.0;
 
-/// Used to tag which team a player belongs to.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Team {
-    Left,
-    Right,
-}
+/// The default time of the timer between rounds in seconds.
+const START_TIMER_TIME: usize = 3;
 
-/// Component used to tag the text containing which players belongs to which
-/// team.
-struct TeamText;
+/// The default amount of points needed to win a match.
+const MATCH_POINTS: usize = 5;
 
-/// Tag used on the exit text.
-struct ExitText;
+/// The default time a player has to wait between dashes (seconds).
+const DASH_COOLDOWN_TIME: f32 = 2.0;
 
 /// Tag used on the puck.
 struct Puck;
@@ -91,12 +85,8 @@ struct Goal;
 /// Tag used od rink walls & corners.
 struct Wall;
 
-/// Component used to keep track of the current score. The left usize is the
-/// score of the left team and the right usize is the score of the right team.
-struct ScoreCount(usize, usize);
-
-/// Event created when player with ID `PlayerId` dashes.
-struct DashEvent(PlayerId);
+/// Event created when a player dashes.
+type DashEvent = ActionPressedEvent<HockeyGamePlugin>;
 
 /// Timer used to restrict how often a player can dash. This timer will started
 /// when a dash is done and a player isn't allowed to dash again until this
@@ -120,9 +110,9 @@ impl DerefMut for DashTimer {
     }
 }
 
-impl Default for DashTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(2.0, false))
+impl DashTimer {
+    fn new(cooldown_time: f32) -> Self {
+        Self(Timer::from_seconds(cooldown_time, false))
     }
 }
 
@@ -131,34 +121,40 @@ pub struct HockeyGamePlugin;
 
 impl Plugin for HockeyGamePlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_event::<DashEvent>()
+        app.add_plugin(MiniGamePlugin::<HockeyGamePlugin>::default())
+            .init_resource::<HockeyConfig>()
+            .init_resource::<PendingConfig<HockeyConfig>>()
+            .init_resource::<MovementConfig>()
+            .init_resource::<PendingConfig<MovementConfig>>()
             .add_system_set(
                 SystemSet::on_enter(GAME_STATE)
-                    .with_system(reset_votes.system())
                     .with_system(setup_map.system())
-                    .with_system(setup_score.system())
-                    .with_system(setup_start_timer::<HockeyGamePlugin, START_TIMER_TIME>.system())
-                    .with_system(setup_players.system().label("players"))
-                    .with_system(setup_screen_text.system().after("players")),
+                    .with_system(setup_start_timer::<HockeyGamePlugin, HockeyConfig>.system())
+                    .with_system(setup_players.system()),
             )
             .add_system_set(
                 SystemSet::on_update(GAME_STATE)
-                    .with_system(handle_disconnect.system().label("vote"))
                     .with_system(handle_connect.system().label("vote"))
-                    .with_system(handle_player_input.system().label("vote").label("dash"))
-                    .with_system(handle_exit_event.system().after("vote"))
                     .with_system(handle_goal.system().label("goal"))
-                    .with_system(update_scoreboard.system())
+                    .with_system(
+                        handle_switch_team
+                            .system()
+                            .after("team_selection")
+                            .before("goal"),
+                    )
+                    .with_system(
+                        handle_reshuffle
+                            .system()
+                            .after("team_selection")
+                            .before("goal"),
+                    )
                     .with_system(handle_start_timer.system().label("start").after("goal"))
-                    .with_system(move_players.system())
-                    .with_system(update_dash_timers.system().after("dash").label("timer"))
-                    .with_system(handle_player_dash.system().after("dash").after("timer"))
+                    .with_system(bot::handle_bots.system().label("bots").after("vote"))
+                    .with_system(move_players.system().after("bots"))
+                    .with_system(update_dash_timers.system().after("vote").label("timer"))
+                    .with_system(handle_player_dash.system().after("vote").after("timer"))
                     .with_system(update_dash_ui.system().after("timer").before("goal")),
             )
-            .add_system_set(
-                SystemSet::on_exit(GAME_STATE)
-                    .with_system(despawn_system::<HockeyGamePlugin>.system()),
-            )
             .add_stage_after(
                 CoreStage::PostUpdate,
                 "dash_ui_rotation",
@@ -168,6 +164,18 @@ impl Plugin for HockeyGamePlugin {
     }
 }
 
+impl MiniGame for HockeyGamePlugin {
+    type Config = HockeyConfig;
+
+    const ID: &'static str = "hockey";
+    const NAME: &'static str = "Hockey";
+    const DESCRIPTION: &'static str =
+        "Two teams, one puck. Dash into the puck to score in the goal of the other team.";
+    const SCREENSHOT: &'static [u8] = include_bytes!("..\\assets\\hockey.png");
+    const SCORING: Scoring = Scoring::Team;
+    const ACTION_TEXT: Option<&'static str> = Some("Press A to dash");
+}
+
 // There is currently no good way to handle rotation of a child entity relative
 // to its parent. This is a hack to make it work (see also "dash_ui_rotation" stage).
 // See: https://github.com/bevyengine/bevy/issues/1780#issuecomment-939385391
@@ -188,90 +196,82 @@ fn update_dash_ui_transform(
 
 fn handle_connect(
     mut commands: Commands,
+    config: Res<HockeyConfig>,
+    players: Res<Players>,
     connected_players: Res<ConnectedPlayers>,
-    players_playing: Query<&Team, With<PlayerId>>,
+    players_playing: Query<(&PlayerId, &Team)>,
 ) {
-    for player in connected_players.values() {
-        let mut left_team_count = 0;
-        let mut right_team_count = 0;
-        for team in players_playing.iter() {
-            match team {
-                Team::Left => left_team_count += 1,
-                Team::Right => right_team_count += 1,
-            }
-        }
-
-        let (team, spawn_pos) = if left_team_count < right_team_count {
-            let idx = rand::thread_rng().gen_range(0..SPAWN_POSITIONS_LEFT.len());
-            (Team::Left, SPAWN_POSITIONS_LEFT[idx])
-        } else {
-            let idx = rand::thread_rng().gen_range(0..SPAWN_POSITIONS_RIGHT.len());
-            (Team::Right, SPAWN_POSITIONS_RIGHT[idx])
-        };
+    let mut teams = players_playing
+        .iter()
+        .map(|(player_id, team)| (*player_id, *team))
+        .collect::<Vec<_>>();
 
-        spawn_hockey_player(&mut commands, player, spawn_pos.into(), team);
+    for player in connected_players.values() {
+        let team = team_for_new_player(&players, &teams);
+        teams.push((player.id(), team));
+        spawn_hockey_player(&mut commands, &config, player, random_spawn_pos(team), team);
     }
 }
 
-fn handle_disconnect(
+/// Respawns the players that have picked the other team during the team
+/// selection on the side of their new team.
+fn handle_switch_team(
     mut commands: Commands,
-    disconnected_players: Res<DisconnectedPlayers>,
+    config: Res<HockeyConfig>,
+    players: Res<Players>,
     players_playing: Query<(Entity, &PlayerId)>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
+    mut switch_team_event_reader: EventReader<SwitchTeamEvent<HockeyGamePlugin>>,
 ) {
-    if !disconnected_players.is_empty() {
-        for (entity, player_id) in players_playing.iter() {
-            if disconnected_players.contains(player_id) {
-                exit_event_writer.send(VoteEvent::Value(*player_id, false));
-                despawn_entity(&mut commands, entity);
-            }
+    for switch_team_event in switch_team_event_reader.iter() {
+        let player_id = switch_team_event.player_id;
+        let player = match players.get(&player_id) {
+            Some(player) => player,
+            None => continue,
+        };
+
+        for (entity, _) in players_playing.iter().filter(|(_, id)| **id == player_id) {
+            despawn_entity(&mut commands, entity);
         }
+
+        let team = switch_team_event.team;
+        spawn_hockey_player(&mut commands, &config, player, random_spawn_pos(team), team);
     }
 }
 
-#[allow(clippy::type_complexity, clippy::too_many_arguments)]
-fn update_scoreboard(
+/// Returns a random spawn position on the side of team `team`.
+fn random_spawn_pos(team: Team) -> Vec2 {
+    let spawn_positions = match team {
+        Team::Left => &SPAWN_POSITIONS_LEFT,
+        Team::Right => &SPAWN_POSITIONS_RIGHT,
+    };
+    let idx = rand::thread_rng().gen_range(0..spawn_positions.len());
+    spawn_positions[idx].into()
+}
+
+/// Splits the players into new teams when the players have voted to reshuffle
+/// the teams during the team selection. The players are respawned on the side
+/// of their new team.
+fn handle_reshuffle(
     mut commands: Commands,
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    score_change: Query<&ScoreCount, Changed<ScoreCount>>,
-    mut score_text_query: QuerySet<(
-        Query<&mut Text, With<LeftScoreText>>,
-        Query<&mut Text, With<RightScoreText>>,
-    )>,
-    players_playing: Query<(&PlayerId, &Team)>,
-    old_team_text: Query<Entity, With<TeamText>>,
-    mut players_playing_count: Local<usize>,
+    config: Res<HockeyConfig>,
+    mut players: ResMut<Players>,
+    players_playing: Query<(Entity, &PlayerId, &Team)>,
+    mut reshuffle_event_reader: EventReader<ReshuffleTeamsEvent<HockeyGamePlugin>>,
 ) {
-    for new_score_count in score_change.iter() {
-        let ScoreCount(left_score, right_score) = new_score_count;
-
-        let mut left_score_text = score_text_query.q0_mut().single_mut().unwrap();
-        if let Some(text_section) = left_score_text.sections.first_mut() {
-            text_section.value = left_score.to_string();
-        }
-
-        let mut right_score_text = score_text_query.q1_mut().single_mut().unwrap();
-        if let Some(text_section) = right_score_text.sections.first_mut() {
-            text_section.value = right_score.to_string();
-        }
+    if reshuffle_event_reader.iter().count() == 0 {
+        return;
     }
 
-    let cur_players_playing_count = players_playing.iter().count();
-    if *players_playing_count != cur_players_playing_count || score_change.iter().count() > 0 {
-        *players_playing_count = cur_players_playing_count;
-
-        let font = fonts.bold.clone();
-        let font_size = 128.0;
-        update_team_text(
-            &mut commands,
-            &players,
-            players_playing,
-            old_team_text,
-            font,
-            font_size,
-        );
+    let current_teams = players_playing
+        .iter()
+        .map(|(_, player_id, team)| (*player_id, *team))
+        .collect::<Vec<_>>();
+    for (entity, ..) in players_playing.iter() {
+        despawn_entity(&mut commands, entity);
     }
+
+    let teams = reshuffled_teams(&players, &current_teams);
+    spawn_teams(&mut commands, &config, &mut players, &teams);
 }
 
 fn update_dash_timers(
@@ -294,6 +294,7 @@ fn update_dash_timers(
 ///  2. If at leasy one direction key is pressed at the moment, the player will
 ///     dash in that direction.
 fn handle_player_dash(
+    config: Res<HockeyConfig>,
     players: Res<Players>,
     mut player_query: Query<(
         &PlayerId,
@@ -308,13 +309,21 @@ fn handle_player_dash(
         return;
     }
 
-    for DashEvent(event_player_id) in dash_event_reader.iter() {
+    for DashEvent {
+        player_id: event_player_id,
+        ..
+    } in dash_event_reader.iter()
+    {
         // TODO: More performant way to get player_query from player_id?
         for (player_id, mut timer, mut velocity, mass) in player_query.iter_mut() {
             if event_player_id == player_id && timer.finished() {
                 if let Some(player) = players.get(player_id) {
-                    let heading_vec = if player.movement_x() != 0.0 || player.movement_y() != 0.0 {
-                        Vec2::new(player.movement_x(), player.movement_y()).normalize()
+                    let (movement_x, movement_y) = player.movement_vec();
+                    let movement_vec = Vec2::new(movement_x, movement_y);
+                    let heading_vec = if movement_vec != Vec2::ZERO {
+                        // The dash is proportional to how far an analog stick is
+                        // pushed. Digital input always gives a full dash.
+                        movement_vec.normalize() * movement_vec.length().min(1.0)
                     } else if velocity.linvel.x == 0.0 && velocity.linvel.y == 0.0 {
                         // The player is standing completly still, no idea in
                         // which direction to dash & would cause NaN when
@@ -323,7 +332,7 @@ fn handle_player_dash(
                     } else {
                         Vec2::from(velocity.linvel).normalize()
                     };
-                    velocity.apply_impulse(mass, (heading_vec * ACCEL_AMOUNT).into());
+                    velocity.apply_impulse(mass, (heading_vec * config.dash_impulse).into());
                     timer.reset();
                 }
             }
@@ -395,6 +404,10 @@ fn update_dash_ui(
 #[allow(clippy::too_many_arguments)]
 fn handle_goal(
     mut commands: Commands,
+    mut config: ResMut<HockeyConfig>,
+    mut pending_config: ResMut<PendingConfig<HockeyConfig>>,
+    mut movement_config: ResMut<MovementConfig>,
+    mut pending_movement_config: ResMut<PendingConfig<MovementConfig>>,
     mut intersection_event: EventReader<IntersectionEvent>,
     mut players: ResMut<Players>,
     players_playing: Query<(Entity, &PlayerId, &Team)>,
@@ -431,6 +444,9 @@ fn handle_goal(
                 }
             };
 
+            pending_config.apply(&mut config);
+            pending_movement_config.apply(&mut movement_config);
+
             // Despawn the old players and respawn them with new shapes and on
             // their side of the rink.
             for (entity, ..) in players_playing.iter() {
@@ -446,7 +462,11 @@ fn handle_goal(
             for (_, player_id, team) in players_playing.iter() {
                 if let Some(player) = players.get_mut(player_id) {
                     if *team == scoring_team {
-                        player.increment_score();
+                        player.increment_score(HockeyGamePlugin::ID);
+                        player.record_stats(HockeyGamePlugin::ID, |stats| {
+                            stats.round_wins += 1;
+                            stats.goals += 1;
+                        });
                     }
 
                     let spawn_pos = match team {
@@ -455,7 +475,7 @@ fn handle_goal(
                     };
 
                     player.reset_action();
-                    spawn_hockey_player(&mut commands, player, spawn_pos.into(), *team);
+                    spawn_hockey_player(&mut commands, &config, player, spawn_pos.into(), *team);
                 }
             }
 
@@ -465,169 +485,21 @@ fn handle_goal(
             velocity.linvel = Vec2::ZERO.into();
             velocity.angvel = 0.0;
 
-            start_timer_query.single_mut().unwrap().reset();
+            start_timer_query
+                .single_mut()
+                .unwrap()
+                .restart(config.start_timer_time);
         }
     }
 }
 
-fn handle_player_input(
-    mut players: ResMut<Players>,
-    mut dash_event_writer: EventWriter<DashEvent>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
-) {
-    if players.is_changed() {
-        for player in players.values_mut() {
-            if let Some(prev_action) = player.previous_action_once() {
-                match prev_action {
-                    ActionEvent::APressed => {
-                        dash_event_writer.send(DashEvent(player.id()));
-                    }
-
-                    ActionEvent::BPressed => {
-                        exit_event_writer.send(VoteEvent::Flip(player.id()));
-                    }
-
-                    _ => (),
-                }
-            }
-        }
-    }
-}
-
-/// If a majority of the players wants to exit, we should return back to the menu.
-fn handle_exit_event(
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    mut game_state: ResMut<State<GameState>>,
-    mut exit_text: Query<&mut Text, With<ExitText>>,
-    mut player_exit_vote: Local<PlayerVote>,
-    mut exit_event_reader: EventReader<VoteEvent>,
-) {
-    let voted_amount_before = player_exit_vote.voted_amount();
-    let total_amount_before = player_exit_vote.total_amount();
-
-    exit_event_reader
-        .iter()
-        .for_each(|vote| player_exit_vote.register_vote(vote));
-
-    let voted_amount_after = player_exit_vote.len();
-    let total_amount_after = players.len();
-
-    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
-        player_exit_vote.set_total_amount(total_amount_after);
-
-        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount {
-            game_state.set(GameState::StartMenu).unwrap();
-        } else {
-            let font = fonts.regular.clone();
-            let font_size = 24.0;
-            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
-                EXIT_TEXT.into(),
-                &players,
-                &player_exit_vote,
-                required_amount,
-                font,
-                font_size,
-            );
-        }
-    }
-}
-
-fn update_team_text(
+fn spawn_hockey_player(
     commands: &mut Commands,
-    players: &Players,
-    players_playing: Query<(&PlayerId, &Team)>,
-    old_team_text: Query<Entity, With<TeamText>>,
-    font: Handle<Font>,
-    font_size: f32,
+    config: &HockeyConfig,
+    player: &Player,
+    spawn_pos: Vec2,
+    team: Team,
 ) {
-    for entity in old_team_text.iter() {
-        despawn_entity(commands, entity);
-    }
-
-    let mut left_team_player_ids = players_playing
-        .iter()
-        .filter(|(_, team)| matches!(team, Team::Left))
-        .map(|(id, _)| *id)
-        .collect::<Vec<_>>();
-    left_team_player_ids.sort_unstable();
-
-    let left_team_text_sections =
-        create_team_text_sections(&left_team_player_ids, players, font.clone(), font_size);
-
-    let left_team_text_bundle = Text2dBundle {
-        text: Text {
-            sections: left_team_text_sections,
-            alignment: TextAlignment {
-                vertical: VerticalAlign::Bottom,
-                horizontal: HorizontalAlign::Center,
-            },
-        },
-        transform: Transform::from_xyz(-GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0 - font_size / 2.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(left_team_text_bundle)
-        .insert(TeamText)
-        .insert(HockeyGamePlugin);
-
-    let right_team_player_ids = players_playing
-        .iter()
-        .filter(|(_, team)| matches!(team, Team::Right))
-        .map(|(id, _)| *id)
-        .collect::<Vec<_>>();
-    left_team_player_ids.sort_unstable();
-
-    let right_team_text_sections =
-        create_team_text_sections(&right_team_player_ids, players, font, font_size);
-
-    let right_team_text_bundle = Text2dBundle {
-        text: Text {
-            sections: right_team_text_sections,
-            alignment: TextAlignment {
-                vertical: VerticalAlign::Bottom,
-                horizontal: HorizontalAlign::Center,
-            },
-        },
-        transform: Transform::from_xyz(GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0 - font_size / 2.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(right_team_text_bundle)
-        .insert(TeamText)
-        .insert(HockeyGamePlugin);
-}
-
-fn create_team_text_sections(
-    team_player_ids: &[PlayerId],
-    players: &Players,
-    font: Handle<Font>,
-    font_size: f32,
-) -> Vec<TextSection> {
-    let mut text_sections = Vec::with_capacity(team_player_ids.len());
-    for player_id in team_player_ids {
-        if let Some(player) = players.get(player_id) {
-            text_sections.push(TextSection {
-                value: "•".into(),
-                style: TextStyle {
-                    font: font.clone(),
-                    font_size,
-                    color: player.color().as_bevy(),
-                },
-            });
-        }
-    }
-    text_sections
-}
-
-fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
-    exit_event_writer.send(VoteEvent::Reset);
-}
-
-fn spawn_hockey_player(commands: &mut Commands, player: &Player, spawn_pos: Vec2, team: Team) {
     let red_color = Color::rgb(1.0, 0.1, 0.1);
 
     let mut entity_commands = spawn_player(
@@ -650,132 +522,40 @@ fn spawn_hockey_player(commands: &mut Commands, player: &Player, spawn_pos: Vec2
     });
 
     entity_commands
-        .insert(DashTimer::default())
+        .insert(DashTimer::new(config.dash_cooldown_time))
         .insert(team)
         .insert(HockeyGamePlugin);
 }
 
-fn setup_screen_text(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>) {
-    let bold_font = fonts.bold.clone();
-    let bold_font_size = 128.0;
-    let regular_font = fonts.regular.clone();
-    let regular_font_size = 24.0;
-    let font_color = Color::WHITE;
-
-    let empty_player_vote = PlayerVote::default();
-    let required_amount = (players.len() / 2) + 1;
-
-    let dash_text = Text::with_section(
-        DASH_TEXT,
-        TextStyle {
-            font: regular_font.clone(),
-            font_size: regular_font_size,
-            color: font_color,
-        },
-        TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    );
-
-    let dash_text_bundle = Text2dBundle {
-        text: dash_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(dash_text_bundle)
-        .insert(HockeyGamePlugin);
-
-    let exit_text = Text {
-        sections: create_vote_text_sections(
-            EXIT_TEXT.into(),
-            &players,
-            &empty_player_vote,
-            required_amount,
-            regular_font,
-            regular_font_size,
-        ),
-        alignment: TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    };
-
-    let exit_text_bundle = Text2dBundle {
-        text: exit_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0 - regular_font_size, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(exit_text_bundle)
-        .insert(ExitText)
-        .insert(HockeyGamePlugin);
-
-    let zero_score_text = Text::with_section(
-        "0",
-        TextStyle {
-            font: bold_font,
-            font_size: bold_font_size,
-            color: Color::WHITE,
-        },
-        TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    );
-
-    let left_score_text_bundle = Text2dBundle {
-        text: zero_score_text.clone(),
-        transform: Transform::from_xyz(-GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(left_score_text_bundle)
-        .insert(LeftScoreText)
-        .insert(HockeyGamePlugin);
-
-    let right_score_text_bundle = Text2dBundle {
-        text: zero_score_text,
-        transform: Transform::from_xyz(GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(right_score_text_bundle)
-        .insert(RightScoreText)
-        .insert(HockeyGamePlugin);
-}
-
-fn setup_score(mut commands: Commands) {
-    commands
-        .spawn()
-        .insert(ScoreCount(0, 0))
-        .insert(HockeyGamePlugin);
+/// Splits the players into two teams with ratings as even as possible.
+fn setup_players(mut commands: Commands, config: Res<HockeyConfig>, mut players: ResMut<Players>) {
+    let player_ids = players.keys().cloned().collect::<Vec<_>>();
+    let teams = balanced_teams(&players, &player_ids);
+    spawn_teams(&mut commands, &config, &mut players, &teams);
 }
 
-fn setup_players(mut commands: Commands, mut players: ResMut<Players>) {
+/// Spawns the players in `teams` on random positions on the side of their team.
+fn spawn_teams(
+    commands: &mut Commands,
+    config: &HockeyConfig,
+    players: &mut Players,
+    teams: &[(PlayerId, Team)],
+) {
     let mut left_spawn_positions = SPAWN_POSITIONS_LEFT.to_vec();
     left_spawn_positions.shuffle(&mut rand::thread_rng());
     let mut right_spawn_positions = SPAWN_POSITIONS_RIGHT.to_vec();
     right_spawn_positions.shuffle(&mut rand::thread_rng());
 
-    let mut players_shuffled = players.values_mut().collect::<Vec<_>>();
-    players_shuffled.shuffle(&mut rand::thread_rng());
-
-    let half_idx = players_shuffled.len() / 2;
-    for (i, player) in players_shuffled.into_iter().enumerate() {
-        let (team, spawn_pos) = if i < half_idx {
-            (Team::Left, left_spawn_positions.pop().unwrap())
-        } else {
-            (Team::Right, right_spawn_positions.pop().unwrap())
-        };
+    for (player_id, team) in teams {
+        if let Some(player) = players.get_mut(player_id) {
+            let spawn_pos = match team {
+                Team::Left => left_spawn_positions.pop().unwrap(),
+                Team::Right => right_spawn_positions.pop().unwrap(),
+            };
 
-        player.reset_action();
-        spawn_hockey_player(&mut commands, player, spawn_pos.into(), team);
+            player.reset_action();
+            spawn_hockey_player(commands, config, player, spawn_pos.into(), *team);
+        }
     }
 }
 
@@ -981,7 +761,7 @@ fn setup_map(
         Team::Right,
     );
 
-    spawn_puck(&mut commands, Vec2::ZERO, 20.0, white_color);
+    spawn_puck(&mut commands, Vec2::ZERO, PUCK_RADIUS, white_color);
 }
 
 fn spawn_rink_wall(
@@ -0,0 +1,439 @@
+use std::cmp::Reverse;
+
+use bevy::{
+    math::{Quat, Vec2, Vec3},
+    prelude::{
+        ChangeTrackers, Color, Commands, EventReader, EventWriter, Handle, HorizontalAlign,
+        IntoSystem, Local, ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, State,
+        SystemSet, Transform, VerticalAlign, With,
+    },
+    text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
+};
+use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
+
+use skitspel::{
+    ActionEvent, DisconnectedPlayers, GameState, GameStats, Players, Profiles, GAME_HEIGHT,
+    GAME_WIDTH,
+};
+use util_bevy::{
+    create_vote_text_sections, despawn_system, AsBevyColor, Fonts, Games, PlayerVote, Shape,
+    VoteEvent,
+};
+
+const GAME_STATE: GameState = GameState::Leaderboard;
+
+const HEADER_TEXT: &str = "Leaderboard";
+const EXIT_TEXT: &str = "Press B to go back to main menu";
+
+/// The maximum amount of players shown in a ranking.
+const RANKING_SIZE: usize = 10;
+
+/// The color of the players in the all-time ranking that aren't connected.
+const OFFLINE_COLOR: Color = Color::rgb(0.6, 0.6, 0.6);
+
+/// Will be used as a tag/component added to all entities related to the
+/// leaderboard.
+#[derive(Clone, Default)]
+pub struct LeaderboardPlugin;
+
+/// Component containing the index (inside `Games`) of the game that the
+/// rankings are currently shown for.
+struct SelectedGame(usize);
+
+/// Tag used on the text containing the name of the selected game.
+struct GameNameText;
+
+/// Tags used on the texts containing the all-time & session rankings.
+struct AllTimeText;
+struct SessionText;
+
+/// Tag used on the exit text.
+struct ExitText;
+
+/// A row in a ranking.
+struct RankingEntry {
+    name: String,
+    color: Color,
+    stats: GameStats,
+}
+
+impl Plugin for LeaderboardPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<Profiles>()
+            .add_system_set(
+                SystemSet::on_enter(GAME_STATE)
+                    .with_system(reset_votes.system())
+                    .with_system(setup_leaderboard.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GAME_STATE)
+                    .with_system(handle_disconnect.system().label("vote"))
+                    .with_system(handle_player_input.system().label("vote"))
+                    .with_system(handle_exit_event.system().after("vote"))
+                    .with_system(update_rankings.system().after("vote")),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GAME_STATE)
+                    .with_system(despawn_system::<LeaderboardPlugin>.system()),
+            );
+    }
+}
+
+fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
+    exit_event_writer.send(VoteEvent::Reset);
+}
+
+fn handle_disconnect(
+    disconnected_players: Res<DisconnectedPlayers>,
+    mut exit_event_writer: EventWriter<VoteEvent>,
+) {
+    for player_id in disconnected_players.iter() {
+        exit_event_writer.send(VoteEvent::Value(*player_id, false));
+    }
+}
+
+/// Pressing left/right changes the game that the rankings are shown for and
+/// pressing B flips the exit vote of the player.
+fn handle_player_input(
+    mut players: ResMut<Players>,
+    games: Res<Games>,
+    mut selected_game_query: Query<&mut SelectedGame>,
+    mut exit_event_writer: EventWriter<VoteEvent>,
+) {
+    if players.is_changed() {
+        for player in players.values_mut() {
+            for prev_action in player.previous_actions_once() {
+                match prev_action {
+                    ActionEvent::BPressed => {
+                        exit_event_writer.send(VoteEvent::Flip(player.id()));
+                    }
+
+                    ActionEvent::LeftPressed | ActionEvent::RightPressed => {
+                        if games.is_empty() {
+                            continue;
+                        }
+
+                        let mut selected_game = selected_game_query.single_mut().unwrap();
+                        let SelectedGame(ref mut idx) = *selected_game;
+                        if let ActionEvent::LeftPressed = prev_action {
+                            *idx = if *idx == 0 { games.len() - 1 } else { *idx - 1 };
+                        } else {
+                            *idx = if *idx == games.len() - 1 { 0 } else { *idx + 1 };
+                        }
+                    }
+
+                    _ => (),
+                }
+            }
+        }
+    }
+}
+
+/// If a majority of the players wants to exit, we should return back to the menu.
+fn handle_exit_event(
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+    mut game_state: ResMut<State<GameState>>,
+    mut exit_text: Query<&mut Text, With<ExitText>>,
+    mut player_exit_vote: Local<PlayerVote>,
+    mut exit_event_reader: EventReader<VoteEvent>,
+) {
+    let voted_amount_before = player_exit_vote.voted_amount();
+    let total_amount_before = player_exit_vote.total_amount();
+
+    exit_event_reader
+        .iter()
+        .for_each(|vote| player_exit_vote.register_vote(vote));
+
+    let voted_amount_after = player_exit_vote.len();
+    let total_amount_after = players.human_count();
+
+    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
+        player_exit_vote.set_total_amount(total_amount_after);
+
+        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
+        if voted_amount_after >= required_amount {
+            game_state.set(GameState::StartMenu).unwrap();
+        } else {
+            let font = fonts.regular.clone();
+            let font_size = 24.0;
+            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
+                EXIT_TEXT.into(),
+                &players,
+                &player_exit_vote,
+                required_amount,
+                font,
+                font_size,
+            );
+        }
+    }
+}
+
+/// Updates the rankings when another game is selected or when the statistics
+/// of the players changes.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+fn update_rankings(
+    fonts: Res<Fonts>,
+    games: Res<Games>,
+    players: Res<Players>,
+    profiles: Res<Profiles>,
+    selected_game_query: Query<(&SelectedGame, ChangeTrackers<SelectedGame>)>,
+    mut game_name_text_query: Query<&mut Text, With<GameNameText>>,
+    mut all_time_text_query: Query<&mut Text, With<AllTimeText>>,
+    mut session_text_query: Query<&mut Text, With<SessionText>>,
+) {
+    let (SelectedGame(idx), selected_game_tracker) = match selected_game_query.single() {
+        Ok(selected_game) => selected_game,
+        Err(_) => return,
+    };
+    if !selected_game_tracker.is_changed() && !players.is_changed() && !profiles.is_changed() {
+        return;
+    }
+
+    let game = if let Some(game) = games.get(*idx) {
+        game
+    } else {
+        return;
+    };
+
+    if let Some(text_section) = game_name_text_query
+        .single_mut()
+        .unwrap()
+        .sections
+        .first_mut()
+    {
+        text_section.value = game.name.into();
+    }
+
+    // Use the color of the player if it is connected.
+    let all_time_entries = profiles
+        .iter()
+        .filter_map(|(name, stats)| {
+            let color = players
+                .values()
+                .find(|player| !player.is_bot() && player.name() == name)
+                .map_or(OFFLINE_COLOR, |player| player.color().as_bevy());
+            Some(RankingEntry {
+                name: name.clone(),
+                color,
+                stats: stats.games.get(game.id)?.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let session_entries = players
+        .values()
+        .filter_map(|player| {
+            Some(RankingEntry {
+                name: player.name().into(),
+                color: player.color().as_bevy(),
+                stats: player.session_stats().games.get(game.id)?.clone(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let font = fonts.regular.clone();
+    all_time_text_query.single_mut().unwrap().sections =
+        ranking_text_sections("All-time", all_time_entries, font.clone());
+    session_text_query.single_mut().unwrap().sections =
+        ranking_text_sections("This session", session_entries, font);
+}
+
+/// Creates the text sections of a ranking. The players are sorted by their
+/// match wins, the round wins are used as a tiebreaker.
+fn ranking_text_sections(
+    header: &str,
+    mut entries: Vec<RankingEntry>,
+    font: Handle<Font>,
+) -> Vec<TextSection> {
+    entries.sort_unstable_by(|a, b| {
+        let key = |entry: &RankingEntry| Reverse((entry.stats.match_wins, entry.stats.round_wins));
+        key(a).cmp(&key(b)).then_with(|| a.name.cmp(&b.name))
+    });
+
+    let mut text_sections = Vec::default();
+    text_sections.push(TextSection {
+        value: header.to_uppercase(),
+        style: TextStyle {
+            font: font.clone(),
+            font_size: 32.0,
+            color: Color::WHITE,
+        },
+    });
+
+    if entries.is_empty() {
+        text_sections.push(TextSection {
+            value: "\nNo rounds played yet".into(),
+            style: TextStyle {
+                font,
+                font_size: 24.0,
+                color: OFFLINE_COLOR,
+            },
+        });
+        return text_sections;
+    }
+
+    for (idx, entry) in entries.iter().take(RANKING_SIZE).enumerate() {
+        text_sections.push(TextSection {
+            value: format!(
+                "\n{}. {}: {} match wins, {} round wins{}",
+                idx + 1,
+                entry.name,
+                entry.stats.match_wins,
+                entry.stats.round_wins,
+                extra_stats_text(&entry.stats)
+            ),
+            style: TextStyle {
+                font: font.clone(),
+                font_size: 24.0,
+                color: entry.color,
+            },
+        });
+    }
+
+    text_sections
+}
+
+/// Returns the game specific statistics that are recorded for the game, i.e.
+/// the statistics that aren't zero.
+fn extra_stats_text(stats: &GameStats) -> String {
+    let mut text = String::new();
+    if stats.goals > 0 {
+        text.push_str(&format!(", {} goals", stats.goals));
+    }
+    if stats.longest_survival > 0.0 {
+        text.push_str(&format!(", best {:.1} s", stats.longest_survival));
+    }
+    if stats.deaths > 0 {
+        text.push_str(&format!(", {} deaths", stats.deaths));
+    }
+    text
+}
+
+fn setup_leaderboard(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>) {
+    let bold_font = fonts.bold.clone();
+    let bold_font_size = 64.0;
+    let regular_font = fonts.regular.clone();
+    let regular_font_size = 24.0;
+    let white_color = Color::WHITE;
+
+    let empty_player_vote = PlayerVote::default();
+    let required_amount = (players.human_count() / 2) + 1;
+
+    let header_y = GAME_HEIGHT * 0.4;
+    let header_text_bundle = Text2dBundle {
+        text: Text::with_section(
+            HEADER_TEXT,
+            TextStyle {
+                font: bold_font.clone(),
+                font_size: bold_font_size,
+                color: white_color,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform::from_xyz(0.0, header_y, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(header_text_bundle)
+        .insert(LeaderboardPlugin);
+
+    // The text is set when the rankings are updated (see `update_rankings`).
+    let game_name_y = header_y - bold_font_size * 1.5;
+    let game_name_text_bundle = Text2dBundle {
+        text: Text::with_section(
+            "",
+            TextStyle {
+                font: bold_font,
+                font_size: 48.0,
+                color: white_color,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform::from_xyz(0.0, game_name_y, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(game_name_text_bundle)
+        .insert(GameNameText)
+        .insert(LeaderboardPlugin);
+
+    let arrow_shape = Shape::polygon(20.0, Vec2::ZERO, 3);
+    for (x, rotation) in [
+        (-250.0, std::f32::consts::FRAC_PI_2),
+        (250.0, -std::f32::consts::FRAC_PI_2),
+    ] {
+        let arrow_bundle = GeometryBuilder::build_as(
+            &arrow_shape,
+            ShapeColors::new(white_color),
+            DrawMode::Fill(FillOptions::DEFAULT),
+            Transform {
+                translation: Vec3::new(x, game_name_y, 0.0),
+                rotation: Quat::from_rotation_z(rotation),
+                ..Default::default()
+            },
+        );
+        commands
+            .spawn_bundle(arrow_bundle)
+            .insert(LeaderboardPlugin);
+    }
+
+    let rankings_y = GAME_HEIGHT * 0.2;
+    let ranking_text_bundle = |x: f32| Text2dBundle {
+        text: Text {
+            sections: Vec::default(),
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(x, rankings_y, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(ranking_text_bundle(-GAME_WIDTH / 4.0))
+        .insert(AllTimeText)
+        .insert(LeaderboardPlugin);
+    commands
+        .spawn_bundle(ranking_text_bundle(GAME_WIDTH / 4.0))
+        .insert(SessionText)
+        .insert(LeaderboardPlugin);
+
+    let exit_text_bundle = Text2dBundle {
+        text: Text {
+            sections: create_vote_text_sections(
+                EXIT_TEXT.into(),
+                &players,
+                &empty_player_vote,
+                required_amount,
+                regular_font,
+                regular_font_size,
+            ),
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(0.0, -GAME_HEIGHT * 0.35, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(exit_text_bundle)
+        .insert(ExitText)
+        .insert(LeaderboardPlugin);
+
+    commands
+        .spawn()
+        .insert(SelectedGame(0))
+        .insert(LeaderboardPlugin);
+}
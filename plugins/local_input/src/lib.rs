@@ -0,0 +1,311 @@
+use std::{
+    collections::HashMap,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+use bevy::prelude::{
+    AppBuilder, Axis, EventReader, Gamepad, GamepadAxis, GamepadAxisType, GamepadButton,
+    GamepadButtonType, GamepadEvent, GamepadEventType, Input, IntoSystem, KeyCode, Plugin, Res,
+    ResMut,
+};
+
+use network::{EventMessage, GeneralEvent, NetworkContext, NetworkEvent};
+use skitspel::{ActionEvent, AnalogAction, PlayerId};
+
+/// How far (between 0.0 & 1.0) a stick needs to be pushed in a direction to
+/// count as the corresponding direction button being pressed.
+const STICK_BUTTON_THRESHOLD: f32 = 0.5;
+
+/// The keys used by one player on the keyboard of the host machine. Pressing
+/// the `join` key toggles between joining and leaving the game.
+struct KeySet {
+    join: KeyCode,
+    up: KeyCode,
+    right: KeyCode,
+    down: KeyCode,
+    left: KeyCode,
+    a: KeyCode,
+    b: KeyCode,
+}
+
+/// The key sets available on the keyboard, every key set can be used by one
+/// player. F11 & escape shouldn't be used since they toggle fullscreen.
+const KEY_SETS: [KeySet; 2] = [
+    KeySet {
+        join: KeyCode::Tab,
+        up: KeyCode::W,
+        right: KeyCode::D,
+        down: KeyCode::S,
+        left: KeyCode::A,
+        a: KeyCode::Space,
+        b: KeyCode::LShift,
+    },
+    KeySet {
+        join: KeyCode::Back,
+        up: KeyCode::Up,
+        right: KeyCode::Right,
+        down: KeyCode::Down,
+        left: KeyCode::Left,
+        a: KeyCode::Return,
+        b: KeyCode::RShift,
+    },
+];
+
+/// The input device of a local player.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum LocalInput {
+    /// The index of the key set in `KEY_SETS`.
+    KeySet(usize),
+    Gamepad(Gamepad),
+}
+
+/// The buttons that a local player are currently pressing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct Buttons {
+    up: bool,
+    right: bool,
+    down: bool,
+    left: bool,
+    a: bool,
+    b: bool,
+}
+
+impl Buttons {
+    /// Returns the events that a client would have sent when changing from
+    /// pressing the `prev` buttons to pressing the buttons in `self`.
+    fn diff(&self, prev: &Buttons) -> Vec<ActionEvent> {
+        let buttons = [
+            (
+                self.up,
+                prev.up,
+                ActionEvent::UpPressed,
+                ActionEvent::UpReleased,
+            ),
+            (
+                self.right,
+                prev.right,
+                ActionEvent::RightPressed,
+                ActionEvent::RightReleased,
+            ),
+            (
+                self.down,
+                prev.down,
+                ActionEvent::DownPressed,
+                ActionEvent::DownReleased,
+            ),
+            (
+                self.left,
+                prev.left,
+                ActionEvent::LeftPressed,
+                ActionEvent::LeftReleased,
+            ),
+            (
+                self.a,
+                prev.a,
+                ActionEvent::APressed,
+                ActionEvent::AReleased,
+            ),
+            (
+                self.b,
+                prev.b,
+                ActionEvent::BPressed,
+                ActionEvent::BReleased,
+            ),
+        ];
+
+        buttons
+            .iter()
+            .filter(|(cur, prev, ..)| cur != prev)
+            .map(|(cur, _, pressed, released)| if *cur { *pressed } else { *released })
+            .collect()
+    }
+}
+
+/// A player that plays on the host machine.
+#[derive(Debug)]
+struct LocalPlayer {
+    id: PlayerId,
+    buttons: Buttons,
+    analog: AnalogAction,
+}
+
+/// Contains all local players that have joined the game. The key is the input
+/// device that the player uses.
+#[derive(Default)]
+struct LocalPlayers(HashMap<LocalInput, LocalPlayer>);
+
+impl Deref for LocalPlayers {
+    type Target = HashMap<LocalInput, LocalPlayer>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for LocalPlayers {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Plugin that lets people play with the keyboard or gamepads connected to the
+/// host machine. This is useful if ex. someone's phone is dead or when testing
+/// games without any phones.
+///
+/// Every local player gets its own `PlayerId` and all its input is injected
+/// into the `NetworkContext`, so local players are handled exactly like the
+/// players connected over websockets.
+///
+/// Keyboard players join/leave by pressing the `join` key of their key set
+/// (see `KEY_SETS`). Gamepad players join/leave by pressing start.
+pub struct LocalInputPlugin;
+
+impl Plugin for LocalInputPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<LocalPlayers>()
+            .add_system(handle_keyboard_input.system())
+            .add_system(handle_gamepad_input.system());
+    }
+}
+
+fn handle_keyboard_input(
+    network_ctx: Res<Arc<Mutex<NetworkContext>>>,
+    keys: Res<Input<KeyCode>>,
+    mut local_players: ResMut<LocalPlayers>,
+) {
+    let network_ctx = network_ctx.lock().unwrap();
+    for (idx, key_set) in KEY_SETS.iter().enumerate() {
+        let local_input = LocalInput::KeySet(idx);
+        if keys.just_pressed(key_set.join) {
+            let name = format!("Keyboard {}", idx + 1);
+            toggle_join(&network_ctx, &mut local_players, local_input, name);
+        }
+
+        if let Some(local_player) = local_players.get_mut(&local_input) {
+            let buttons = Buttons {
+                up: keys.pressed(key_set.up),
+                right: keys.pressed(key_set.right),
+                down: keys.pressed(key_set.down),
+                left: keys.pressed(key_set.left),
+                a: keys.pressed(key_set.a),
+                b: keys.pressed(key_set.b),
+            };
+            update_buttons(&network_ctx, local_player, buttons);
+        }
+    }
+}
+
+/// The D-pad & left stick are used for movement, the right trigger is sent as
+/// the analog trigger. South & east are the A & B buttons respectively.
+fn handle_gamepad_input(
+    network_ctx: Res<Arc<Mutex<NetworkContext>>>,
+    mut gamepad_events: EventReader<GamepadEvent>,
+    buttons: Res<Input<GamepadButton>>,
+    button_axes: Res<Axis<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    mut local_players: ResMut<LocalPlayers>,
+) {
+    let network_ctx = network_ctx.lock().unwrap();
+
+    for GamepadEvent(gamepad, event_type) in gamepad_events.iter() {
+        let local_input = LocalInput::Gamepad(*gamepad);
+        if let GamepadEventType::Disconnected = event_type {
+            if let Some(local_player) = local_players.remove(&local_input) {
+                leave(&network_ctx, local_player.id);
+            }
+        }
+    }
+
+    for GamepadButton(gamepad, button_type) in buttons.get_just_pressed() {
+        if let GamepadButtonType::Start = button_type {
+            let name = format!("Gamepad {}", gamepad.0 + 1);
+            toggle_join(
+                &network_ctx,
+                &mut local_players,
+                LocalInput::Gamepad(*gamepad),
+                name,
+            );
+        }
+    }
+
+    for (local_input, local_player) in local_players.iter_mut() {
+        let gamepad = if let LocalInput::Gamepad(gamepad) = local_input {
+            *gamepad
+        } else {
+            continue;
+        };
+
+        let pressed = |button_type| buttons.pressed(GamepadButton(gamepad, button_type));
+        let axis = |axis_type| axes.get(GamepadAxis(gamepad, axis_type)).unwrap_or(0.0);
+        let stick_x = axis(GamepadAxisType::LeftStickX);
+        let stick_y = axis(GamepadAxisType::LeftStickY);
+        let trigger = button_axes
+            .get(GamepadButton(gamepad, GamepadButtonType::RightTrigger2))
+            .unwrap_or(0.0);
+
+        let new_buttons = Buttons {
+            up: pressed(GamepadButtonType::DPadUp) || stick_y > STICK_BUTTON_THRESHOLD,
+            right: pressed(GamepadButtonType::DPadRight) || stick_x > STICK_BUTTON_THRESHOLD,
+            down: pressed(GamepadButtonType::DPadDown) || stick_y < -STICK_BUTTON_THRESHOLD,
+            left: pressed(GamepadButtonType::DPadLeft) || stick_x < -STICK_BUTTON_THRESHOLD,
+            a: pressed(GamepadButtonType::South),
+            b: pressed(GamepadButtonType::East),
+        };
+        update_buttons(&network_ctx, local_player, new_buttons);
+
+        let analog = AnalogAction::new(stick_x, stick_y, trigger);
+        if analog != local_player.analog {
+            local_player.analog = analog;
+            send_action(&network_ctx, local_player.id, ActionEvent::Analog(analog));
+        }
+    }
+}
+
+/// Lets the `local_input` join the game if it isn't already playing, otherwise
+/// the player using the `local_input` leaves the game.
+fn toggle_join(
+    network_ctx: &NetworkContext,
+    local_players: &mut LocalPlayers,
+    local_input: LocalInput,
+    name: String,
+) {
+    if let Some(local_player) = local_players.remove(&local_input) {
+        leave(network_ctx, local_player.id);
+    } else {
+        let player_id = network_ctx.generate_player_id();
+        network_ctx.inject(EventMessage {
+            player_id,
+            event: NetworkEvent::General(GeneralEvent::Connected(name.clone(), name, None)),
+        });
+        local_players.insert(
+            local_input,
+            LocalPlayer {
+                id: player_id,
+                buttons: Buttons::default(),
+                analog: AnalogAction::default(),
+            },
+        );
+    }
+}
+
+fn leave(network_ctx: &NetworkContext, player_id: PlayerId) {
+    network_ctx.inject(EventMessage {
+        player_id,
+        event: NetworkEvent::General(GeneralEvent::Disconnected),
+    });
+}
+
+fn update_buttons(network_ctx: &NetworkContext, local_player: &mut LocalPlayer, buttons: Buttons) {
+    for action_event in buttons.diff(&local_player.buttons) {
+        send_action(network_ctx, local_player.id, action_event);
+    }
+    local_player.buttons = buttons;
+}
+
+fn send_action(network_ctx: &NetworkContext, player_id: PlayerId, action_event: ActionEvent) {
+    network_ctx.inject(EventMessage {
+        player_id,
+        event: NetworkEvent::Action(action_event),
+    });
+}
@@ -0,0 +1,179 @@
+use bevy::{
+    core::Time,
+    prelude::{
+        Color, Commands, HorizontalAlign, IntoSystem, Plugin, Query, Res, ResMut, State, SystemSet,
+        Transform, VerticalAlign, With,
+    },
+    text::{Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
+};
+
+use skitspel::{GameState, Players, Team, GAME_HEIGHT};
+use tournament::Tournament;
+use util_bevy::{
+    despawn_system, ignore_player_input, spawn_countdown, tick_countdown, AsBevyColor, Countdown,
+    CountdownText, Fonts, MatchResult, MatchWins,
+};
+
+const GAME_STATE: GameState = GameState::MatchResults;
+
+/// The amount of seconds that the match results are shown.
+const RESULTS_TIME: usize = 8;
+
+/// Will be used as a tag/component added to all entities related to the
+/// screen showing the results of a match.
+#[derive(Clone, Default)]
+pub struct MatchResultsPlugin;
+
+impl Plugin for MatchResultsPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<MatchWins>()
+            .add_system_set(SystemSet::on_enter(GAME_STATE).with_system(setup_results.system()))
+            .add_system_set(
+                SystemSet::on_update(GAME_STATE)
+                    .with_system(handle_results_timer.system())
+                    .with_system(ignore_player_input.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GAME_STATE)
+                    .with_system(despawn_system::<MatchResultsPlugin>.system()),
+            );
+    }
+}
+
+fn setup_results(
+    mut commands: Commands,
+    fonts: Res<Fonts>,
+    players: Res<Players>,
+    match_wins: Res<MatchWins>,
+    match_result: Res<MatchResult>,
+) {
+    let bold_font = fonts.bold.clone();
+    let bold_font_size = 64.0;
+    let regular_font = fonts.regular.clone();
+    let regular_font_size = 32.0;
+
+    let winner_player = match match_result.winners.as_slice() {
+        [player_id] => players.get(player_id),
+        _ => None,
+    };
+    let (winner_text, winner_color) = match (match_result.winning_team, winner_player) {
+        (Some(Team::Left), _) => ("Team left wins the match!".into(), Color::WHITE),
+        (Some(Team::Right), _) => ("Team right wins the match!".into(), Color::WHITE),
+        (None, Some(player)) => (
+            format!("{} wins the match!", player.name()),
+            player.color().as_bevy(),
+        ),
+        (None, None) => ("The match ended in a draw".into(), Color::WHITE),
+    };
+
+    let mut header_text_sections = vec![
+        TextSection {
+            value: format!("{}\n", match_result.game_name),
+            style: TextStyle {
+                font: bold_font.clone(),
+                font_size: bold_font_size,
+                color: Color::WHITE,
+            },
+        },
+        TextSection {
+            value: winner_text,
+            style: TextStyle {
+                font: bold_font,
+                font_size: bold_font_size,
+                color: winner_color,
+            },
+        },
+    ];
+
+    if let Some((left_score, right_score)) = match_result.team_score {
+        header_text_sections.push(TextSection {
+            value: format!("\n{} - {}", left_score, right_score),
+            style: TextStyle {
+                font: regular_font.clone(),
+                font_size: bold_font_size,
+                color: Color::WHITE,
+            },
+        });
+    }
+
+    let header_text_bundle = Text2dBundle {
+        text: Text {
+            sections: header_text_sections,
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(0.0, GAME_HEIGHT * 0.35, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(header_text_bundle)
+        .insert(MatchResultsPlugin);
+
+    let score_text_sections = match_result
+        .scores
+        .iter()
+        .filter_map(|(player_id, score)| {
+            let player = players.get(player_id)?;
+            Some(TextSection {
+                value: format!(
+                    "\n{}: {} points, {} match wins",
+                    player.name(),
+                    score,
+                    match_wins.wins(player_id)
+                ),
+                style: TextStyle {
+                    font: regular_font.clone(),
+                    font_size: regular_font_size,
+                    color: player.color().as_bevy(),
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let score_text_bundle = Text2dBundle {
+        text: Text {
+            sections: score_text_sections,
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(0.0, GAME_HEIGHT * 0.35 - 3.0 * bold_font_size, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(score_text_bundle)
+        .insert(MatchResultsPlugin);
+
+    spawn_countdown::<MatchResultsPlugin>(&mut commands, RESULTS_TIME, regular_font);
+}
+
+/// Moves on when the results have been shown long enough. The players returns
+/// to the game selection menu, or to the tournament standings if the match was
+/// played in a tournament.
+fn handle_results_timer(
+    time: Res<Time>,
+    tournament: Res<Tournament>,
+    mut game_state: ResMut<State<GameState>>,
+    mut countdown_query: Query<&mut Countdown>,
+    mut countdown_text_query: Query<&mut Text, With<CountdownText>>,
+) {
+    let mut countdown = countdown_query.single_mut().unwrap();
+    let mut countdown_text = countdown_text_query.single_mut().unwrap();
+    if !tick_countdown(&time, &mut countdown, &mut countdown_text, "Continuing in") {
+        return;
+    }
+
+    let next_game_state = if !tournament.is_running() {
+        GameState::GameSelectionMenu
+    } else if tournament.is_finished() {
+        GameState::TournamentPodium
+    } else {
+        GameState::TournamentStandings
+    };
+    game_state.set(next_game_state).unwrap();
+}
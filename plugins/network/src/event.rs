@@ -2,7 +2,10 @@
 //!
 //! First byte indicates the type:
 //!   0 => Action event (move/fire/jump etc.)
-//!   1 => Connect event (sent from client when it connects containing name)
+//!   1 => Legacy connect event (sent from outdated clients that doesn't do a
+//!        handshake, these clients are rejected)
+//!   3 => Analog event (sent from clients with analog input ex. a joystick)
+//!   4 => Handshake (MUST be the first message sent from the client)
 //!
 //! If first byte is `ActionEvent` (0) then the second byte represents:
 //!   0  => UpPressed
@@ -17,37 +20,121 @@
 //!   9  => AReleased
 //!   10 => BPressed
 //!   11 => BReleased
+//!
+//! If first byte is handshake (4) then the second byte is the protocol version
+//! of the client and the third byte is a bitflag of the features that the
+//! client supports (see the `FEATURE_*` constants). The fourth byte represents:
+//!   0 => Connect (the rest of the message is the UTF-8 encoded name)
+//!   1 => Resume  (the rest of the message is the 8 byte session token that
+//!                 the client was given when it first connected)
+//!   2 => Spectate (connect as a spectator that only receives messages from the
+//!                  server, any input sent from a spectator is ignored)
+//!
+//! If first byte is analog event (3) then the next two bytes is the x and y
+//! value of the analog vector as i8 (positive y is up). The optional fourth
+//! byte is the trigger value as u8 (0 => released, 255 => fully pressed).
+//!
+//! # Binary format of messages sent from the server to the clients
+//!
+//! First byte indicates the type:
+//!   0 => Game state (the state/game that is currently active)
+//!   1 => Score (the current score of the receiving player)
+//!   2 => Died (the receiving player died in the current round)
+//!   3 => Team (the team that the receiving player belongs to)
+//!   4 => Session token (used by the client to resume the session if it
+//!        gets disconnected)
+//!   5 => Handshake accepted
+//!   6 => Handshake rejected (the connection is closed after this message)
+//!   7 => Queue position (the server is full and the receiving client is
+//!        waiting for a free slot)
+//!   8 => Name (the name of the receiving player, set by the server)
+//!
+//! If first byte is game state (0) then the second byte represents:
+//!   0 => StartMenu
+//!   1 => GameSelectionMenu
+//!   2 => Game
+//!   3 => TournamentStandings
+//!   4 => TournamentPodium
+//!   5 => MatchResults
+//!   6 => Leaderboard
+//! The rest of the message is the UTF-8 encoded name of the state (ex. the name
+//! of the game that is being played).
+//!
+//! If first byte is score (1) then the next four bytes is the score as a u32
+//! in big-endian.
+//!
+//! If first byte is team (3) then the second byte represents:
+//!   0 => Left
+//!   1 => Right
+//!
+//! If first byte is session token (4) then the next eight bytes is the token
+//! as a u64 in big-endian. This is also sent as an acknowledgement when a
+//! session is resumed successfully.
+//!
+//! If first byte is handshake accepted (5) then the second byte is the protocol
+//! version of the server and the third byte is the bitflag of the features that
+//! both the client and server supports.
+//!
+//! If first byte is handshake rejected (6) then the second byte represents the
+//! reason and the third byte is the protocol version of the server:
+//!   0 => Outdated client
+//!   1 => Outdated server
+//!   2 => Invalid handshake
+//!   3 => Session expired
+//!   4 => Empty name
+//!   5 => Name too long
+//!
+//! If first byte is queue position (7) then the next four bytes is the position
+//! in the queue as a u32 in big-endian (starting at 1). A position of 0 means
+//! that the client have been promoted from the queue and is now a player.
+//!
+//! If first byte is name (8) then the rest of the message is the UTF-8 encoded
+//! name.
+use std::{convert::TryFrom, time::Duration};
+
 use async_tungstenite::tungstenite::Message;
-use bevy::core::Timer;
-use futures_util::stream::SplitSink;
+use futures_util::stream::{SplitSink, SplitStream};
 
-use skitspel::{ActionEvent, PlayerId};
+use skitspel::{
+    ActionEvent, AnalogAction, GameState, HandshakeRejectReason, PlayerId, ServerMessage, Team,
+};
 
 use crate::wsstream::WsStream;
 
 pub type WebSocketSink = SplitSink<WsStream, Message>;
+pub type WebSocketSource = SplitStream<WsStream>;
+
+/// The version of the protocol used between the server and the clients. Should
+/// be incremented every time that an incompatible change is made to the binary
+/// format of the messages.
+pub const PROTOCOL_VERSION: u8 = 2;
+
+/// The client is able to send analog input (message type 3).
+pub const FEATURE_ANALOG: u8 = 1 << 0;
+/// The client is able to resume its session with a session token.
+pub const FEATURE_SESSION_RESUME: u8 = 1 << 1;
 
-/// A timer used to synchronize action events.
-///
-/// Since the actions of players are updated every tick (which is really frequent)
-/// and the clients send `ActionEvent`s over the wire (which is really slow), we
-/// don't want to update the action events every tick. Instead we want to "hold"
-/// the previosly sent `ActionEvent` from the clients for some time so that it
-/// actually gets applied properly (instead of being over after one tick).
-///
-/// When this timer expires, we will re-evaluate the inputs given from the client
-/// and update the `ActionEvent`s accordingly.
-pub struct EventTimer(pub Timer);
-
-impl EventTimer {
-    /// The time (in seconds) that a `ActionEvent` will be held/applied.
-    const HOLD_TIME_SEC: f32 = 1.0 / 10.0;
+/// All features supported by the server.
+pub const SUPPORTED_FEATURES: u8 = FEATURE_ANALOG | FEATURE_SESSION_RESUME;
+
+/// The handshake sent by a client as its first message.
+#[derive(Debug, Clone)]
+pub struct Handshake {
+    pub version: u8,
+    /// Bitflag containing the features supported by the client.
+    pub features: u8,
+    pub request: HandshakeRequest,
 }
 
-impl Default for EventTimer {
-    fn default() -> Self {
-        Self(Timer::from_seconds(Self::HOLD_TIME_SEC, true))
-    }
+#[derive(Debug, Clone)]
+pub enum HandshakeRequest {
+    /// Connect as a new player with the given name.
+    Connect(String),
+    /// Resume the session with the given session token.
+    Resume(u64),
+    /// Connect as a spectator. A spectator isn't a player, it will only receive
+    /// the messages broadcasted from the server.
+    Spectate,
 }
 
 /// Represents an event that have been triggered by a client.
@@ -66,27 +153,67 @@ pub enum NetworkEvent {
     General(GeneralEvent),
     /// An event representing an action (ex. steering/fire/jump).
     Action(ActionEvent),
+    /// The handshake of a client. This is only valid as the first message sent
+    /// from a client and will never be propagated to the `NetworkContext`.
+    Handshake(Handshake),
     /// An event represeting a message received from a client that was invalid.
     Invalid(Vec<u8>),
 }
 
 #[derive(Debug)]
 pub enum GeneralEvent {
-    /// The String is the name that was specified by the newly connected player.
+    /// The first String is the name of the newly connected player, it is unique
+    /// among the connected players (see `Names`). The second String is the name
+    /// that was specified by the player, the two names only differ if the
+    /// specified name was already taken.
     ///
     /// The sink can be used to send data to the newly connected client. This
     /// will not be propagated all the way through the "system". The sink will
     /// at one point be moved to its correct place and the value will be set to
     /// None.
-    Connected(String, Option<WebSocketSink>),
+    ///
+    /// Local players (ex. a keyboard on the host machine) are connected without
+    /// a sink since there is no client to send data to.
+    Connected(String, String, Option<WebSocketSink>),
+    /// A bot controlled by the server was added. The String is the name of the
+    /// bot. Bots are only added if there is a free slot, they are never put
+    /// in the queue.
+    BotConnected(String),
+    /// The u64 is the session token sent by a player that reconnects. The
+    /// `PlayerId` of the event will be set to the ID of the player that owns
+    /// the session, so the player should be seen as resumed rather than new.
+    ///
+    /// The sink is handled in the same way as for the `Connected` variant.
+    Reconnected(u64, Option<WebSocketSink>),
+    /// The connection of the player dropped. The player might reconnect within
+    /// the grace period, so the player should be kept around until a
+    /// `Disconnected` event is received.
+    Dropped,
     Disconnected,
+    /// A spectator connected. The `PlayerId` of the event is the ID of the
+    /// spectator, which will never be used as the ID of a player.
+    ///
+    /// The sink is handled in the same way as for the `Connected` variant.
+    SpectatorConnected(Option<WebSocketSink>),
+    SpectatorDisconnected,
+    /// A new round-trip time have been measured for the player. This is
+    /// measured with websocket pings sent periodically from the server.
+    Latency(Duration),
 }
 
 impl Clone for GeneralEvent {
     fn clone(&self) -> Self {
         match self {
-            Self::Connected(name, _) => Self::Connected(name.clone(), None),
+            Self::Connected(name, requested_name, _) => {
+                Self::Connected(name.clone(), requested_name.clone(), None)
+            }
+            Self::BotConnected(name) => Self::BotConnected(name.clone()),
+            Self::Reconnected(token, _) => Self::Reconnected(*token, None),
+            Self::Dropped => Self::Dropped,
             Self::Disconnected => Self::Disconnected,
+            Self::SpectatorConnected(_) => Self::SpectatorConnected(None),
+            Self::SpectatorDisconnected => Self::SpectatorDisconnected,
+            Self::Latency(rtt) => Self::Latency(*rtt),
         }
     }
 }
@@ -101,7 +228,8 @@ pub fn decode_message(data: &[u8]) -> NetworkEvent {
     // See top-level comment for mapping between values and events.
     match data[0] {
         0 => decode_action_event(data),
-        1 => decode_connect_event(data),
+        3 => decode_analog_event(data),
+        4 => decode_handshake(data),
         _ => NetworkEvent::Invalid(data.to_vec()),
     }
 }
@@ -129,9 +257,123 @@ fn decode_action_event(data: &[u8]) -> NetworkEvent {
     })
 }
 
-pub fn decode_connect_event(data: &[u8]) -> NetworkEvent {
-    match std::str::from_utf8(&data[1..]) {
-        Ok(name) => NetworkEvent::General(GeneralEvent::Connected(name.to_string(), None)),
-        _ => NetworkEvent::Invalid(data.to_vec()),
+fn decode_analog_event(data: &[u8]) -> NetworkEvent {
+    if data.len() != 3 && data.len() != 4 {
+        return NetworkEvent::Invalid(data.to_vec());
+    }
+
+    NetworkEvent::Action(ActionEvent::Analog(AnalogAction {
+        x: data[1] as i8,
+        y: data[2] as i8,
+        trigger: data.get(3).cloned().unwrap_or(0),
+    }))
+}
+
+fn decode_handshake(data: &[u8]) -> NetworkEvent {
+    if data.len() < 4 {
+        return NetworkEvent::Invalid(data.to_vec());
+    }
+
+    let version = data[1];
+    let features = data[2];
+    let payload = &data[4..];
+
+    // See top-level comment for mapping between values and requests.
+    let request = match data[3] {
+        0 => match std::str::from_utf8(payload) {
+            Ok(name) => HandshakeRequest::Connect(name.to_string()),
+            Err(_) => return NetworkEvent::Invalid(data.to_vec()),
+        },
+        1 if payload.len() == 8 => {
+            let mut token_bytes = [0; 8];
+            token_bytes.copy_from_slice(payload);
+            HandshakeRequest::Resume(u64::from_be_bytes(token_bytes))
+        }
+        2 => HandshakeRequest::Spectate,
+        _ => return NetworkEvent::Invalid(data.to_vec()),
+    };
+
+    NetworkEvent::Handshake(Handshake {
+        version,
+        features,
+        request,
+    })
+}
+
+/// Returns true if the given `data` is a connect message sent from an outdated
+/// client that doesn't know about the handshake.
+pub fn is_legacy_connect(data: &[u8]) -> bool {
+    data.first() == Some(&1)
+}
+
+/// Returns a human readable description of the `reason`. This is sent as the
+/// reason of the close frame so that it is visible even for outdated clients.
+pub fn reject_reason_description(reason: HandshakeRejectReason) -> &'static str {
+    match reason {
+        HandshakeRejectReason::OutdatedClient => "Outdated client, please reload the page.",
+        HandshakeRejectReason::OutdatedServer => "The server is outdated.",
+        HandshakeRejectReason::InvalidHandshake => "Invalid handshake.",
+        HandshakeRejectReason::SessionExpired => "The session have expired.",
+        HandshakeRejectReason::EmptyName => "The name can't be empty.",
+        HandshakeRejectReason::NameTooLong => "The name is too long.",
+    }
+}
+
+/// Utility function to encode the given `ServerMessage` into the binary format
+/// that is sent over the wire to the clients.
+pub fn encode_server_message(msg: &ServerMessage) -> Vec<u8> {
+    // See top-level comment for mapping between values and messages.
+    match msg {
+        ServerMessage::GameState(game_state, name) => {
+            let game_state_byte = match game_state {
+                GameState::StartMenu => 0,
+                GameState::GameSelectionMenu => 1,
+                GameState::Game(_) => 2,
+                GameState::TournamentStandings => 3,
+                GameState::TournamentPodium => 4,
+                GameState::MatchResults => 5,
+                GameState::Leaderboard => 6,
+            };
+            let mut data = vec![0, game_state_byte];
+            data.extend_from_slice(name.as_bytes());
+            data
+        }
+        ServerMessage::Score(score) => {
+            let score = u32::try_from(*score).unwrap_or(u32::MAX);
+            let mut data = vec![1];
+            data.extend_from_slice(&score.to_be_bytes());
+            data
+        }
+        ServerMessage::Died => vec![2],
+        ServerMessage::Team(Team::Left) => vec![3, 0],
+        ServerMessage::Team(Team::Right) => vec![3, 1],
+        ServerMessage::SessionToken(token) => {
+            let mut data = vec![4];
+            data.extend_from_slice(&token.to_be_bytes());
+            data
+        }
+        ServerMessage::QueuePosition(position) => {
+            let position = u32::try_from(*position).unwrap_or(u32::MAX);
+            let mut data = vec![7];
+            data.extend_from_slice(&position.to_be_bytes());
+            data
+        }
+        ServerMessage::Name(name) => {
+            let mut data = vec![8];
+            data.extend_from_slice(name.as_bytes());
+            data
+        }
+        ServerMessage::HandshakeAccepted { version, features } => vec![5, *version, *features],
+        ServerMessage::HandshakeRejected { reason, version } => {
+            let reason_byte = match reason {
+                HandshakeRejectReason::OutdatedClient => 0,
+                HandshakeRejectReason::OutdatedServer => 1,
+                HandshakeRejectReason::InvalidHandshake => 2,
+                HandshakeRejectReason::SessionExpired => 3,
+                HandshakeRejectReason::EmptyName => 4,
+                HandshakeRejectReason::NameTooLong => 5,
+            };
+            vec![6, reason_byte, *version]
+        }
     }
 }
@@ -0,0 +1,201 @@
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// The max size of the head (request line + headers) of a HTTP request.
+const MAX_HEAD_SIZE: usize = 8 * 1024;
+
+/// The files of the web client that are embedded into the executable. These
+/// are served over HTTP(S) on the same port as the websocket.
+///
+/// The tuples contains: (path, content type, content)
+const CLIENT_FILES: [(&str, &str, &[u8]); 4] = [
+    (
+        "/index.html",
+        "text/html; charset=utf-8",
+        include_bytes!("../../../client/index.html"),
+    ),
+    (
+        "/skitspel.js",
+        "text/javascript; charset=utf-8",
+        include_bytes!("../../../client/skitspel.js"),
+    ),
+    (
+        "/skitspel.css",
+        "text/css; charset=utf-8",
+        include_bytes!("../../../client/skitspel.css"),
+    ),
+    (
+        "/github_logo.png",
+        "image/png",
+        include_bytes!("../../../client/github_logo.png"),
+    ),
+];
+
+/// A script that is only served by the game server (not when the client is
+/// hosted somewhere else). The client uses this to know that it should prefill
+/// the connect form with the address of the page.
+const CONFIG_JS: &[u8] = b"var servedByGameServer = true;\n";
+
+/// Wraps a stream and replays the `prefix` before any data is read from the
+/// `inner` stream.
+///
+/// This is used to "peek" at the HTTP request sent by a client. The bytes that
+/// are read to check if the request is a websocket upgrade request are put in
+/// the `prefix` so that the websocket handshake can read the whole request.
+#[derive(Debug)]
+pub struct PrefixedStream<S> {
+    prefix: Vec<u8>,
+    /// The amount of bytes of the `prefix` that have been read.
+    pos: usize,
+    inner: S,
+}
+
+impl<S> PrefixedStream<S> {
+    pub fn new(prefix: Vec<u8>, inner: S) -> Self {
+        Self {
+            prefix,
+            pos: 0,
+            inner,
+        }
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for PrefixedStream<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        if self.pos < self.prefix.len() {
+            let remaining = &self.prefix[self.pos..];
+            let len = remaining.len().min(buf.len());
+            buf[..len].copy_from_slice(&remaining[..len]);
+            self.pos += len;
+            Poll::Ready(Ok(len))
+        } else {
+            Pin::new(&mut self.inner).poll_read(cx, buf)
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for PrefixedStream<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// Reads from the `stream` until the whole head of a HTTP request have been
+/// read. All bytes read are returned, this might include parts of the body.
+pub async fn read_request_head<S>(stream: &mut S) -> io::Result<Vec<u8>>
+where
+    S: AsyncRead + Unpin,
+{
+    let mut head = Vec::new();
+    let mut buf = [0; 1024];
+    while find_head_end(&head).is_none() {
+        if head.len() > MAX_HEAD_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "HTTP request head too large",
+            ));
+        }
+
+        let n = stream.read(&mut buf).await?;
+        if n == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+    Ok(head)
+}
+
+/// Returns true if the HTTP request in `head` is a websocket upgrade request.
+pub fn is_websocket_upgrade(head: &[u8]) -> bool {
+    let head = String::from_utf8_lossy(&head[..find_head_end(head).unwrap_or(head.len())]);
+    head.lines().skip(1).any(|line| {
+        let mut parts = line.splitn(2, ':');
+        let name = parts.next().unwrap_or_default().trim();
+        let value = parts.next().unwrap_or_default().trim();
+        name.eq_ignore_ascii_case("upgrade") && value.eq_ignore_ascii_case("websocket")
+    })
+}
+
+/// Responds to the HTTP request in `head` with one of the embedded files of the
+/// web client. The connection is closed after the response have been written.
+pub async fn serve_client_files<S>(stream: &mut S, head: &[u8]) -> io::Result<()>
+where
+    S: AsyncWrite + Unpin,
+{
+    let head = String::from_utf8_lossy(head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or_default();
+    let path = target.split('?').next().unwrap_or_default();
+
+    let response = if method != "GET" && method != "HEAD" {
+        http_response(
+            "405 Method Not Allowed",
+            "text/plain",
+            b"Method Not Allowed",
+        )
+    } else if path == "/config.js" {
+        http_response("200 OK", "text/javascript; charset=utf-8", CONFIG_JS)
+    } else {
+        let path = if path == "/" { "/index.html" } else { path };
+        match CLIENT_FILES
+            .iter()
+            .find(|(file_path, ..)| *file_path == path)
+        {
+            Some((_, content_type, content)) => http_response("200 OK", content_type, content),
+            None => http_response("404 Not Found", "text/plain", b"Not Found"),
+        }
+    };
+
+    let response = if method == "HEAD" {
+        let body_start = find_head_end(&response).unwrap_or(response.len());
+        &response[..body_start]
+    } else {
+        &response[..]
+    };
+
+    stream.write_all(response).await?;
+    stream.flush().await?;
+    stream.close().await
+}
+
+fn http_response(status: &str, content_type: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Returns the index of the first byte after the head of a HTTP request/response
+/// (i.e. the index after the first empty line).
+fn find_head_end(data: &[u8]) -> Option<usize> {
+    data.windows(4)
+        .position(|window| window == b"\r\n\r\n")
+        .map(|idx| idx + 4)
+}
@@ -1,13 +1,19 @@
 mod event;
+mod http;
+mod lobby;
+mod name;
 mod network;
+mod session;
 mod wsstream;
 
 use std::sync::{Arc, Mutex};
 
 use bevy::prelude::{AppBuilder, IntoSystem, Plugin};
 
-pub use event::{EventMessage, EventTimer, GeneralEvent, NetworkEvent};
-use network::setup_network;
+use skitspel::{Latencies, ServerMessageEvent};
+
+pub use event::{EventMessage, GeneralEvent, NetworkEvent};
+use network::{handle_server_messages, setup_network};
 pub use network::{ActionMessageIter, GeneralMessageIter, NetworkContext};
 
 /// Plugin that handles all network logic for the game.
@@ -18,8 +24,10 @@ pub struct NetworkPlugin;
 
 impl Plugin for NetworkPlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.init_resource::<EventTimer>()
-            .init_resource::<Arc<Mutex<NetworkContext>>>()
-            .add_startup_system(setup_network.system());
+        app.init_resource::<Arc<Mutex<NetworkContext>>>()
+            .init_resource::<Latencies>()
+            .add_event::<ServerMessageEvent>()
+            .add_startup_system(setup_network.system())
+            .add_system(handle_server_messages.system());
     }
 }
@@ -0,0 +1,156 @@
+use std::collections::{HashSet, VecDeque};
+
+use skitspel::{PlayerId, MAX_PLAYERS};
+
+use crate::GeneralEvent;
+
+/// Keeps track of which connected clients that are active players and which
+/// clients that are waiting in the queue for a free slot.
+///
+/// The games can't handle more than `MAX_PLAYERS` players, so any client that
+/// connects when all slots are taken are put in a queue. The first client in the
+/// queue is promoted to an active player when an active player disconnects.
+#[derive(Debug, Default)]
+pub(crate) struct Lobby {
+    active: HashSet<PlayerId>,
+
+    /// The clients waiting for a free slot together with the `Connected` events
+    /// that they sent when connecting.
+    queue: VecDeque<(PlayerId, GeneralEvent)>,
+}
+
+impl Lobby {
+    pub fn is_active(&self, player_id: PlayerId) -> bool {
+        self.active.contains(&player_id)
+    }
+
+    /// Returns true if all slots for active players are taken.
+    pub fn is_full(&self) -> bool {
+        self.active.len() >= MAX_PLAYERS
+    }
+
+    /// Adds the client with ID `player_id` to the lobby. Returns true if the
+    /// client was admitted as an active player directly, false if it was put
+    /// in the queue.
+    pub fn join(&mut self, player_id: PlayerId, connect_event: GeneralEvent) -> bool {
+        if !self.is_full() {
+            self.active.insert(player_id);
+            true
+        } else {
+            self.queue.push_back((player_id, connect_event));
+            false
+        }
+    }
+
+    /// Removes the client with ID `player_id` from the lobby. If an active
+    /// player left and there are clients waiting in the queue, the first client
+    /// in the queue is promoted. The ID and the `Connected` event of the
+    /// promoted client is returned.
+    pub fn leave(&mut self, player_id: PlayerId) -> Option<(PlayerId, GeneralEvent)> {
+        if self.active.remove(&player_id) {
+            let (promoted_id, connect_event) = self.queue.pop_front()?;
+            self.active.insert(promoted_id);
+            Some((promoted_id, connect_event))
+        } else {
+            self.queue.retain(|(id, _)| *id != player_id);
+            None
+        }
+    }
+
+    /// Returns the IDs of the clients waiting in the queue together with their
+    /// position in the queue (starting at 1).
+    pub fn queue_positions(&self) -> impl Iterator<Item = (PlayerId, usize)> + '_ {
+        self.queue
+            .iter()
+            .enumerate()
+            .map(|(idx, (player_id, _))| (*player_id, idx + 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn connect_event(name: &str) -> GeneralEvent {
+        GeneralEvent::Connected(name.into(), name.into(), None)
+    }
+
+    fn event_name(event: &GeneralEvent) -> &str {
+        match event {
+            GeneralEvent::Connected(name, ..) => name,
+            _ => panic!("Expected a connect event, got: {:?}", event),
+        }
+    }
+
+    /// Returns a lobby where all slots are taken by the players with IDs
+    /// 1..=MAX_PLAYERS.
+    fn full_lobby() -> Lobby {
+        let mut lobby = Lobby::default();
+        for id in 1..=MAX_PLAYERS as u64 {
+            assert!(lobby.join(id.into(), connect_event("Active")));
+        }
+        lobby
+    }
+
+    #[test]
+    fn join_admits_players_until_full() {
+        let mut lobby = full_lobby();
+        assert!(lobby.is_full());
+
+        let queued_id = PlayerId::from(100u64);
+        assert!(!lobby.join(queued_id, connect_event("Queued")));
+        assert!(!lobby.is_active(queued_id));
+        assert_eq!(
+            lobby.queue_positions().collect::<Vec<_>>(),
+            [(queued_id, 1)]
+        );
+    }
+
+    #[test]
+    fn leave_promotes_in_queue_order() {
+        let mut lobby = full_lobby();
+        let first_id = PlayerId::from(100u64);
+        let second_id = PlayerId::from(101u64);
+        lobby.join(first_id, connect_event("First"));
+        lobby.join(second_id, connect_event("Second"));
+
+        let (promoted_id, event) = lobby.leave(1u64.into()).unwrap();
+        assert_eq!(promoted_id, first_id);
+        assert_eq!(event_name(&event), "First");
+        assert!(lobby.is_active(first_id));
+        assert!(lobby.is_full());
+        assert_eq!(
+            lobby.queue_positions().collect::<Vec<_>>(),
+            [(second_id, 1)]
+        );
+
+        let (promoted_id, event) = lobby.leave(2u64.into()).unwrap();
+        assert_eq!(promoted_id, second_id);
+        assert_eq!(event_name(&event), "Second");
+        assert_eq!(lobby.queue_positions().count(), 0);
+    }
+
+    #[test]
+    fn leave_without_queue_frees_slot() {
+        let mut lobby = full_lobby();
+        assert!(lobby.leave(1u64.into()).is_none());
+        assert!(!lobby.is_full());
+        assert!(!lobby.is_active(1u64.into()));
+    }
+
+    #[test]
+    fn leaving_queue_doesnt_promote() {
+        let mut lobby = full_lobby();
+        let first_id = PlayerId::from(100u64);
+        let second_id = PlayerId::from(101u64);
+        lobby.join(first_id, connect_event("First"));
+        lobby.join(second_id, connect_event("Second"));
+
+        assert!(lobby.leave(first_id).is_none());
+        assert!(!lobby.is_active(first_id));
+        assert_eq!(
+            lobby.queue_positions().collect::<Vec<_>>(),
+            [(second_id, 1)]
+        );
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use skitspel::{HandshakeRejectReason, PlayerId};
+
+/// The max amount of graphemes (user-perceived characters) allowed in a name.
+pub(crate) const MAX_NAME_GRAPHEMES: usize = 16;
+
+/// Cleans up the `name` specified by a client. Control characters and zero
+/// width characters are removed and any leading/trailing whitespace is trimmed.
+///
+/// Returns the reason that the name should be rejected if the cleaned up name
+/// is empty or too long.
+pub(crate) fn sanitize_name(name: &str) -> Result<String, HandshakeRejectReason> {
+    let name = name
+        .chars()
+        .filter(|c| !c.is_control() && !is_zero_width(*c))
+        .collect::<String>();
+    let name = name.trim();
+
+    if name.is_empty() {
+        Err(HandshakeRejectReason::EmptyName)
+    } else if name.graphemes(true).count() > MAX_NAME_GRAPHEMES {
+        Err(HandshakeRejectReason::NameTooLong)
+    } else {
+        Ok(name.to_string())
+    }
+}
+
+/// Returns true if `c` is a character that is invisible when rendered, these
+/// can be used to create names that looks identical to other names.
+fn is_zero_width(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}' // Soft hyphen
+            | '\u{180E}' // Mongolian vowel separator
+            | '\u{200B}'..='\u{200F}' // Zero width space/joiners & direction marks
+            | '\u{202A}'..='\u{202E}' // Direction embedding/override
+            | '\u{2060}'..='\u{2064}' // Word joiner & invisible operators
+            | '\u{FEFF}' // Zero width no-break space
+    )
+}
+
+/// Keeps track of the names of all connected clients so that no two clients
+/// gets the same name.
+#[derive(Debug, Default)]
+pub(crate) struct Names(HashMap<PlayerId, String>);
+
+impl Names {
+    /// Registers the `name` for the client with ID `player_id`. If the name is
+    /// already taken by another client, a suffix is added to make it unique
+    /// (ex. "Anna" => "Anna (2)"). The name is truncated before the suffix is
+    /// added so that the unique name never is longer than `MAX_NAME_GRAPHEMES`.
+    /// Returns the name that was registered.
+    pub fn register(&mut self, player_id: PlayerId, name: &str) -> String {
+        let is_taken = |name: &str| {
+            self.0
+                .iter()
+                .any(|(id, other_name)| *id != player_id && other_name == name)
+        };
+
+        let mut unique_name = name.to_string();
+        let mut suffix = 2;
+        while is_taken(&unique_name) {
+            let suffix_str = suffix.to_string();
+            // The suffix is surrounded by " (" & ")".
+            let max_len = MAX_NAME_GRAPHEMES.saturating_sub(suffix_str.len() + 3);
+            let truncated_name = name.graphemes(true).take(max_len).collect::<String>();
+            unique_name = format!("{} ({})", truncated_name.trim_end(), suffix_str);
+            suffix += 1;
+        }
+
+        self.0.insert(player_id, unique_name.clone());
+        unique_name
+    }
+
+    pub fn remove(&mut self, player_id: PlayerId) {
+        self.0.remove(&player_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grapheme_count(name: &str) -> usize {
+        name.graphemes(true).count()
+    }
+
+    #[test]
+    fn sanitize_removes_invisible_characters_and_trims() {
+        assert_eq!(sanitize_name("  An\u{200B}na\n "), Ok("Anna".to_string()));
+    }
+
+    #[test]
+    fn sanitize_rejects_empty_names() {
+        assert_eq!(sanitize_name(""), Err(HandshakeRejectReason::EmptyName));
+        assert_eq!(
+            sanitize_name(" \u{200B}\u{FEFF}\t"),
+            Err(HandshakeRejectReason::EmptyName)
+        );
+    }
+
+    #[test]
+    fn sanitize_counts_graphemes() {
+        // "e" followed by a combining acute accent is one grapheme.
+        let max_name = "e\u{301}".repeat(MAX_NAME_GRAPHEMES);
+        assert_eq!(sanitize_name(&max_name), Ok(max_name));
+
+        let too_long_name = "e\u{301}".repeat(MAX_NAME_GRAPHEMES + 1);
+        assert_eq!(
+            sanitize_name(&too_long_name),
+            Err(HandshakeRejectReason::NameTooLong)
+        );
+    }
+
+    #[test]
+    fn register_adds_suffix_to_taken_names() {
+        let mut names = Names::default();
+        assert_eq!(names.register(1u64.into(), "Anna"), "Anna");
+        assert_eq!(names.register(2u64.into(), "Anna"), "Anna (2)");
+        assert_eq!(names.register(3u64.into(), "Anna"), "Anna (3)");
+
+        // Re-registering the same player doesn't clash with its own name.
+        assert_eq!(names.register(1u64.into(), "Anna"), "Anna");
+    }
+
+    #[test]
+    fn register_reuses_removed_names() {
+        let mut names = Names::default();
+        names.register(1u64.into(), "Anna");
+        names.remove(1u64.into());
+        assert_eq!(names.register(2u64.into(), "Anna"), "Anna");
+    }
+
+    #[test]
+    fn register_truncates_long_names_before_suffix() {
+        let mut names = Names::default();
+        let name = "abcdefghijklmnop";
+        assert_eq!(grapheme_count(name), MAX_NAME_GRAPHEMES);
+
+        assert_eq!(names.register(1u64.into(), name), name);
+        assert_eq!(names.register(2u64.into(), name), "abcdefghijkl (2)");
+    }
+
+    #[test]
+    fn register_trims_whitespace_left_by_truncation() {
+        let mut names = Names::default();
+        let name = "abcdefghijk mnop";
+        names.register(1u64.into(), name);
+        assert_eq!(names.register(2u64.into(), name), "abcdefghijk (2)");
+    }
+
+    #[test]
+    fn register_keeps_multi_digit_suffixes_within_limit() {
+        let mut names = Names::default();
+        let name = "e\u{301}".repeat(MAX_NAME_GRAPHEMES);
+        let mut unique_names = Vec::default();
+        for id in 1..=10u64 {
+            unique_names.push(names.register(id.into(), &name));
+        }
+
+        for unique_name in &unique_names {
+            assert!(grapheme_count(unique_name) <= MAX_NAME_GRAPHEMES);
+        }
+        let last_name = unique_names.last().unwrap();
+        assert!(last_name.ends_with(" (10)"));
+        assert_eq!(grapheme_count(last_name), MAX_NAME_GRAPHEMES);
+    }
+}
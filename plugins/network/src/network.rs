@@ -1,30 +1,60 @@
 use std::{
     collections::{hash_map::Entry, HashMap},
     error::Error,
-    net::{Ipv4Addr, SocketAddr},
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
     sync::{Arc, Mutex},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use async_native_tls::{Identity, TlsAcceptor};
-use async_tungstenite::WebSocketStream;
+use async_tungstenite::{
+    tungstenite::{
+        protocol::{frame::coding::CloseCode, CloseFrame},
+        Message,
+    },
+    WebSocketStream,
+};
 use bevy::{app::AppExit, prelude::*};
-use futures_util::{stream::StreamExt, AsyncRead, AsyncWrite};
+use futures_util::{stream::StreamExt, AsyncRead, AsyncWrite, SinkExt};
 use smol::{
     channel::{self, Receiver, Sender, TryRecvError},
+    lock::Mutex as AsyncMutex,
     net::{TcpListener, TcpStream},
+    Timer,
 };
 
-use skitspel::{ActionEvent, PlayerId, PlayerIdGenerator, Port, TLSCertificate};
+use skitspel::{
+    ActionEvent, HandshakeRejectReason, JoinUrl, PlayerId, PlayerIdGenerator, Port, ServerMessage,
+    ServerMessageEvent, TLSCertificate,
+};
 
 use crate::{
-    event::{decode_message, EventMessage, GeneralEvent, NetworkEvent, WebSocketSink},
+    event::{
+        decode_message, encode_server_message, is_legacy_connect, reject_reason_description,
+        EventMessage, GeneralEvent, HandshakeRequest, NetworkEvent, WebSocketSink, WebSocketSource,
+        FEATURE_SESSION_RESUME, PROTOCOL_VERSION, SUPPORTED_FEATURES,
+    },
+    http::{is_websocket_upgrade, read_request_head, serve_client_files, PrefixedStream},
+    lobby::Lobby,
+    name::{sanitize_name, Names},
+    session::{Sessions, SESSION_GRACE_PERIOD},
     wsstream::WsStream,
-    EventTimer,
 };
 
-/// The buffer size for the channel containing events.
+/// The time between pings sent to the clients to measure their latency.
+const PING_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The buffer size for the channel containing the general events (connects,
+/// disconnects etc.) that are read by the bevy systems.
 const EVENT_CHANNEL_BUF_SIZE: usize = 20;
 
+/// The buffer size for the channel that all events received from the clients
+/// are put into. The client handlers wait for a free slot when the channel is
+/// full, so a client flooding the server with input is slowed down in its own
+/// handler instead of making the channel grow without limit. No events are
+/// dropped.
+const INPUT_CHANNEL_BUF_SIZE: usize = 4096;
+
 /// This is the "central" struct of the network logic, all messages goes through
 /// this struct.
 ///
@@ -55,40 +85,92 @@ pub struct NetworkContext {
     /// client.
     ///
     /// These channels will be read by a "bevy system" which acts on the events.
-    /// Using one channel per client prevents one client to clog a potential
-    /// shared channel. This separation also makes it cleaner to read one message
-    /// for every client per game-tick.
+    /// All events that have been received are read every game-tick, in the
+    /// same order that they were sent by the client. The channels are unbounded
+    /// so no events (ex. a quick press & release) are ever dropped, and the
+    /// `event_message_handler` never has to wait on a single player. The rate
+    /// of the events is instead limited by `INPUT_CHANNEL_BUF_SIZE`.
     client_channels: HashMap<PlayerId, Receiver<ActionEvent>>,
 
-    /// Contains the sinks for the websocket clients. These are used to send
-    /// data to the clients.
-    client_websockets: HashMap<PlayerId, WebSocketSink>,
+    /// Contains the senders used to send data to the websocket clients. The
+    /// sink of every websocket is moved into its own `websocket_sink_handler`
+    /// which writes all messages put into the corresponding channel.
+    client_websockets: HashMap<PlayerId, Sender<ServerMessage>>,
+
+    /// Contains the senders used to send data to the spectators. Spectators
+    /// aren't players, they only receive the messages broadcasted to all
+    /// clients (and any messages sent directly to them).
+    spectator_websockets: HashMap<PlayerId, Sender<ServerMessage>>,
 
     /// Used to generate new unique player IDs.
     id_generator: Arc<Mutex<PlayerIdGenerator>>,
 }
 
 impl NetworkContext {
+    /// Sends the given `msg` to the client with ID `player_id` (which can be
+    /// the ID of a spectator). The message is written to the websocket
+    /// asynchronously, so this will never block.
+    pub fn send(&self, player_id: PlayerId, msg: ServerMessage) {
+        let client_tx_opt = self
+            .client_websockets
+            .get(&player_id)
+            .or_else(|| self.spectator_websockets.get(&player_id));
+        if let Some(client_tx) = client_tx_opt {
+            if let Err(err) = client_tx.try_send(msg) {
+                eprintln!(
+                    "Unable to send msg to player with ID {}: {:#?}",
+                    player_id, err
+                );
+            }
+        }
+    }
+
+    /// Sends the given `msg` to all connected clients, including spectators.
+    pub fn broadcast(&self, msg: ServerMessage) {
+        let client_ids = self.client_websockets.keys();
+        let spectator_ids = self.spectator_websockets.keys();
+        for player_id in client_ids.chain(spectator_ids) {
+            self.send(*player_id, msg.clone());
+        }
+    }
+
+    /// Generates a new unique player ID. This is used for players that aren't
+    /// connected over a websocket (ex. local players).
+    pub fn generate_player_id(&self) -> PlayerId {
+        self.id_generator.lock().unwrap().generate()
+    }
+
+    /// Puts the given `event_message` into the same channel as the events
+    /// received from the websocket clients. This allows events from other
+    /// sources (ex. local players) to be handled exactly like the events sent
+    /// from a websocket client.
+    ///
+    /// A player injected with a `Connected` event without a sink will not have
+    /// a websocket, so all messages sent to that player are ignored.
+    pub fn inject(&self, event_message: EventMessage) {
+        if let Some(channel_tx) = &self.channel_tx {
+            // This will only fail if the channel is full (see
+            // `INPUT_CHANNEL_BUF_SIZE`) or if it is closed (the server is
+            // shutting down).
+            if let Err(err) = channel_tx.try_send(event_message) {
+                eprintln!("Unable to inject event: {:#?}", err);
+            }
+        }
+    }
+
     pub fn iter_common(&mut self) -> GeneralMessageIter {
         GeneralMessageIter { network_ctx: self }
     }
 
-    /// Only returs an iterator if the `event_timer` have finished the time to
-    /// start a new tick.
-    pub fn iter_action<'s>(
-        &'s mut self,
-        time: &Time,
-        event_timer: &mut EventTimer,
-    ) -> Option<ActionMessageIter> {
-        if event_timer.0.tick(time.delta()).just_finished() {
-            let player_ids = self.client_websockets.keys().cloned().collect::<Vec<_>>();
-            Some(ActionMessageIter {
-                network_ctx: self,
-                player_ids,
-                cur_player_idx: 0,
-            })
-        } else {
-            None
+    /// Returns an iterator that drains all `ActionEvent`s that have been received
+    /// since the last call. The events of a specific player are returned in the
+    /// order that they were sent by the client.
+    pub fn iter_action(&mut self) -> ActionMessageIter {
+        let player_ids = self.client_channels.keys().cloned().collect::<Vec<_>>();
+        ActionMessageIter {
+            network_ctx: self,
+            player_ids,
+            cur_player_idx: 0,
         }
     }
 }
@@ -121,19 +203,17 @@ impl<'a> Iterator for ActionMessageIter<'a> {
     type Item = (PlayerId, ActionEvent);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.cur_player_idx >= self.player_ids.len() {
-            return None;
+        // Drains all events from the channel of the current player before moving
+        // on to the next player.
+        while let Some(player_id) = self.player_ids.get(self.cur_player_idx) {
+            if let Some(client_rx) = self.network_ctx.client_channels.get_mut(player_id) {
+                if let Ok(action_event) = client_rx.try_recv() {
+                    return Some((*player_id, action_event));
+                }
+            }
+            self.cur_player_idx += 1;
         }
-
-        let player_id = self.player_ids.get(self.cur_player_idx)?;
-        let client_rx = self.network_ctx.client_channels.get_mut(player_id)?;
-        let event = match client_rx.try_recv() {
-            Ok(action_event) => action_event,
-            Err(TryRecvError::Empty | TryRecvError::Closed) => ActionEvent::None,
-        };
-
-        self.cur_player_idx += 1;
-        Some((*player_id, event))
+        None
     }
 }
 
@@ -153,12 +233,13 @@ impl<'a> Iterator for ActionMessageIter<'a> {
 /// from clients. For every client, a new "task" is spawned that handles all
 /// communication with that specific client.
 pub(crate) fn setup_network(
+    mut commands: Commands,
     network_ctx: ResMut<Arc<Mutex<NetworkContext>>>,
     port: Res<Port>,
     tls_cert: Option<Res<TLSCertificate>>,
     mut exit: EventWriter<AppExit>,
 ) {
-    let (channel_tx, channel_rx) = channel::unbounded();
+    let (channel_tx, channel_rx) = channel::bounded(INPUT_CHANNEL_BUF_SIZE);
     let (common_client_tx, common_client_rx) = channel::bounded(EVENT_CHANNEL_BUF_SIZE);
     network_ctx.lock().unwrap().channel_tx = Some(channel_tx.clone());
     network_ctx.lock().unwrap().common_client_channel = Some(common_client_rx);
@@ -182,17 +263,66 @@ pub(crate) fn setup_network(
         None
     };
 
+    let join_url = join_url(port.0, tls.is_some());
+    println!("Players can join at: {}", join_url.0);
+    commands.insert_resource(join_url);
+
     let server_addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), port.0);
     let id_generator = Arc::clone(&network_ctx.lock().unwrap().id_generator);
+    let sessions = Arc::new(AsyncMutex::new(Sessions::default()));
     smol::spawn(websocket_listener(
         server_addr,
         id_generator,
+        sessions,
         channel_tx,
         tls,
     ))
     .detach();
 }
 
+/// Sends all `ServerMessageEvent`s created by the systems during this tick to
+/// the clients.
+pub(crate) fn handle_server_messages(
+    network_ctx: Res<Arc<Mutex<NetworkContext>>>,
+    mut server_message_reader: EventReader<ServerMessageEvent>,
+) {
+    let network_ctx_guard = network_ctx.lock().unwrap();
+    for server_message_event in server_message_reader.iter() {
+        match server_message_event {
+            ServerMessageEvent::Player(player_id, msg) => {
+                network_ctx_guard.send(*player_id, msg.clone())
+            }
+            ServerMessageEvent::Broadcast(msg) => network_ctx_guard.broadcast(msg.clone()),
+        }
+    }
+}
+
+/// Creates the URL that the players should use to join the game. The address
+/// is the IP of this computer on the local network.
+///
+/// Connections from private addresses never uses TLS (see `is_private_address`),
+/// so HTTPS is only used if TLS is enabled and the IP isn't private.
+fn join_url(port: u16, tls: bool) -> JoinUrl {
+    let ip = local_ip().unwrap_or_else(|| Ipv4Addr::LOCALHOST.into());
+    let addr = SocketAddr::new(ip, port);
+    let scheme = if tls && !is_private_address(addr) {
+        "https"
+    } else {
+        "http"
+    };
+    JoinUrl(format!("{}://{}/", scheme, addr))
+}
+
+/// Finds the IP of the network interface that is used to reach other computers.
+///
+/// "Connecting" a UDP socket doesn't send any packets, but the OS picks the
+/// local address that would be used for the route to the given address.
+fn local_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(8, 8, 8, 8), 80)).ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
 fn create_tls_acceptor(
     tls_cert: &TLSCertificate,
 ) -> Result<async_native_tls::TlsAcceptor, Box<dyn Error>> {
@@ -214,10 +344,14 @@ fn create_tls_acceptor(
 /// `event_ctx.client_channels` for every client (one time every game-tick).
 ///
 /// This function will:
-///  * Create/remove `event_ctx.client_channels` when a client connects/disconnects.
+///  * Create/remove `event_ctx.client_channels` when a player is admitted (or
+///    promoted from the queue) and when it disconnects.
 ///  * Create/remove `event_ctx.client_websockets` when a client connects/disconnects.
 ///  * Propagate messages from the `event_ctx.channel_tx` channel into the
 ///    corresponding `event_ctx.client_channels`/`event_ctx.common_client_channel`.
+///  * Admit at most `MAX_PLAYERS` active players. Clients connecting when all
+///    slots are taken are put in a queue and their events aren't propagated
+///    until they have been promoted to active players.
 async fn event_message_handler(
     event_ctx: Arc<Mutex<NetworkContext>>,
     channel_rx: Receiver<EventMessage>,
@@ -229,39 +363,145 @@ async fn event_message_handler(
     // `event_ctx.client_channels`.
     let mut client_channels_tx = HashMap::new();
 
+    let mut lobby = Lobby::default();
+    let mut names = Names::default();
+
     while let Ok(EventMessage {
         player_id,
         mut event,
     }) = channel_rx.recv().await
     {
-        // Edge-case to handle new connection. Need to setup all the structures
-        // before starting the "processing" of the message/event.
-        if let NetworkEvent::General(GeneralEvent::Connected(_, ref mut sink_opt)) = event {
+        // Bots are never put in the queue, they are only added if there is a
+        // free slot.
+        if let NetworkEvent::General(GeneralEvent::BotConnected(_)) = event {
+            if lobby.is_full() {
+                println!(
+                    "event_message_handler :: Server full, bot with ID {} not added.",
+                    player_id
+                );
+                continue;
+            }
+        }
+
+        // Edge-case to handle new connection (or a resumed session). Need to
+        // setup all the structures before starting the "processing" of the
+        // message/event.
+        let new_connection = match event {
+            NetworkEvent::General(GeneralEvent::Connected(_, _, ref mut sink_opt)) => {
+                println!(
+                    "event_message_handler :: Received connect from player with ID: {}",
+                    player_id
+                );
+                Some((sink_opt.take(), None))
+            }
+            NetworkEvent::General(GeneralEvent::BotConnected(_)) => {
+                println!(
+                    "event_message_handler :: Received connect from bot with ID: {}",
+                    player_id
+                );
+                Some((None, None))
+            }
+            NetworkEvent::General(GeneralEvent::Reconnected(token, ref mut sink_opt)) => {
+                println!(
+                    "event_message_handler :: Received reconnect from player with ID: {}",
+                    player_id
+                );
+                Some((sink_opt.take(), Some(token)))
+            }
+            _ => None,
+        };
+
+        if let NetworkEvent::General(GeneralEvent::SpectatorConnected(ref mut sink_opt)) = event {
             println!(
-                "event_message_handler :: Received connect from player with ID: {}",
+                "event_message_handler :: Received connect from spectator with ID: {}",
                 player_id
             );
-
-            let mut event_ctx_guard = event_ctx.lock().unwrap();
             if let Some(sink) = sink_opt.take() {
-                let (tx, rx) = channel::bounded(EVENT_CHANNEL_BUF_SIZE);
-                event_ctx_guard.client_channels.insert(player_id, rx);
-                client_channels_tx.insert(player_id, tx);
-                event_ctx_guard.client_websockets.insert(player_id, sink);
+                let (websocket_tx, websocket_rx) = channel::unbounded();
+                smol::spawn(websocket_sink_handler(player_id, sink, websocket_rx)).detach();
+                event_ctx
+                    .lock()
+                    .unwrap()
+                    .spectator_websockets
+                    .insert(player_id, websocket_tx);
             } else {
-                unreachable!("Received connect with no sink. Player ID: {}", player_id)
+                unreachable!("Received connect with no sink. Spectator ID: {}", player_id)
             }
         }
 
-        if let NetworkEvent::Action(car_event) = event {
-            // TODO: Probably shouldn't drop them now. Should sent messages on
-            //       pressed/release, so is important to read all messages.
+        if let Some((sink_opt, token_opt)) = new_connection {
+            let mut event_ctx_guard = event_ctx.lock().unwrap();
+
+            // Players without a sink are local players or bots (injected
+            // through `NetworkContext::inject`), they don't have a websocket.
+            if let Some(sink) = sink_opt {
+                let (websocket_tx, websocket_rx) = channel::unbounded();
+                smol::spawn(websocket_sink_handler(player_id, sink, websocket_rx)).detach();
+                if let Some(token) = token_opt {
+                    // Lets the client know that the session was resumed.
+                    let _ = websocket_tx.try_send(ServerMessage::SessionToken(token));
+                }
+                event_ctx_guard
+                    .client_websockets
+                    .insert(player_id, websocket_tx);
+            }
+        }
+
+        // Makes sure that the name of a newly connected player is unique. The
+        // client is told about the name that it got.
+        if let NetworkEvent::General(
+            GeneralEvent::Connected(ref mut name, ..) | GeneralEvent::BotConnected(ref mut name),
+        ) = event
+        {
+            *name = names.register(player_id, name);
+            event_ctx
+                .lock()
+                .unwrap()
+                .send(player_id, ServerMessage::Name(name.clone()));
+        }
+
+        // Only events from active players are propagated. Clients waiting in the
+        // queue aren't players (yet).
+        let propagate = match event {
+            NetworkEvent::General(
+                ref general_event @ (GeneralEvent::Connected(..) | GeneralEvent::BotConnected(_)),
+            ) => {
+                let admitted = lobby.join(player_id, general_event.clone());
+                if admitted {
+                    open_action_channel(&event_ctx, &mut client_channels_tx, player_id);
+                } else {
+                    println!(
+                        "event_message_handler :: Server full, player with ID {} put in queue.",
+                        player_id
+                    );
+                    send_queue_positions(&event_ctx, &lobby);
+                }
+                admitted
+            }
+            NetworkEvent::General(GeneralEvent::Reconnected(..)) => {
+                if lobby.is_active(player_id) {
+                    open_action_channel(&event_ctx, &mut client_channels_tx, player_id);
+                } else {
+                    send_queue_positions(&event_ctx, &lobby);
+                }
+                lobby.is_active(player_id)
+            }
+            NetworkEvent::General(
+                GeneralEvent::Dropped | GeneralEvent::Disconnected | GeneralEvent::Latency(_),
+            ) => lobby.is_active(player_id),
+            _ => true,
+        };
+
+        if let NetworkEvent::Action(action_event) = event {
             match client_channels_tx.entry(player_id) {
                 Entry::Occupied(mut entry) => {
-                    // Drop any event that doesn't fit into the channel. We don't
-                    // want to buffer old, delayed, inputs.
-                    let _ = entry.get_mut().try_send(car_event);
+                    // The channel is unbounded, so this will only fail if the
+                    // channel is closed (the player is disconnecting).
+                    let _ = entry.get_mut().try_send(action_event);
                 }
+                // Clients waiting in the queue have no action channel, their
+                // actions are ignored until they are promoted.
+                Entry::Vacant(_) if !lobby.is_active(player_id) => (),
                 Entry::Vacant(_) => unreachable!(
                     "Received message from non-existing player with ID: {}",
                     player_id
@@ -275,19 +515,48 @@ async fn event_message_handler(
                 player_id,
                 event: event.clone(),
             });
-        } else if let Err(err) = common_client_tx
-            .send(EventMessage {
-                player_id,
-                event: event.clone(),
-            })
-            .await
-        {
-            println!("Unable to send msg to common client: {}", err);
+        } else if propagate {
+            if let Err(err) = common_client_tx
+                .send(EventMessage {
+                    player_id,
+                    event: event.clone(),
+                })
+                .await
+            {
+                println!("Unable to send msg to common client: {}", err);
+            }
+        }
+
+        // A slot is freed up when a player disconnects. Promote the first
+        // client in the queue (if any) and let it connect as a new player.
+        if let NetworkEvent::General(GeneralEvent::Disconnected) = event {
+            names.remove(player_id);
+            if let Some((promoted_id, connect_event)) = lobby.leave(player_id) {
+                println!(
+                    "event_message_handler :: Player with ID {} promoted from queue.",
+                    promoted_id
+                );
+                open_action_channel(&event_ctx, &mut client_channels_tx, promoted_id);
+                event_ctx
+                    .lock()
+                    .unwrap()
+                    .send(promoted_id, ServerMessage::QueuePosition(0));
+                if let Err(err) = common_client_tx
+                    .send(EventMessage {
+                        player_id: promoted_id,
+                        event: NetworkEvent::General(connect_event),
+                    })
+                    .await
+                {
+                    println!("Unable to send msg to common client: {}", err);
+                }
+            }
+            send_queue_positions(&event_ctx, &lobby);
         }
 
         // Edge-case to handle disconnects. Need to handle the message/event
         // before starting to remove the now unnused structures.
-        if let NetworkEvent::General(GeneralEvent::Disconnected) = event {
+        if let NetworkEvent::General(GeneralEvent::Dropped | GeneralEvent::Disconnected) = event {
             println!(
                 "event_message_handler :: Received disconnect from player with ID: {}",
                 player_id
@@ -299,12 +568,114 @@ async fn event_message_handler(
                 rx.close();
             }
             event_ctx_guard.client_websockets.remove(&player_id);
+        } else if let NetworkEvent::General(GeneralEvent::SpectatorDisconnected) = event {
+            println!(
+                "event_message_handler :: Received disconnect from spectator with ID: {}",
+                player_id
+            );
+            event_ctx
+                .lock()
+                .unwrap()
+                .spectator_websockets
+                .remove(&player_id);
         }
     }
 
     println!("event_message_handler :: Stopped");
 }
 
+/// Creates the channel that the `ActionEvent`s of the player with ID `player_id`
+/// are sent through. This is only done for active players, clients waiting in
+/// the queue have no channel so that their actions never reaches the games.
+fn open_action_channel(
+    event_ctx: &Arc<Mutex<NetworkContext>>,
+    client_channels_tx: &mut HashMap<PlayerId, Sender<ActionEvent>>,
+    player_id: PlayerId,
+) {
+    let (tx, rx) = channel::unbounded();
+    event_ctx
+        .lock()
+        .unwrap()
+        .client_channels
+        .insert(player_id, rx);
+    client_channels_tx.insert(player_id, tx);
+}
+
+/// Lets all clients waiting in the queue know their current position.
+fn send_queue_positions(event_ctx: &Arc<Mutex<NetworkContext>>, lobby: &Lobby) {
+    let event_ctx_guard = event_ctx.lock().unwrap();
+    for (player_id, position) in lobby.queue_positions() {
+        event_ctx_guard.send(player_id, ServerMessage::QueuePosition(position));
+    }
+}
+
+/// Writes all messages put into the `websocket_rx` channel to the websocket of
+/// the client with ID `player_id`. A ping is also sent every `PING_INTERVAL`,
+/// the pong sent back by the client is used to measure the latency.
+///
+/// This will run until the corresponding sender is removed from the
+/// `NetworkContext` (when the client disconnects) or until the websocket can't
+/// be written to anymore.
+async fn websocket_sink_handler(
+    player_id: PlayerId,
+    mut sink: WebSocketSink,
+    websocket_rx: Receiver<ServerMessage>,
+) {
+    let mut next_ping = Instant::now() + PING_INTERVAL;
+    loop {
+        let recv_msg = async {
+            let msg = websocket_rx.recv().await.ok()?;
+            Some(Message::Binary(encode_server_message(&msg)))
+        };
+        let ping_timer = async {
+            Timer::at(next_ping).await;
+            Some(Message::Ping(ping_payload()))
+        };
+
+        let msg = match smol::future::or(recv_msg, ping_timer).await {
+            Some(msg) => msg,
+            None => break,
+        };
+        if let Message::Ping(_) = msg {
+            next_ping = Instant::now() + PING_INTERVAL;
+        }
+
+        if let Err(err) = sink.send(msg).await {
+            eprintln!(
+                "Unable to write message to websocket of player with ID {}: {}",
+                player_id, err
+            );
+            break;
+        }
+    }
+}
+
+/// Creates the payload of a ping. The payload is the current time in
+/// microseconds. The client sends back the same payload in its pong.
+fn ping_payload() -> Vec<u8> {
+    timestamp_micros().to_be_bytes().to_vec()
+}
+
+/// Calculates the round-trip time from the payload of a pong sent from a
+/// client. Returns None if the payload isn't a payload created by the server.
+fn pong_rtt(payload: &[u8]) -> Option<Duration> {
+    let mut timestamp_bytes = [0; 8];
+    if payload.len() != timestamp_bytes.len() {
+        return None;
+    }
+    timestamp_bytes.copy_from_slice(payload);
+    let ping_timestamp = u64::from_be_bytes(timestamp_bytes);
+    let rtt_micros = timestamp_micros().checked_sub(ping_timestamp)?;
+    Some(Duration::from_micros(rtt_micros))
+}
+
+fn timestamp_micros() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_micros() as u64)
+        .unwrap_or(0)
+}
+
 /// Listens and accepts new websocket connections.
 ///
 /// When a new connection is established, this function spawns a
@@ -313,6 +684,7 @@ async fn event_message_handler(
 async fn websocket_listener(
     server_addr: SocketAddr,
     id_generator: Arc<Mutex<PlayerIdGenerator>>,
+    sessions: Arc<AsyncMutex<Sessions>>,
     channel_tx: Sender<EventMessage>,
     tls: Option<Arc<TlsAcceptor>>,
 ) {
@@ -338,9 +710,9 @@ async fn websocket_listener(
                     None
                 };
 
-                let player_id = id_generator.lock().unwrap().generate();
                 smol::spawn(websocket_client_handler(
-                    player_id,
+                    Arc::clone(&id_generator),
+                    Arc::clone(&sessions),
                     channel_tx.clone(),
                     stream,
                     client_addr,
@@ -366,16 +738,26 @@ async fn websocket_listener(
 /// When a new client connects to the server, one of these function will be
 /// spawned which will work as a proxy to read/write data between the client and
 /// the variables in the `NetworkContext`.
+///
+/// The first message sent by the client must be a handshake. If the protocol
+/// version of the client doesn't match the version of the server, the handshake
+/// is rejected and the connection is closed. The handshake either contains a
+/// connect request (in which case a new player and session is created) or a
+/// resume request containing a session token (in which case the old session is
+/// resumed). When the connection closes, the session is kept alive during the
+/// grace period before the player is seen as disconnected (if the client
+/// supports resuming sessions).
 async fn websocket_client_handler(
-    player_id: PlayerId,
+    id_generator: Arc<Mutex<PlayerIdGenerator>>,
+    sessions: Arc<AsyncMutex<Sessions>>,
     channel_tx: Sender<EventMessage>,
     client_stream: TcpStream,
     client_addr: SocketAddr,
     tls: Option<Arc<TlsAcceptor>>,
 ) {
-    println!("Started client handler for player with ID: {}.", player_id);
+    println!("Started client handler for addr: {}.", client_addr);
 
-    let (client_tx, mut client_rx) = if let Some(tls) = tls {
+    let (mut client_tx, mut client_rx) = if let Some(tls) = tls {
         match tls.accept(client_stream).await {
             Ok(tls_client_stream) => match accept(tls_client_stream, client_addr).await {
                 Some(stream) => WsStream::Tls(stream),
@@ -402,45 +784,191 @@ async fn websocket_client_handler(
         Some(Ok(msg)) => msg,
         Some(Err(err)) => {
             eprintln!(
-                "Received error from client with player id \"{}\" when waiting for connect msg: {}",
-                player_id, err
+                "Received error from client with addr {} when waiting for handshake: {}",
+                client_addr, err
             );
             return;
         }
         None => {
             eprintln!(
-                "Channel closed by client with player id \"{}\" when waiting for connect msg.",
-                player_id
+                "Channel closed by client with addr {} when waiting for handshake.",
+                client_addr
             );
             return;
         }
     };
 
-    let connect_event = decode_message(&connect_msg.into_data());
-    let name = if let NetworkEvent::General(GeneralEvent::Connected(name, _)) = connect_event {
-        name
-    } else {
-        eprintln!(
-            "Got invalid message type when expecting connect msg from player with ID {}: {:#?}",
-            player_id, connect_event
-        );
-        return;
+    let connect_data = connect_msg.into_data();
+    let handshake = match decode_message(&connect_data) {
+        NetworkEvent::Handshake(handshake) => handshake,
+        _ if is_legacy_connect(&connect_data) => {
+            reject_handshake(
+                client_tx,
+                client_addr,
+                HandshakeRejectReason::OutdatedClient,
+            )
+            .await;
+            return;
+        }
+        event => {
+            eprintln!(
+                "Got invalid message type when expecting handshake from addr {}: {:#?}",
+                client_addr, event
+            );
+            reject_handshake(
+                client_tx,
+                client_addr,
+                HandshakeRejectReason::InvalidHandshake,
+            )
+            .await;
+            return;
+        }
     };
 
-    if let Err(err) = channel_tx
-        .send(EventMessage {
-            player_id,
-            event: NetworkEvent::General(GeneralEvent::Connected(name, Some(client_tx))),
-        })
-        .await
-    {
-        eprintln!(
-            "Unable to put connect message into internal channel for player with ID {}: {:#?}",
-            player_id, err
-        );
+    if handshake.version != PROTOCOL_VERSION {
+        let reason = if handshake.version < PROTOCOL_VERSION {
+            HandshakeRejectReason::OutdatedClient
+        } else {
+            HandshakeRejectReason::OutdatedServer
+        };
+        reject_handshake(client_tx, client_addr, reason).await;
         return;
     }
 
+    let features = handshake.features & SUPPORTED_FEATURES;
+    let accept_msg = encode_server_message(&ServerMessage::HandshakeAccepted {
+        version: PROTOCOL_VERSION,
+        features,
+    });
+
+    let (player_id, generation) = match handshake.request {
+        HandshakeRequest::Connect(name) => {
+            let name = match sanitize_name(&name) {
+                Ok(name) => name,
+                Err(reason) => {
+                    reject_handshake(client_tx, client_addr, reason).await;
+                    return;
+                }
+            };
+
+            let player_id = id_generator.lock().unwrap().generate();
+            let (token, generation) = sessions.lock().await.create(player_id);
+
+            let mut init_msgs = vec![accept_msg];
+            if features & FEATURE_SESSION_RESUME != 0 {
+                init_msgs.push(encode_server_message(&ServerMessage::SessionToken(token)));
+            }
+            for init_msg in init_msgs {
+                if let Err(err) = client_tx.send(Message::Binary(init_msg)).await {
+                    eprintln!(
+                        "Unable to send handshake response to player with ID {}: {}",
+                        player_id, err
+                    );
+                    sessions.lock().await.remove(player_id);
+                    return;
+                }
+            }
+
+            if let Err(err) = channel_tx
+                .send(EventMessage {
+                    player_id,
+                    event: NetworkEvent::General(GeneralEvent::Connected(
+                        name.clone(),
+                        name,
+                        Some(client_tx),
+                    )),
+                })
+                .await
+            {
+                eprintln!(
+                    "Unable to put connect message into internal channel for player with ID {}: {:#?}",
+                    player_id, err
+                );
+                return;
+            }
+
+            (player_id, generation)
+        }
+
+        HandshakeRequest::Spectate => {
+            spectator_handler(
+                id_generator,
+                channel_tx,
+                client_tx,
+                client_rx,
+                client_addr,
+                accept_msg,
+            )
+            .await;
+            return;
+        }
+
+        HandshakeRequest::Resume(token) => {
+            let resumed = sessions.lock().await.resume(token);
+            let (player_id, generation) = match resumed {
+                Some(session) => session,
+                None => {
+                    eprintln!(
+                        "Client with addr {} tried to resume an unknown or expired session.",
+                        client_addr
+                    );
+                    reject_handshake(
+                        client_tx,
+                        client_addr,
+                        HandshakeRejectReason::SessionExpired,
+                    )
+                    .await;
+                    return;
+                }
+            };
+
+            if let Err(err) = client_tx.send(Message::Binary(accept_msg)).await {
+                eprintln!(
+                    "Unable to send handshake response to player with ID {}: {}",
+                    player_id, err
+                );
+                // The session have already been resumed, so the reconnect must
+                // still be registered. It will be dropped when the read fails.
+            }
+
+            // The event is put into the channel while holding the lock. This
+            // ensures that a `Dropped` event from an old connection of the same
+            // session can't be put into the channel after this `Reconnected`
+            // event (unless the session is resumed again by a newer connection).
+            {
+                let sessions_guard = sessions.lock().await;
+                if !sessions_guard.is_current(player_id, generation) {
+                    // Already resumed by a newer connection.
+                    return;
+                }
+
+                if let Err(err) = channel_tx
+                    .send(EventMessage {
+                        player_id,
+                        event: NetworkEvent::General(GeneralEvent::Reconnected(
+                            token,
+                            Some(client_tx),
+                        )),
+                    })
+                    .await
+                {
+                    eprintln!(
+                        "Unable to put reconnect message into internal channel for player with ID {}: {:#?}",
+                        player_id, err
+                    );
+                    return;
+                }
+            }
+
+            (player_id, generation)
+        }
+    };
+
+    println!(
+        "Client with addr {} connected as player with ID: {}.",
+        client_addr, player_id
+    );
+
     loop {
         let msg_result = match client_rx.next().await {
             Some(msg_result) => msg_result,
@@ -461,7 +989,16 @@ async fn websocket_client_handler(
             }
         };
 
-        let event = decode_message(&msg.into_data());
+        let event = match msg {
+            Message::Pong(payload) => match pong_rtt(&payload) {
+                Some(rtt) => NetworkEvent::General(GeneralEvent::Latency(rtt)),
+                None => continue,
+            },
+            // Pings are answered automatically and the close is handled when
+            // the stream ends.
+            Message::Ping(_) | Message::Close(_) => continue,
+            msg => decode_message(&msg.into_data()),
+        };
         if let Err(err) = channel_tx.send(EventMessage { player_id, event }).await {
             eprintln!(
                 "Unable to put message into internal channel for player with ID {}: {:#?}",
@@ -470,26 +1007,183 @@ async fn websocket_client_handler(
         }
     }
 
+    // If the session have been resumed by a newer connection, there is nothing
+    // more to do. Otherwise let the game know that the connection dropped and
+    // wait for the grace period to see if the client reconnects.
+    {
+        let mut sessions_guard = sessions.lock().await;
+        if !sessions_guard.drop_connection(player_id, generation) {
+            println!("Stopped client handler for player with ID: {}.", player_id);
+            return;
+        }
+
+        if let Err(err) = channel_tx
+            .send(EventMessage {
+                player_id,
+                event: NetworkEvent::General(GeneralEvent::Dropped),
+            })
+            .await
+        {
+            eprintln!(
+                "Unable to put dropped message into internal channel for player with ID {}: {:#?}",
+                player_id, err
+            );
+        }
+    }
+
+    // Clients that can't resume sessions are disconnected directly.
+    if features & FEATURE_SESSION_RESUME != 0 {
+        Timer::after(SESSION_GRACE_PERIOD).await;
+    }
+
+    let mut sessions_guard = sessions.lock().await;
+    if sessions_guard.expire(player_id, generation) {
+        if let Err(err) = channel_tx
+            .send(EventMessage {
+                player_id,
+                event: NetworkEvent::General(GeneralEvent::Disconnected),
+            })
+            .await
+        {
+            eprintln!(
+                "Unable to put disconnect message into internal channel for player with ID {}: {:#?}",
+                player_id, err
+            );
+        }
+    }
+
+    println!("Stopped client handler for player with ID: {}.", player_id);
+}
+
+/// Handles all communication with a spectator. The spectator gets its own ID,
+/// but it is never seen as a player. Any messages sent from the spectator are
+/// ignored, this function only waits for the connection to close.
+async fn spectator_handler(
+    id_generator: Arc<Mutex<PlayerIdGenerator>>,
+    channel_tx: Sender<EventMessage>,
+    mut client_tx: WebSocketSink,
+    mut client_rx: WebSocketSource,
+    client_addr: SocketAddr,
+    accept_msg: Vec<u8>,
+) {
+    let spectator_id = id_generator.lock().unwrap().generate();
+
+    if let Err(err) = client_tx.send(Message::Binary(accept_msg)).await {
+        eprintln!(
+            "Unable to send handshake response to spectator with ID {}: {}",
+            spectator_id, err
+        );
+        return;
+    }
+
     if let Err(err) = channel_tx
         .send(EventMessage {
-            player_id,
-            event: NetworkEvent::General(GeneralEvent::Disconnected),
+            player_id: spectator_id,
+            event: NetworkEvent::General(GeneralEvent::SpectatorConnected(Some(client_tx))),
         })
         .await
     {
         eprintln!(
-            "Unable to put disconnect message into internal channel for player with ID {}: {:#?}",
-            player_id, err
+            "Unable to put connect message into internal channel for spectator with ID {}: {:#?}",
+            spectator_id, err
         );
+        return;
     }
 
-    println!("Stopped client handler for player with ID: {}.", player_id);
+    println!(
+        "Client with addr {} connected as spectator with ID: {}.",
+        client_addr, spectator_id
+    );
+
+    while let Some(Ok(_)) = client_rx.next().await {}
+
+    if let Err(err) = channel_tx
+        .send(EventMessage {
+            player_id: spectator_id,
+            event: NetworkEvent::General(GeneralEvent::SpectatorDisconnected),
+        })
+        .await
+    {
+        eprintln!(
+            "Unable to put disconnect message into internal channel for spectator with ID {}: {:#?}",
+            spectator_id, err
+        );
+    }
+
+    println!(
+        "Stopped client handler for spectator with ID: {}.",
+        spectator_id
+    );
 }
 
-async fn accept<S>(client_stream: S, client_addr: SocketAddr) -> Option<WebSocketStream<S>>
+/// Lets the client know that its handshake was rejected and closes the
+/// connection. The reason is also set in the close frame so that it is visible
+/// for clients that doesn't understand the handshake response.
+async fn reject_handshake(
+    mut client_tx: WebSocketSink,
+    client_addr: SocketAddr,
+    reason: HandshakeRejectReason,
+) {
+    println!(
+        "Rejected handshake from client with addr {}: {:?}",
+        client_addr, reason
+    );
+
+    let reject_msg = encode_server_message(&ServerMessage::HandshakeRejected {
+        reason,
+        version: PROTOCOL_VERSION,
+    });
+    let close_frame = CloseFrame {
+        code: CloseCode::Policy,
+        reason: reject_reason_description(reason).into(),
+    };
+
+    if let Err(err) = client_tx.send(Message::Binary(reject_msg)).await {
+        eprintln!(
+            "Unable to send handshake rejection to addr {}: {}",
+            client_addr, err
+        );
+    } else if let Err(err) = client_tx.send(Message::Close(Some(close_frame))).await {
+        eprintln!(
+            "Unable to close connection with addr {}: {}",
+            client_addr, err
+        );
+    }
+}
+
+/// Reads the HTTP request sent by the client. If it is a websocket upgrade
+/// request, the websocket connection is established. Otherwise the request is
+/// seen as a request for one of the files of the web client, which is served
+/// before the connection is closed (in which case None is returned).
+async fn accept<S>(
+    mut client_stream: S,
+    client_addr: SocketAddr,
+) -> Option<WebSocketStream<PrefixedStream<S>>>
 where
     S: AsyncRead + AsyncWrite + Unpin,
 {
+    let head = match read_request_head(&mut client_stream).await {
+        Ok(head) => head,
+        Err(err) => {
+            eprintln!(
+                "Unable to read HTTP request from addr {}: {:#?}",
+                client_addr, err
+            );
+            return None;
+        }
+    };
+
+    if !is_websocket_upgrade(&head) {
+        if let Err(err) = serve_client_files(&mut client_stream, &head).await {
+            eprintln!(
+                "Unable to serve client files to addr {}: {:#?}",
+                client_addr, err
+            );
+        }
+        return None;
+    }
+
+    let client_stream = PrefixedStream::new(head, client_stream);
     match async_tungstenite::accept_async(client_stream).await {
         Ok(websocket_stream) => Some(websocket_stream),
         Err(err) => {
@@ -0,0 +1,229 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use skitspel::PlayerId;
+
+/// The time that a session is kept alive after its connection have dropped.
+/// If the client reconnects within this time, it will resume the session and
+/// keep its `PlayerId` (and with that its `Player` containing score, color etc.).
+pub(crate) const SESSION_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
+/// Keeps track of the sessions of all clients. A session is created when a
+/// client connects and a token for the session is given to the client. The
+/// client can use the token to resume the session if its connection drops.
+#[derive(Debug, Default)]
+pub(crate) struct Sessions {
+    /// The key is the token given to the client.
+    sessions: HashMap<u64, Session>,
+
+    /// Maps the ID of a player to the token of its session.
+    tokens: HashMap<PlayerId, u64>,
+}
+
+#[derive(Debug)]
+struct Session {
+    player_id: PlayerId,
+
+    /// Incremented every time that the session is resumed. A client might
+    /// reconnect before the server have noticed that the old connection is dead.
+    /// This is used to ensure that the old connection can't drop the session
+    /// when it eventually closes.
+    generation: usize,
+
+    /// Set to the time when the connection dropped. Set to None if the session
+    /// currently has an active connection.
+    dropped_at: Option<Instant>,
+}
+
+impl Sessions {
+    /// Creates a new session for the player with ID `player_id`. Returns the
+    /// token of the session together with the generation of the connection.
+    pub fn create(&mut self, player_id: PlayerId) -> (u64, usize) {
+        let mut token = rand::random::<u64>();
+        while self.sessions.contains_key(&token) {
+            token = rand::random::<u64>();
+        }
+
+        let generation = 0;
+        self.sessions.insert(
+            token,
+            Session {
+                player_id,
+                generation,
+                dropped_at: None,
+            },
+        );
+        self.tokens.insert(player_id, token);
+        (token, generation)
+    }
+
+    /// Resumes the session with the given `token`. Returns the ID of the player
+    /// together with the generation of the new connection. Returns None if no
+    /// session with the given token exists or if the grace period have passed.
+    pub fn resume(&mut self, token: u64) -> Option<(PlayerId, usize)> {
+        let session = self.sessions.get_mut(&token)?;
+        if let Some(dropped_at) = session.dropped_at {
+            if dropped_at.elapsed() > SESSION_GRACE_PERIOD {
+                return None;
+            }
+        }
+
+        session.dropped_at = None;
+        session.generation += 1;
+        Some((session.player_id, session.generation))
+    }
+
+    /// Returns true if the connection with the given `generation` is the latest
+    /// connection of the session of the player with ID `player_id`.
+    pub fn is_current(&self, player_id: PlayerId, generation: usize) -> bool {
+        self.tokens
+            .get(&player_id)
+            .and_then(|token| self.sessions.get(token))
+            .map(|session| session.generation == generation)
+            .unwrap_or(false)
+    }
+
+    /// Removes the session of the player with ID `player_id` directly, without
+    /// waiting for the grace period.
+    pub fn remove(&mut self, player_id: PlayerId) {
+        if let Some(token) = self.tokens.remove(&player_id) {
+            self.sessions.remove(&token);
+        }
+    }
+
+    /// Marks the session of the player with ID `player_id` as dropped. Returns
+    /// false if the session have been resumed by a newer connection (with a
+    /// different `generation`), in that case nothing is done.
+    pub fn drop_connection(&mut self, player_id: PlayerId, generation: usize) -> bool {
+        let token = match self.tokens.get(&player_id) {
+            Some(token) => token,
+            None => return false,
+        };
+
+        match self.sessions.get_mut(token) {
+            Some(session) if session.generation == generation => {
+                session.dropped_at = Some(Instant::now());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes the session of the player with ID `player_id` if it is still
+    /// dropped (it haven't been resumed since it was dropped). Returns true if
+    /// the session was removed.
+    pub fn expire(&mut self, player_id: PlayerId, generation: usize) -> bool {
+        let token = match self.tokens.get(&player_id) {
+            Some(token) => *token,
+            None => return false,
+        };
+
+        match self.sessions.get(&token) {
+            Some(session) if session.generation == generation && session.dropped_at.is_some() => {
+                self.sessions.remove(&token);
+                self.tokens.remove(&player_id);
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Moves the time that the session with the given `token` dropped back in
+    /// time by `duration`.
+    fn set_dropped_ago(sessions: &mut Sessions, token: u64, duration: Duration) {
+        let session = sessions.sessions.get_mut(&token).unwrap();
+        session.dropped_at = Some(Instant::now().checked_sub(duration).unwrap());
+    }
+
+    #[test]
+    fn resume_keeps_player_id_and_bumps_generation() {
+        let mut sessions = Sessions::default();
+        let player_id = PlayerId::from(1u64);
+        let (token, generation) = sessions.create(player_id);
+        assert_eq!(generation, 0);
+
+        assert!(sessions.drop_connection(player_id, generation));
+        assert_eq!(sessions.resume(token), Some((player_id, 1)));
+        assert!(sessions.is_current(player_id, 1));
+        assert!(!sessions.is_current(player_id, 0));
+    }
+
+    #[test]
+    fn resume_with_unknown_token_fails() {
+        let mut sessions = Sessions::default();
+        let (token, _) = sessions.create(1u64.into());
+        assert_eq!(sessions.resume(token.wrapping_add(1)), None);
+    }
+
+    #[test]
+    fn old_connection_cant_drop_resumed_session() {
+        let mut sessions = Sessions::default();
+        let player_id = PlayerId::from(1u64);
+        let (token, old_generation) = sessions.create(player_id);
+
+        // The client reconnects before the old connection have been noticed
+        // as dead.
+        let (_, new_generation) = sessions.resume(token).unwrap();
+        assert!(!sessions.drop_connection(player_id, old_generation));
+        assert!(!sessions.expire(player_id, old_generation));
+        assert!(sessions.is_current(player_id, new_generation));
+    }
+
+    #[test]
+    fn resume_within_grace_period() {
+        let mut sessions = Sessions::default();
+        let player_id = PlayerId::from(1u64);
+        let (token, generation) = sessions.create(player_id);
+
+        sessions.drop_connection(player_id, generation);
+        set_dropped_ago(&mut sessions, token, SESSION_GRACE_PERIOD / 2);
+        assert_eq!(sessions.resume(token), Some((player_id, 1)));
+    }
+
+    #[test]
+    fn resume_after_grace_period_fails() {
+        let mut sessions = Sessions::default();
+        let player_id = PlayerId::from(1u64);
+        let (token, generation) = sessions.create(player_id);
+
+        sessions.drop_connection(player_id, generation);
+        set_dropped_ago(
+            &mut sessions,
+            token,
+            SESSION_GRACE_PERIOD + Duration::from_secs(1),
+        );
+        assert_eq!(sessions.resume(token), None);
+    }
+
+    #[test]
+    fn expire_only_removes_dropped_sessions() {
+        let mut sessions = Sessions::default();
+        let player_id = PlayerId::from(1u64);
+        let (token, generation) = sessions.create(player_id);
+
+        // The connection is still active.
+        assert!(!sessions.expire(player_id, generation));
+
+        sessions.drop_connection(player_id, generation);
+        assert!(sessions.expire(player_id, generation));
+        assert!(!sessions.is_current(player_id, generation));
+        assert_eq!(sessions.resume(token), None);
+    }
+
+    #[test]
+    fn remove_drops_session_directly() {
+        let mut sessions = Sessions::default();
+        let player_id = PlayerId::from(1u64);
+        let (token, _) = sessions.create(player_id);
+
+        sessions.remove(player_id);
+        assert_eq!(sessions.resume(token), None);
+    }
+}
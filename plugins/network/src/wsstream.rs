@@ -11,6 +11,8 @@ use async_tungstenite::{
 use futures_util::{Sink, Stream};
 use smol::net::TcpStream;
 
+use crate::http::PrefixedStream;
+
 /// A WebSocket or WebSocket+TLS connection.
 ///
 /// Taken from:
@@ -19,10 +21,10 @@ use smol::net::TcpStream;
 #[derive(Debug)]
 pub enum WsStream {
     /// A plain WebSocket connection.
-    Plain(WebSocketStream<TcpStream>),
+    Plain(WebSocketStream<PrefixedStream<TcpStream>>),
 
     /// A WebSocket connection secured by TLS.
-    Tls(WebSocketStream<TlsStream<TcpStream>>),
+    Tls(WebSocketStream<PrefixedStream<TlsStream<TcpStream>>>),
 }
 
 impl Sink<Message> for WsStream {
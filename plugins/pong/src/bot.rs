@@ -0,0 +1,94 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::{
+    math::Vec2,
+    prelude::{Query, ResMut, Transform},
+};
+
+use skitspel::{BotInput, PlayerId, Players};
+use util_bevy::apply_bot_input;
+
+use crate::{colliders::Colliders, util, Ball, PLAYER_RADIUS_INNER};
+
+/// When the paddle of a bot is closer than this angle (in radians) to its
+/// target, the bot starts to slow down to not overshoot the target.
+const BOT_SLOW_ANGLE: f32 = PI / 16.0;
+
+/// Moves the paddles of the bots towards the position where the ball will hit
+/// their goals. If the ball isn't heading towards the goal of a bot, the bot
+/// moves back to the middle of its goal.
+pub(crate) fn handle_bots(
+    mut players: ResMut<Players>,
+    ball_query: Query<(&Ball, &Transform)>,
+    colliders_query: Query<&Colliders>,
+) {
+    let (ball, ball_transform) = ball_query.single().unwrap();
+    let colliders = colliders_query.single().unwrap();
+
+    let ball_pos = Vec2::new(ball_transform.translation.x, ball_transform.translation.y);
+    let hit_angle = predict_hit_angle(ball_pos, ball.angle);
+
+    let bot_ids = players
+        .values()
+        .filter(|player| player.is_bot())
+        .map(|player| player.id())
+        .collect::<Vec<_>>();
+
+    for bot_id in bot_ids {
+        let input = bot_input(&colliders, bot_id, hit_angle);
+        apply_bot_input(&mut players, bot_id, &input);
+    }
+}
+
+fn bot_input(colliders: &Colliders, bot_id: PlayerId, hit_angle: Option<f32>) -> BotInput {
+    let (paddle_angle, (goal_start_angle, goal_length_angle)) = match (
+        colliders.player_angle(bot_id),
+        colliders.player_goal_angles(bot_id),
+    ) {
+        (Some(paddle_angle), Some(goal_angles)) => (paddle_angle, goal_angles),
+        _ => return BotInput::default(),
+    };
+
+    let target_angle = match hit_angle {
+        Some(hit_angle)
+            if util::is_between_angles(hit_angle, goal_start_angle, goal_length_angle) =>
+        {
+            hit_angle
+        }
+        _ => goal_start_angle + goal_length_angle / 2.0,
+    };
+
+    // The shortest angle from the paddle to the target, between -PI & PI.
+    // A positive angle is counter-clockwise, which is the direction that the
+    // paddle moves in when moving "right".
+    let delta_angle = (target_angle - paddle_angle + PI).rem_euclid(TAU) - PI;
+    let movement_x = (delta_angle / BOT_SLOW_ANGLE).clamp(-1.0, 1.0);
+
+    BotInput {
+        movement: (movement_x, 0.0),
+        ..Default::default()
+    }
+}
+
+/// Returns the angle at which the ball at position `ball_pos`, traveling in
+/// the direction `ball_angle`, will cross the circle that the paddles are
+/// located on.
+fn predict_hit_angle(ball_pos: Vec2, ball_angle: f32) -> Option<f32> {
+    let dir = util::polar_to_cartesian(1.0, ball_angle);
+
+    // Solves `|ball_pos + t * dir| = PLAYER_RADIUS_INNER` for a positive `t`.
+    let b = ball_pos.dot(dir);
+    let c = ball_pos.length_squared() - PLAYER_RADIUS_INNER * PLAYER_RADIUS_INNER;
+    let discriminant = b * b - c;
+    if discriminant < 0.0 {
+        return None;
+    }
+
+    let t = -b + discriminant.sqrt();
+    if t < 0.0 {
+        return None;
+    }
+
+    let (_, hit_angle) = util::cartesian_to_polar(ball_pos + t * dir);
+    Some(hit_angle)
+}
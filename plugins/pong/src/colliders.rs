@@ -130,6 +130,27 @@ impl Colliders {
         }
     }
 
+    /// Returns the angle of the middle of the paddle of the player with ID
+    /// `player_id`. Returns None if the player doesn't have a paddle.
+    pub fn player_angle(&self, player_id: PlayerId) -> Option<f32> {
+        self.player_colliders
+            .get(&player_id)
+            .map(|pos| util::cartesian_to_polar(*pos).1)
+    }
+
+    /// Returns the start angle and the length (both in radians) of the goal of
+    /// the player with ID `player_id`. The start angle is given in the same
+    /// coordinate system as the positions (i.e. not "converted").
+    pub fn player_goal_angles(&self, player_id: PlayerId) -> Option<(f32, f32)> {
+        if self.player_order.contains(&player_id) {
+            let start_angle = util::convert_angle(self.player_goal_start_angle(player_id));
+            let goal_length_angle = util::angle_per_player(self.amount_of_players);
+            Some((start_angle, goal_length_angle))
+        } else {
+            None
+        }
+    }
+
     /// Returns `Some` if the ball is colliding with a player paddle. The returned
     /// tuple contains the ID of the player with the paddle that is colliding
     /// with the ball.
@@ -0,0 +1,70 @@
+use serde::Deserialize;
+
+use util_bevy::{ensure_positive, Config, ConfigError, MatchConfig, MatchRules, StartTimerConfig};
+
+use crate::{
+    BALL_SPEED_INCREMENT, BALL_START_SPEED, MATCH_POINTS, PLAYER_CONSTANT_SPEED, START_TIMER_TIME,
+};
+
+/// The tuning of the "Pong" game. Read from the `[pong]` section of the config
+/// file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PongConfig {
+    /// How long the timer between rounds are in seconds.
+    pub start_timer_time: usize,
+    /// The rules deciding when a match is over (the `[pong.match]` table).
+    #[serde(rename = "match")]
+    pub match_rules: MatchRules,
+    /// The speed that the ball starts with.
+    pub ball_start_speed: f32,
+    /// The amount of speed that is added to the ball everytime it collides
+    /// with a player paddle.
+    pub ball_speed_increment: f32,
+    /// The speed of the player paddles in radians per second.
+    pub paddle_speed: f32,
+}
+
+impl Default for PongConfig {
+    fn default() -> Self {
+        Self {
+            start_timer_time: START_TIMER_TIME,
+            match_rules: MatchRules::first_to(MATCH_POINTS),
+            ball_start_speed: BALL_START_SPEED,
+            ball_speed_increment: BALL_SPEED_INCREMENT,
+            paddle_speed: PLAYER_CONSTANT_SPEED,
+        }
+    }
+}
+
+impl Config for PongConfig {
+    const SECTION: &'static str = "pong";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure_positive(
+            Self::SECTION,
+            "start_timer_time",
+            self.start_timer_time as f32,
+        )?;
+        self.match_rules.validate(Self::SECTION)?;
+        ensure_positive(Self::SECTION, "ball_start_speed", self.ball_start_speed)?;
+        ensure_positive(
+            Self::SECTION,
+            "ball_speed_increment",
+            self.ball_speed_increment,
+        )?;
+        ensure_positive(Self::SECTION, "paddle_speed", self.paddle_speed)
+    }
+}
+
+impl StartTimerConfig for PongConfig {
+    fn start_timer_time(&self) -> usize {
+        self.start_timer_time
+    }
+}
+
+impl MatchConfig for PongConfig {
+    fn match_rules(&self) -> &MatchRules {
+        &self.match_rules
+    }
+}
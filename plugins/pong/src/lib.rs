@@ -1,5 +1,4 @@
 use std::{
-    cmp::Reverse,
     collections::HashSet,
     f32::consts::{FRAC_PI_4, FRAC_PI_8, PI, TAU},
 };
@@ -8,31 +7,35 @@ use bevy::{
     core::Time,
     math::{Quat, Vec2, Vec3},
     prelude::{
-        AppBuilder, Assets, Color, Commands, Entity, EventReader, EventWriter, HorizontalAlign,
-        IntoSystem, Local, Mesh, MeshBundle, ParallelSystemDescriptorCoercion, Plugin, Query,
-        RenderPipelines, Res, ResMut, State, SystemSet, Transform, VerticalAlign, With,
+        AppBuilder, Assets, Color, Commands, Entity, EventReader, EventWriter, IntoSystem, Mesh,
+        MeshBundle, ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines, Res, ResMut,
+        SystemSet, Transform, With,
     },
     render::{mesh::Indices, pipeline::PrimitiveTopology},
-    text::{Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
 use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
 
 use rand::{prelude::SliceRandom, Rng};
 
 use colliders::Colliders;
-use skitspel::{ActionEvent, DisconnectedPlayers, GameState, PlayerId, Players, GAME_HEIGHT};
+use skitspel::{
+    DisconnectedPlayers, GameState, PlayerId, Players, ServerMessage, ServerMessageEvent,
+    GAME_HEIGHT,
+};
 use util_bevy::{
-    create_vote_text_sections, despawn_entity, despawn_system, handle_start_timer,
-    setup_start_timer, AsBevyColor, Fonts, PlayerVote, Shape, StartTimer, VoteEvent,
+    despawn_entity, handle_start_timer, setup_start_timer, AsBevyColor, MiniGame, MiniGamePlugin,
+    PendingConfig, Scoring, Shape, StartTimer,
 };
 use util_rapier::{create_circle_points, indices_from_vertices, vertices_with_thickness};
 
+pub use config::PongConfig;
+
+mod bot;
 mod colliders;
+mod config;
 mod util;
 
-const GAME_STATE: GameState = GameState::PongGame;
-
-const EXIT_TEXT: &str = "Press B to go back to main menu";
+const GAME_STATE: GameState = PongGamePlugin::GAME_STATE;
 
 /// The radius, from the middle of the screen, of the circle that represents
 /// the goals. The goals will be drawn `+- GOAL_THICKNESS / 2.0` around this
@@ -59,28 +62,28 @@ const PLAYER_LENGTH_FRAC: f32 = 1.0 / 6.0;
 /// The radius of the ball.
 const BALL_RADIUS: f32 = 10.0;
 
-/// The speed that the ball starts with. The speed of the ball will be increased
-/// `BALL_SPEED_INCREMENT` amount for every bounce that is done.
+/// The default speed that the ball starts with. The speed of the ball will be
+/// increased `BALL_SPEED_INCREMENT` amount for every bounce that is done.
 const BALL_START_SPEED: f32 = 150.0;
 
-/// The amount of speed that is added to the ball everytime it collides with
-/// a player paddle.
+/// The default amount of speed that is added to the ball everytime it collides
+/// with a player paddle.
 const BALL_SPEED_INCREMENT: f32 = 25.0;
 
-/// The speed of the players paddle. This is written in the unit
+/// The default speed of the players paddle. This is written in the unit
 /// `amount of radians per second`.
 const PLAYER_CONSTANT_SPEED: f32 = PI / 4.0;
 
-/// How long the timer between rounds are in seconds.
+/// The default time of the timer between rounds in seconds.
 const START_TIMER_TIME: usize = 3;
 
+/// The default amount of points needed to win a match.
+const MATCH_POINTS: usize = 10;
+
 /// The amount of points that will be plotted for the circle. A higher number
 /// will make the circle more circular "smooth".
 const AMOUNT_OF_POINTS: usize = 64;
 
-/// Tag used on the exit text.
-struct ExitText;
-
 /// Tag used on the ball.
 #[derive(Default)]
 struct Ball {
@@ -94,8 +97,8 @@ struct Ball {
 impl Ball {
     /// Moves the ball to the middle of the screen, resets the speed to the start
     /// speed and randomizes a direction in which it will start traveling.
-    fn reset(&mut self, transform: &mut Transform) {
-        self.speed = BALL_START_SPEED;
+    fn reset(&mut self, transform: &mut Transform, config: &PongConfig) {
+        self.speed = config.ball_start_speed;
         self.angle = rand::thread_rng().gen_range(0.0..TAU);
         transform.translation.x = 0.0;
         transform.translation.y = 0.0;
@@ -105,9 +108,6 @@ impl Ball {
 /// Tag used on the walls (the circle representing the goals).
 struct Wall;
 
-/// Component used to tag the text containing the score.
-struct ScoreText;
-
 /// Event created when a player dies and its character is removed.
 #[derive(Debug)]
 struct DeathEvent(PlayerId);
@@ -117,46 +117,54 @@ pub struct PongGamePlugin;
 
 impl Plugin for PongGamePlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_event::<DeathEvent>()
+        app.add_plugin(MiniGamePlugin::<PongGamePlugin>::default())
+            .add_event::<DeathEvent>()
+            .init_resource::<PongConfig>()
+            .init_resource::<PendingConfig<PongConfig>>()
             .add_system_set(
                 SystemSet::on_enter(GAME_STATE)
-                    .with_system(reset_votes.system())
                     .with_system(setup_colliders.system())
-                    .with_system(setup_start_timer::<PongGamePlugin, START_TIMER_TIME>.system())
-                    .with_system(setup_screen_text.system())
+                    .with_system(setup_start_timer::<PongGamePlugin, PongConfig>.system())
                     .with_system(setup_ball.system()),
             )
             .add_system_set(
                 SystemSet::on_update(GAME_STATE)
                     .with_system(handle_disconnect.system().label("vote"))
-                    .with_system(handle_player_input.system().label("vote").label("jump"))
-                    .with_system(handle_exit_event.system().after("vote"))
-                    .with_system(update_scoreboard.system())
-                    .with_system(move_players.system().label("players").after("vote"))
+                    .with_system(bot::handle_bots.system().label("bots").after("vote"))
+                    .with_system(move_players.system().label("players").after("bots"))
                     .with_system(move_ball.system().label("ball").after("players"))
                     .with_system(handle_collision.system().label("collision").after("ball"))
                     .with_system(handle_reset.system().label("reset").after("collision"))
                     .with_system(handle_start_timer.system().label("start").after("reset")),
-            )
-            .add_system_set(
-                SystemSet::on_exit(GAME_STATE)
-                    .with_system(despawn_system::<PongGamePlugin>.system()),
             );
     }
 }
 
+impl MiniGame for PongGamePlugin {
+    type Config = PongConfig;
+
+    const ID: &'static str = "pong";
+    const NAME: &'static str = "Pong";
+    const DESCRIPTION: &'static str =
+        "Defend your goal with your paddle. The last player with an intact goal wins.";
+    const SCREENSHOT: &'static [u8] = include_bytes!("..\\assets\\pong.png");
+    const SCORING: Scoring = Scoring::Individual;
+
+    // Move the texts down so that they aren't drawn on top of the goals.
+    const TEXT_Y: f32 = GAME_HEIGHT / 4.0 - 2.0 * 24.0;
+    const SCOREBOARD_MARGIN: f32 = 15.0 + 3.5 * 24.0;
+}
+
+/// Removes the goals & paddles of disconnected players. The player entities
+/// themselves are despawned by the `MiniGamePlugin`.
 fn handle_disconnect(
-    mut commands: Commands,
     disconnected_players: Res<DisconnectedPlayers>,
-    players_playing: Query<(Entity, &PlayerId)>,
+    players_playing: Query<&PlayerId>,
     mut colliders_query: Query<&mut Colliders>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
 ) {
     if !disconnected_players.is_empty() {
-        for (entity, player_id) in players_playing.iter() {
+        for player_id in players_playing.iter() {
             if disconnected_players.contains(player_id) {
-                exit_event_writer.send(VoteEvent::Value(*player_id, false));
-
                 // TODO: Can there be problems with removing the graphic & collider
                 //       for the player? Is there a possibility that the entity
                 //       is used in the same tick that can cause a panic?
@@ -164,135 +172,14 @@ fn handle_disconnect(
                     .single_mut()
                     .unwrap()
                     .remove_player(*player_id);
-                despawn_entity(&mut commands, entity);
-            }
-        }
-    }
-}
-
-fn update_scoreboard(
-    mut commands: Commands,
-    fonts: Res<Fonts>,
-    players: Res<Players>,
-    score_text_query: Query<Entity, With<ScoreText>>,
-) {
-    if players.is_changed() {
-        for entity in score_text_query.iter() {
-            despawn_entity(&mut commands, entity);
-        }
-
-        let font = fonts.regular.clone();
-
-        let mut sorted_players = players.values().collect::<Vec<_>>();
-        sorted_players.sort_unstable_by_key(|p| Reverse(p.score()));
-
-        let mut text_sections = Vec::default();
-        text_sections.push(TextSection {
-            value: "SCORE".into(),
-            style: TextStyle {
-                font: font.clone(),
-                font_size: 32.0,
-                color: Color::WHITE,
-            },
-        });
-
-        for (idx, player) in sorted_players.iter().enumerate() {
-            let color = player.color().as_bevy();
-            text_sections.push(TextSection {
-                value: format!("\n{}. {}", idx + 1, player.score()),
-                style: TextStyle {
-                    font: font.clone(),
-                    font_size: 24.0,
-                    color,
-                },
-            });
-        }
-
-        let top_margin = 15.0 + 3.5 * 24.0;
-        let text_bundle = Text2dBundle {
-            text: Text {
-                sections: text_sections,
-                alignment: TextAlignment {
-                    vertical: VerticalAlign::Bottom,
-                    horizontal: HorizontalAlign::Center,
-                },
-            },
-            transform: Transform::from_xyz(0.0, (GAME_HEIGHT / 2.0) - top_margin, 0.0),
-            ..Default::default()
-        };
-
-        commands
-            .spawn_bundle(text_bundle)
-            .insert(PongGamePlugin)
-            .insert(ScoreText);
-    }
-}
-
-fn handle_player_input(
-    mut players: ResMut<Players>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
-) {
-    if players.is_changed() {
-        for player in players.values_mut() {
-            if let Some(prev_action) = player.previous_action_once() {
-                match prev_action {
-                    ActionEvent::APressed => {
-                        // TODO: Add action when pressing A?
-                    }
-
-                    ActionEvent::BPressed => {
-                        exit_event_writer.send(VoteEvent::Flip(player.id()));
-                    }
-
-                    _ => (),
-                }
             }
         }
     }
 }
 
-/// If a majority of the players wants to exit, we should return back to the menu.
-fn handle_exit_event(
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    mut game_state: ResMut<State<GameState>>,
-    mut exit_text: Query<&mut Text, With<ExitText>>,
-    mut player_exit_vote: Local<PlayerVote>,
-    mut exit_event_reader: EventReader<VoteEvent>,
-) {
-    let voted_amount_before = player_exit_vote.voted_amount();
-    let total_amount_before = player_exit_vote.total_amount();
-
-    exit_event_reader
-        .iter()
-        .for_each(|vote| player_exit_vote.register_vote(vote));
-
-    let voted_amount_after = player_exit_vote.len();
-    let total_amount_after = players.len();
-
-    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
-        player_exit_vote.set_total_amount(total_amount_after);
-
-        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount {
-            game_state.set(GameState::StartMenu).unwrap();
-        } else {
-            let font = fonts.regular.clone();
-            let font_size = 24.0;
-            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
-                EXIT_TEXT.into(),
-                &players,
-                &player_exit_vote,
-                required_amount,
-                font,
-                font_size,
-            );
-        }
-    }
-}
-
 fn move_players(
     time: Res<Time>,
+    config: Res<PongConfig>,
     players: Res<Players>,
     mut player_query: Query<(&PlayerId, &mut Transform)>,
     mut colliders_query: Query<&mut Colliders>,
@@ -304,8 +191,7 @@ fn move_players(
         if let Some(player) = players.get(player_id) {
             let movement_x = player.movement_x();
             if movement_x != 0.0 {
-                let sign = if movement_x > 0.0 { 1.0 } else { -1.0 };
-                let delta_angle = sign * PLAYER_CONSTANT_SPEED * delta_tick;
+                let delta_angle = movement_x * config.paddle_speed * delta_tick;
 
                 // Unable to move outside its own goal.
                 if colliders.can_move_player(*player_id, delta_angle) {
@@ -333,9 +219,11 @@ fn move_ball(
 }
 
 fn handle_collision(
+    config: Res<PongConfig>,
     mut ball_query: Query<(&mut Ball, &Transform)>,
     mut colliders_query: Query<&mut Colliders>,
     mut death_event_writer: EventWriter<DeathEvent>,
+    mut server_message_writer: EventWriter<ServerMessageEvent>,
 ) {
     let (mut ball, ball_transform) = ball_query.single_mut().unwrap();
     let mut colliders = colliders_query.single_mut().unwrap();
@@ -356,16 +244,19 @@ fn handle_collision(
         };
 
         ball.angle = (reverse_angle + extra_angle) % TAU;
-        ball.speed += BALL_SPEED_INCREMENT;
+        ball.speed += config.ball_speed_increment;
     } else if let Some(player_id) = colliders.goal_collision(ball_pos, BALL_RADIUS) {
         // The ball have hit the "goal" of the player with ID `player_id`.
         death_event_writer.send(DeathEvent(player_id));
+        server_message_writer.send(ServerMessageEvent::Player(player_id, ServerMessage::Died));
     }
 }
 
 #[allow(clippy::too_many_arguments)]
 fn handle_reset(
     mut commands: Commands,
+    mut config: ResMut<PongConfig>,
+    mut pending_config: ResMut<PendingConfig<PongConfig>>,
     mut meshes: ResMut<Assets<Mesh>>,
     render_pipelines: Res<RenderPipelines>,
     mut ball_query: Query<(&mut Ball, &mut Transform)>,
@@ -383,6 +274,8 @@ fn handle_reset(
         return;
     }
 
+    pending_config.apply(&mut config);
+
     for (entity, _) in player_query.iter() {
         despawn_entity(&mut commands, entity);
     }
@@ -391,14 +284,21 @@ fn handle_reset(
     }
 
     let (mut ball, mut transform) = ball_query.single_mut().unwrap();
-    ball.reset(&mut transform);
+    ball.reset(&mut transform, &config);
 
     let mut colliders = colliders_query.single_mut().unwrap();
     colliders.reset();
 
-    start_timer_query.single_mut().unwrap().reset();
+    start_timer_query
+        .single_mut()
+        .unwrap()
+        .restart(config.start_timer_time);
 
     let mut player_ids = if let Some(DeathEvent(player_id)) = death_event {
+        if let Some(player) = players.get_mut(player_id) {
+            player.record_stats(PongGamePlugin::ID, |stats| stats.deaths += 1);
+        }
+
         let mut players_alive = player_query
             .iter()
             .map(|(_, id)| *id)
@@ -410,7 +310,8 @@ fn handle_reset(
             // the game should be reset so that every player is alive again.
             let winner_id = players_alive.iter().next().unwrap();
             if let Some(player) = players.get_mut(winner_id) {
-                player.increment_score();
+                player.increment_score(PongGamePlugin::ID);
+                player.record_stats(PongGamePlugin::ID, |stats| stats.round_wins += 1);
             }
 
             players.keys().cloned().collect::<Vec<_>>()
@@ -436,44 +337,6 @@ fn handle_reset(
     );
 }
 
-fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
-    exit_event_writer.send(VoteEvent::Reset);
-}
-
-fn setup_screen_text(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>) {
-    let font = fonts.regular.clone();
-    let font_size = 24.0;
-
-    let empty_player_vote = PlayerVote::default();
-    let required_amount = (players.len() / 2) + 1;
-
-    let exit_text = Text {
-        sections: create_vote_text_sections(
-            EXIT_TEXT.into(),
-            &players,
-            &empty_player_vote,
-            required_amount,
-            font,
-            font_size,
-        ),
-        alignment: TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    };
-
-    let exit_text_bundle = Text2dBundle {
-        text: exit_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0 - font_size * 3.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(exit_text_bundle)
-        .insert(ExitText)
-        .insert(PongGamePlugin);
-}
-
 fn setup_colliders(mut commands: Commands) {
     commands
         .spawn()
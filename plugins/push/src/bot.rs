@@ -0,0 +1,122 @@
+use bevy::{
+    math::Vec2,
+    prelude::{Query, ResMut},
+};
+use bevy_rapier2d::prelude::{RigidBodyPosition, RigidBodyVelocity};
+
+use skitspel::{
+    BotInput, PlayerId, Players, GAME_HEIGHT, GAME_WIDTH, PLAYER_RADIUS, RAPIER_SCALE_FACTOR,
+};
+use util_bevy::apply_bot_input;
+
+use crate::PILLAR_RADIUS;
+
+/// How far ahead (in seconds) the bots predict their own position when looking
+/// for danger.
+const LOOKAHEAD_TIME: f32 = 0.5;
+
+/// A bot tries to get away if its predicted position is closer than this (in
+/// pixels) to a border or to the pillar in the middle.
+const DANGER_DISTANCE: f32 = PLAYER_RADIUS + 40.0;
+
+/// The bots start to slow down when they are closer than this (in pixels) to
+/// the player that they are chasing.
+const SLOW_DISTANCE: f32 = 100.0;
+
+/// A bot spins if there is another player closer than this (in pixels).
+const SPIN_DISTANCE: f32 = PLAYER_RADIUS * 2.0 + 30.0;
+
+/// Moves the bots towards the closest other player to push it into a red wall.
+/// If a bot is about to hit a red wall itself, it moves away from the wall
+/// instead.
+pub(crate) fn handle_bots(
+    mut players: ResMut<Players>,
+    player_query: Query<(&PlayerId, &RigidBodyPosition, &RigidBodyVelocity)>,
+) {
+    let player_positions = player_query
+        .iter()
+        .map(|(player_id, pos, _)| {
+            let pos = Vec2::new(
+                pos.position.translation.x * RAPIER_SCALE_FACTOR,
+                pos.position.translation.y * RAPIER_SCALE_FACTOR,
+            );
+            (*player_id, pos)
+        })
+        .collect::<Vec<_>>();
+
+    for (player_id, _, velocity) in player_query.iter() {
+        if !players.get(player_id).map_or(false, |p| p.is_bot()) {
+            continue;
+        }
+
+        let bot_pos =
+            if let Some((_, pos)) = player_positions.iter().find(|(id, _)| id == player_id) {
+                *pos
+            } else {
+                continue;
+            };
+        let bot_velocity = Vec2::from(velocity.linvel) * RAPIER_SCALE_FACTOR;
+
+        let input = bot_input(*player_id, bot_pos, bot_velocity, &player_positions);
+        apply_bot_input(&mut players, *player_id, &input);
+    }
+}
+
+fn bot_input(
+    bot_id: PlayerId,
+    bot_pos: Vec2,
+    bot_velocity: Vec2,
+    player_positions: &[(PlayerId, Vec2)],
+) -> BotInput {
+    let predicted_pos = bot_pos + bot_velocity * LOOKAHEAD_TIME;
+    let escape_dir = escape_direction(predicted_pos);
+    if escape_dir != Vec2::ZERO {
+        return BotInput {
+            movement: escape_dir.normalize().into(),
+            ..Default::default()
+        };
+    }
+
+    let closest_player_pos = player_positions
+        .iter()
+        .filter(|(id, _)| *id != bot_id)
+        .map(|(_, pos)| *pos)
+        .min_by(|a, b| {
+            a.distance(bot_pos)
+                .partial_cmp(&b.distance(bot_pos))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+    if let Some(target) = closest_player_pos {
+        let mut input = BotInput::towards(bot_pos.into(), target.into(), SLOW_DISTANCE);
+        input.a = target.distance(bot_pos) < SPIN_DISTANCE;
+        input
+    } else {
+        BotInput::default()
+    }
+}
+
+/// Returns the direction that a player at position `pos` should move in to
+/// get away from the red walls and the red pillar. Returns a zero vector if
+/// the position is safe.
+fn escape_direction(pos: Vec2) -> Vec2 {
+    let mut dir = Vec2::ZERO;
+
+    if pos.x < -GAME_WIDTH / 2.0 + DANGER_DISTANCE {
+        dir.x += 1.0;
+    } else if pos.x > GAME_WIDTH / 2.0 - DANGER_DISTANCE {
+        dir.x -= 1.0;
+    }
+    if pos.y < -GAME_HEIGHT / 2.0 + DANGER_DISTANCE {
+        dir.y += 1.0;
+    } else if pos.y > GAME_HEIGHT / 2.0 - DANGER_DISTANCE {
+        dir.y -= 1.0;
+    }
+
+    let pillar_distance = pos.length();
+    if pillar_distance < PILLAR_RADIUS + DANGER_DISTANCE && pillar_distance > f32::EPSILON {
+        dir += pos / pillar_distance;
+    }
+
+    dir
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+
+use skitspel::TORQUE_ACCEL_AMOUNT;
+use util_bevy::{ensure_positive, Config, ConfigError, MatchConfig, MatchRules, StartTimerConfig};
+
+use crate::{MATCH_POINTS, START_TIMER_TIME};
+
+/// The tuning of the "Push" game. Read from the `[push]` section of the config
+/// file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PushConfig {
+    /// How long the timer between rounds are in seconds.
+    pub start_timer_time: usize,
+    /// The rules deciding when a match is over (the `[push.match]` table).
+    #[serde(rename = "match")]
+    pub match_rules: MatchRules,
+    /// The amount of torque acceleration that is applied to a player every
+    /// tick during a spin.
+    pub spin_torque: f32,
+}
+
+impl Default for PushConfig {
+    fn default() -> Self {
+        Self {
+            start_timer_time: START_TIMER_TIME,
+            match_rules: MatchRules::first_to(MATCH_POINTS),
+            spin_torque: TORQUE_ACCEL_AMOUNT,
+        }
+    }
+}
+
+impl Config for PushConfig {
+    const SECTION: &'static str = "push";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure_positive(
+            Self::SECTION,
+            "start_timer_time",
+            self.start_timer_time as f32,
+        )?;
+        self.match_rules.validate(Self::SECTION)?;
+        ensure_positive(Self::SECTION, "spin_torque", self.spin_torque)
+    }
+}
+
+impl StartTimerConfig for PushConfig {
+    fn start_timer_time(&self) -> usize {
+        self.start_timer_time
+    }
+}
+
+impl MatchConfig for PushConfig {
+    fn match_rules(&self) -> &MatchRules {
+        &self.match_rules
+    }
+}
@@ -1,14 +1,11 @@
-use std::cmp::Reverse;
-
 use bevy::{
     core::Time,
     math::Vec2,
     prelude::{
-        AppBuilder, Assets, Color, Commands, Entity, EventReader, EventWriter, HorizontalAlign,
-        IntoSystem, Local, Mesh, ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines,
-        Res, ResMut, State, SystemSet, Transform, VerticalAlign, With,
+        AppBuilder, Assets, Color, Commands, Entity, EventReader, EventWriter, IntoSystem, Mesh,
+        ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines, Res, ResMut, SystemSet,
+        Transform, With,
     },
-    text::{Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
 use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
 use bevy_rapier2d::{
@@ -22,16 +19,21 @@ use bevy_rapier2d::{
 use rand::prelude::SliceRandom;
 
 use skitspel::{
-    ActionEvent, DisconnectedPlayers, GameState, PlayerId, Players, GAME_HEIGHT, GAME_WIDTH,
-    MAX_PLAYERS, PLAYER_RADIUS, RAPIER_SCALE_FACTOR, TORQUE_ACCEL_AMOUNT,
+    GameState, PlayerId, Players, ServerMessage, ServerMessageEvent, GAME_HEIGHT, GAME_WIDTH,
+    MAX_PLAYERS, PLAYER_RADIUS, RAPIER_SCALE_FACTOR,
 };
 use util_bevy::{
-    create_vote_text_sections, despawn_entity, despawn_system, handle_start_timer,
-    setup_start_timer, AsBevyColor, Fonts, PlayerVote, Shape, StartTimer, VoteEvent,
+    despawn_entity, handle_start_timer, setup_start_timer, AsBevyColor, MiniGame, MiniGamePlugin,
+    PendingConfig, Scoring, Shape, StartTimer,
 };
-use util_rapier::{move_players, spawn_border_walls, spawn_player};
+use util_rapier::{move_players, spawn_border_walls, spawn_player, MovementConfig};
+
+pub use config::PushConfig;
+
+mod bot;
+mod config;
 
-const GAME_STATE: GameState = GameState::PushGame;
+const GAME_STATE: GameState = PushGamePlugin::GAME_STATE;
 
 const SPAWN_POSITIONS: [(f32, f32); MAX_PLAYERS] = [
     (-GAME_WIDTH * 0.375, GAME_HEIGHT * 0.375),
@@ -49,17 +51,14 @@ const SPAWN_POSITIONS: [(f32, f32); MAX_PLAYERS] = [
 #[derive(Debug, Clone)]
 struct DeathCollider;
 
-/// Component used to tag the text containing the score.
-struct ScoreText;
+/// The radius of the pillars.
+const PILLAR_RADIUS: f32 = 120.0;
 
-const SPIN_TEXT: &str = "Press A to spin\n";
-const EXIT_TEXT: &str = "Press B to go back to main menu";
-
-/// How long the timer between rounds are in seconds.
+/// The default time of the timer between rounds in seconds.
 const START_TIMER_TIME: usize = 3;
 
-/// Tag used on the exit text.
-struct ExitText;
+/// The default amount of points needed to win a match.
+const MATCH_POINTS: usize = 10;
 
 /// Tag used on the pillar in the middle. It seems to be some problems with the
 /// z-ordering in bevy, so the countdown StartText isn't being displayed on top
@@ -74,65 +73,56 @@ pub struct PushGamePlugin;
 
 impl Plugin for PushGamePlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_system_set(
-            SystemSet::on_enter(GAME_STATE)
-                .with_system(reset_votes.system())
-                .with_system(setup_map.system())
-                .with_system(setup_start_timer::<PushGamePlugin, START_TIMER_TIME>.system())
-                .with_system(setup_screen_text.system()),
-        )
-        .add_system_set(
-            SystemSet::on_update(GAME_STATE)
-                .with_system(handle_disconnect.system().label("vote"))
-                .with_system(handle_player_input.system().label("vote"))
-                .with_system(handle_exit_event.system().after("vote"))
-                .with_system(handle_winner.system())
-                .with_system(update_scoreboard.system())
-                .with_system(reset_players.system().label("reset"))
-                .with_system(handle_death_pillar.system().label("pillar").after("reset"))
-                .with_system(handle_start_timer.system().after("pillar"))
-                .with_system(move_players.system())
-                .with_system(spin_players.system())
-                .with_system(handle_death.system()),
-        )
-        .add_system_set(
-            SystemSet::on_exit(GAME_STATE).with_system(despawn_system::<PushGamePlugin>.system()),
-        );
+        app.add_plugin(MiniGamePlugin::<PushGamePlugin>::default())
+            .init_resource::<PushConfig>()
+            .init_resource::<PendingConfig<PushConfig>>()
+            .init_resource::<MovementConfig>()
+            .init_resource::<PendingConfig<MovementConfig>>()
+            .add_system_set(
+                SystemSet::on_enter(GAME_STATE)
+                    .with_system(setup_map.system())
+                    .with_system(setup_start_timer::<PushGamePlugin, PushConfig>.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GAME_STATE)
+                    .with_system(reset_players.system().label("reset").after("winner"))
+                    .with_system(handle_death_pillar.system().label("pillar").after("reset"))
+                    .with_system(handle_start_timer.system().after("pillar"))
+                    .with_system(bot::handle_bots.system().label("bots").after("vote"))
+                    .with_system(move_players.system().after("bots"))
+                    .with_system(spin_players.system().after("bots"))
+                    .with_system(handle_death.system()),
+            );
     }
 }
 
-fn handle_disconnect(
-    mut commands: Commands,
-    disconnected_players: Res<DisconnectedPlayers>,
-    players_alive_query: Query<(Entity, &PlayerId)>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
-) {
-    if !disconnected_players.is_empty() {
-        for (entity, player_id) in players_alive_query.iter() {
-            if disconnected_players.contains(player_id) {
-                exit_event_writer.send(VoteEvent::Value(*player_id, false));
-                despawn_entity(&mut commands, entity);
-            }
-        }
-    }
-}
+impl MiniGame for PushGamePlugin {
+    type Config = PushConfig;
 
-fn handle_winner(mut players: ResMut<Players>, players_alive_query: Query<&PlayerId>) {
-    if players_alive_query.iter().len() == 1 {
-        let player_id = players_alive_query.iter().next().unwrap();
-        if let Some(winning_player) = players.get_mut(player_id) {
-            winning_player.increment_score();
-        }
-    }
+    const ID: &'static str = "push";
+    const NAME: &'static str = "Push";
+    const DESCRIPTION: &'static str =
+        "Avoid the red walls & the red circle in the middle. The last player alive wins the round.";
+    const SCREENSHOT: &'static [u8] = include_bytes!("..\\assets\\push.png");
+    const SCORING: Scoring = Scoring::LastSurvivor;
+    const ACTION_TEXT: Option<&'static str> = Some("Press A to spin");
 }
 
+#[allow(clippy::too_many_arguments)]
 fn reset_players(
     mut commands: Commands,
+    mut config: ResMut<PushConfig>,
+    mut pending_config: ResMut<PendingConfig<PushConfig>>,
+    mut movement_config: ResMut<MovementConfig>,
+    mut pending_movement_config: ResMut<PendingConfig<MovementConfig>>,
     mut players: ResMut<Players>,
     players_alive_query: Query<Entity, With<PlayerId>>,
     mut start_timer_query: Query<&mut StartTimer>,
 ) {
     if players_alive_query.iter().count() <= 1 {
+        pending_config.apply(&mut config);
+        pending_movement_config.apply(&mut movement_config);
+
         // Remove any players that are still alive.
         for entity in players_alive_query.iter() {
             despawn_entity(&mut commands, entity);
@@ -158,70 +148,16 @@ fn reset_players(
             .insert(PushGamePlugin);
         }
 
-        start_timer_query.single_mut().unwrap().reset();
-    }
-}
-
-fn update_scoreboard(
-    mut commands: Commands,
-    fonts: Res<Fonts>,
-    players: Res<Players>,
-    score_text_query: Query<Entity, With<ScoreText>>,
-) {
-    if players.is_changed() {
-        for entity in score_text_query.iter() {
-            despawn_entity(&mut commands, entity);
-        }
-
-        let font = fonts.regular.clone();
-
-        let mut sorted_players = players.values().collect::<Vec<_>>();
-        sorted_players.sort_unstable_by_key(|p| Reverse(p.score()));
-
-        let mut text_sections = Vec::default();
-        text_sections.push(TextSection {
-            value: "SCORE".into(),
-            style: TextStyle {
-                font: font.clone(),
-                font_size: 32.0,
-                color: Color::WHITE,
-            },
-        });
-
-        for (idx, player) in sorted_players.iter().enumerate() {
-            let color = player.color().as_bevy();
-            text_sections.push(TextSection {
-                value: format!("\n{}. {}", idx + 1, player.score()),
-                style: TextStyle {
-                    font: font.clone(),
-                    font_size: 24.0,
-                    color,
-                },
-            });
-        }
-
-        let top_margin = 15.0;
-        let text_bundle = Text2dBundle {
-            text: Text {
-                sections: text_sections,
-                alignment: TextAlignment {
-                    vertical: VerticalAlign::Bottom,
-                    horizontal: HorizontalAlign::Center,
-                },
-            },
-            transform: Transform::from_xyz(0.0, (GAME_HEIGHT / 2.0) - top_margin, 0.0),
-            ..Default::default()
-        };
-
-        commands
-            .spawn_bundle(text_bundle)
-            .insert(PushGamePlugin)
-            .insert(ScoreText);
+        start_timer_query
+            .single_mut()
+            .unwrap()
+            .restart(config.start_timer_time);
     }
 }
 
 fn spin_players(
     time: Res<Time>,
+    config: Res<PushConfig>,
     players: Res<Players>,
     mut player_query: Query<(&PlayerId, &mut RigidBodyVelocity, &RigidBodyMassProps)>,
 ) {
@@ -229,7 +165,7 @@ fn spin_players(
     for (player_id, mut velocity, mass) in player_query.iter_mut() {
         if let Some(player) = players.get(player_id) {
             if player.a_is_pressed() {
-                velocity.apply_torque_impulse(mass, TORQUE_ACCEL_AMOUNT * delta_tick);
+                velocity.apply_torque_impulse(mass, config.spin_torque * delta_tick);
             }
         }
     }
@@ -240,9 +176,10 @@ fn spin_players(
 fn handle_death(
     mut commands: Commands,
     mut intersection_event: EventReader<IntersectionEvent>,
-    players_query: Query<Entity, With<PlayerId>>,
+    players_query: Query<&PlayerId>,
     death_walls_query: Query<Entity, With<DeathCollider>>,
     start_timer_query: Query<&StartTimer>,
+    mut server_message_writer: EventWriter<ServerMessageEvent>,
 ) {
     if !start_timer_query.single().unwrap().finished() {
         return;
@@ -252,12 +189,20 @@ fn handle_death(
         if intersection.intersecting {
             let entity_a = intersection.collider1.entity();
             let entity_b = intersection.collider2.entity();
-            if players_query.get(entity_a).is_ok() && death_walls_query.get(entity_b).is_ok() {
-                despawn_entity(&mut commands, entity_a);
-            } else if players_query.get(entity_b).is_ok() && death_walls_query.get(entity_a).is_ok()
+            let (player_entity, player_id) = if let (Ok(player_id), Ok(_)) =
+                (players_query.get(entity_a), death_walls_query.get(entity_b))
             {
-                despawn_entity(&mut commands, entity_b);
-            }
+                (entity_a, player_id)
+            } else if let (Ok(player_id), Ok(_)) =
+                (players_query.get(entity_b), death_walls_query.get(entity_a))
+            {
+                (entity_b, player_id)
+            } else {
+                continue;
+            };
+
+            server_message_writer.send(ServerMessageEvent::Player(*player_id, ServerMessage::Died));
+            despawn_entity(&mut commands, player_entity);
         }
     }
 }
@@ -275,9 +220,8 @@ fn handle_death_pillar(
     let start_timer = start_timer_query.single().unwrap();
     if start_timer.just_finished() {
         let pos = Vec2::ZERO;
-        let radius = 120.0;
         let red_color = Color::rgb(1.0, 0.1, 0.1);
-        spawn_pillar_death(&mut commands, pos, radius, red_color);
+        spawn_pillar_death(&mut commands, pos, PILLAR_RADIUS, red_color);
     } else if start_timer.elapsed_secs() == 0.0 {
         // True if just reset, remove pillar.
         for (entity, _) in death_walls_query.iter() {
@@ -286,123 +230,6 @@ fn handle_death_pillar(
     }
 }
 
-/// Updates the components inside the `Players` according to which buttons the
-/// players have pushed.
-fn handle_player_input(
-    mut players: ResMut<Players>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
-) {
-    if players.is_changed() {
-        for player in players.values_mut() {
-            if let Some(ActionEvent::BPressed) = player.previous_action_once() {
-                exit_event_writer.send(VoteEvent::Flip(player.id()));
-            }
-        }
-    }
-}
-
-/// If a majority of the players wants to exit, we should return back to the menu.
-fn handle_exit_event(
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    mut game_state: ResMut<State<GameState>>,
-    mut exit_text: Query<&mut Text, With<ExitText>>,
-    mut player_exit_vote: Local<PlayerVote>,
-    mut exit_event_reader: EventReader<VoteEvent>,
-) {
-    let voted_amount_before = player_exit_vote.voted_amount();
-    let total_amount_before = player_exit_vote.total_amount();
-
-    exit_event_reader
-        .iter()
-        .for_each(|vote| player_exit_vote.register_vote(vote));
-
-    let voted_amount_after = player_exit_vote.len();
-    let total_amount_after = players.len();
-
-    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
-        player_exit_vote.set_total_amount(total_amount_after);
-
-        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount {
-            game_state.set(GameState::StartMenu).unwrap();
-        } else {
-            let font = fonts.regular.clone();
-            let font_size = 24.0;
-            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
-                EXIT_TEXT.into(),
-                &players,
-                &player_exit_vote,
-                required_amount,
-                font,
-                font_size,
-            );
-        }
-    }
-}
-
-fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
-    exit_event_writer.send(VoteEvent::Reset);
-}
-
-fn setup_screen_text(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>) {
-    let font = fonts.regular.clone();
-    let font_size = 24.0;
-    let font_color = Color::WHITE;
-
-    let empty_player_vote = PlayerVote::default();
-    let required_amount = (players.len() / 2) + 1;
-
-    let spin_text = Text::with_section(
-        SPIN_TEXT,
-        TextStyle {
-            font: font.clone(),
-            font_size,
-            color: font_color,
-        },
-        TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    );
-
-    let spin_text_bundle = Text2dBundle {
-        text: spin_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(spin_text_bundle)
-        .insert(PushGamePlugin);
-
-    let exit_text = Text {
-        sections: create_vote_text_sections(
-            EXIT_TEXT.into(),
-            &players,
-            &empty_player_vote,
-            required_amount,
-            font,
-            font_size,
-        ),
-        alignment: TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    };
-
-    let exit_text_bundle = Text2dBundle {
-        text: exit_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0 - font_size, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(exit_text_bundle)
-        .insert(PushGamePlugin)
-        .insert(ExitText);
-}
-
 fn setup_map(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
@@ -430,7 +257,7 @@ fn setup_map(
         Vec2::new(GAME_WIDTH * 0.25, -GAME_HEIGHT * 0.20),
     ];
     for pos in positions {
-        spawn_pillar_wall(&mut commands, pos, 120.0, grey_color);
+        spawn_pillar_wall(&mut commands, pos, PILLAR_RADIUS, grey_color);
     }
 }
 
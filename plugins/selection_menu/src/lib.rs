@@ -6,15 +6,18 @@ use bevy::{
         Assets, BuildChildren, Changed, Children, Color, Commands, EventReader, EventWriter,
         Handle, HorizontalAlign, IntoSystem, Local, ParallelSystemDescriptorCoercion, Plugin,
         Query, Res, ResMut, SpriteBundle, State, SystemSet, Transform, VerticalAlign, With,
+        Without,
     },
-    sprite::ColorMaterial,
+    sprite::{ColorMaterial, Sprite},
     text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
 use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
 
-use skitspel::{ActionEvent, DisconnectedPlayers, GameState, Players, GAME_HEIGHT};
+use skitspel::{ActionEvent, DisconnectedPlayers, GameState, Players, GAME_HEIGHT, GAME_WIDTH};
+use tournament::{TournamentConfig, TournamentGame};
 use util_bevy::{
-    create_vote_text_sections, despawn_system, Fonts, Game, Games, PlayerVote, Shape, VoteEvent,
+    create_vote_text_sections, despawn_system, Fonts, Games, GamesConfig, PlayerVote, Shape,
+    VoteEvent,
 };
 
 const GAME_STATE: GameState = GameState::GameSelectionMenu;
@@ -71,16 +74,34 @@ impl DerefMut for StartVoteEvent {
 #[derive(Clone)]
 pub struct GameSelectionPlugin;
 
-/// Tag used for the currently selected game and its index in the `Games` vector.
+/// Tag used for the currently selected game and its index in `SelectableGames`.
 struct SelectedGame(usize);
 
+/// Component containing the games that can be selected. A game is selectable if
+/// it isn't disabled in the `GamesConfig` and if it supports the amount of
+/// players that are connected when the menu is entered. The tournament is
+/// selectable if its playlist contains any games.
+struct SelectableGames(Vec<Selectable>);
+
+enum Selectable {
+    /// The index of the game inside `Games`.
+    Game(usize),
+    /// A tournament playing the games in the playlist.
+    Tournament(Vec<TournamentGame>),
+}
+
 /// Tag used on the games displayed on screen. The index if the index of the game
-/// inside `Games` (used to keep track of order).
+/// inside `SelectableGames` (used to keep track of order).
 struct SelectableGame(usize);
 
+/// Tag used on the text containing the description of the selected game.
+struct DescriptionText;
+
 impl Plugin for GameSelectionPlugin {
     fn build(&self, app: &mut bevy::prelude::AppBuilder) {
-        app.add_event::<ExitVoteEvent>()
+        app.init_resource::<GamesConfig>()
+            .init_resource::<TournamentConfig>()
+            .add_event::<ExitVoteEvent>()
             .add_event::<StartVoteEvent>()
             .add_system_set(
                 SystemSet::on_enter(GAME_STATE)
@@ -116,14 +137,13 @@ fn handle_disconnect(
 
 fn handle_player_input(
     mut players: ResMut<Players>,
-    games: Res<Games>,
-    mut selected_game_query: Query<&mut SelectedGame>,
+    mut selected_game_query: Query<(&mut SelectedGame, &SelectableGames)>,
     mut start_vote_event: EventWriter<StartVoteEvent>,
     mut exit_event_writer: EventWriter<ExitVoteEvent>,
 ) {
     if players.is_changed() {
         for player in players.values_mut() {
-            if let Some(prev_action) = player.previous_action_once() {
+            for prev_action in player.previous_actions_once() {
                 match prev_action {
                     ActionEvent::APressed => {
                         start_vote_event.send(StartVoteEvent(VoteEvent::Flip(player.id())));
@@ -134,11 +154,25 @@ fn handle_player_input(
                     }
 
                     ActionEvent::LeftPressed | ActionEvent::RightPressed => {
-                        let SelectedGame(ref mut idx) = *selected_game_query.single_mut().unwrap();
+                        let (mut selected_game, SelectableGames(selectables)) =
+                            selected_game_query.single_mut().unwrap();
+                        if selectables.is_empty() {
+                            continue;
+                        }
+
+                        let SelectedGame(ref mut idx) = *selected_game;
                         if let ActionEvent::LeftPressed = prev_action {
-                            *idx = if *idx == 0 { games.len() - 1 } else { *idx - 1 };
+                            *idx = if *idx == 0 {
+                                selectables.len() - 1
+                            } else {
+                                *idx - 1
+                            };
                         } else {
-                            *idx = if *idx == games.len() - 1 { 0 } else { *idx + 1 };
+                            *idx = if *idx == selectables.len() - 1 {
+                                0
+                            } else {
+                                *idx + 1
+                            };
                         }
                     }
 
@@ -150,13 +184,15 @@ fn handle_player_input(
 }
 
 /// If a majority of the players wants to start, we should start the selected game.
+/// The game isn't started if the amount of players have changed so that the
+/// selected game doesn't support it anymore.
 #[allow(clippy::too_many_arguments)]
 fn handle_start_event(
     players: Res<Players>,
     fonts: Res<Fonts>,
     games: Res<Games>,
     mut game_state: ResMut<State<GameState>>,
-    selected_game_query: Query<&SelectedGame>,
+    selected_game_query: Query<(&SelectedGame, &SelectableGames)>,
     mut start_text: Query<&mut Text, With<StartText>>,
     mut player_start_vote: Local<PlayerVote>,
     mut start_event_reader: EventReader<StartVoteEvent>,
@@ -169,27 +205,47 @@ fn handle_start_event(
         .for_each(|vote| player_start_vote.register_vote(vote));
 
     let voted_amount_after = player_start_vote.len();
-    let total_amount_after = players.len();
+    let total_amount_after = players.human_count();
 
     if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
         player_start_vote.set_total_amount(total_amount_after);
 
         let required_amount = (player_start_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount {
-            let SelectedGame(idx) = *selected_game_query.single().unwrap();
-            let new_game_state = games.get(idx).unwrap().game_state;
-            game_state.set(new_game_state).unwrap();
-        } else {
-            let font = fonts.regular.clone();
-            let font_size = 24.0;
-            start_text.single_mut().unwrap().sections = create_vote_text_sections(
-                START_TEXT.into(),
-                &players,
-                &player_start_vote,
-                required_amount,
-                font,
-                font_size,
-            );
+        let (SelectedGame(idx), SelectableGames(selectables)) =
+            selected_game_query.single().unwrap();
+        let selected_game_state = match selectables.get(*idx) {
+            Some(Selectable::Game(game_idx)) => games
+                .get(*game_idx)
+                .filter(|game| game.supports_player_count(players.len()))
+                .map(|game| game.game_state),
+            // The tournament itself is created when entering the standings
+            // before the first game.
+            Some(Selectable::Tournament(playlist))
+                if playlist
+                    .iter()
+                    .any(|game| game.supports_player_count(players.len())) =>
+            {
+                Some(GameState::TournamentStandings)
+            }
+            _ => None,
+        };
+
+        match selected_game_state {
+            Some(selected_game_state) if voted_amount_after >= required_amount => {
+                game_state.set(selected_game_state).unwrap();
+            }
+            _ => {
+                let font = fonts.regular.clone();
+                let font_size = 24.0;
+                start_text.single_mut().unwrap().sections = create_vote_text_sections(
+                    START_TEXT.into(),
+                    &players,
+                    &player_start_vote,
+                    required_amount,
+                    font,
+                    font_size,
+                );
+            }
         }
     }
 }
@@ -211,13 +267,13 @@ fn handle_exit_event(
         .for_each(|vote| player_exit_vote.register_vote(vote));
 
     let voted_amount_after = player_exit_vote.len();
-    let total_amount_after = players.len();
+    let total_amount_after = players.human_count();
 
     if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
         player_exit_vote.set_total_amount(total_amount_after);
 
         let required_amount = (player_exit_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount && total_amount_after >= 2 {
+        if voted_amount_after >= required_amount && players.len() >= 2 {
             game_state.set(GameState::StartMenu).unwrap();
         } else {
             let font = fonts.regular.clone();
@@ -235,23 +291,59 @@ fn handle_exit_event(
 }
 
 // TODO: Move selected game to middle.
+#[allow(clippy::type_complexity)]
 fn handle_changed_game(
     mut materials: ResMut<Assets<ColorMaterial>>,
-    selected_game: Query<&SelectedGame, Changed<SelectedGame>>,
+    games: Res<Games>,
+    players: Res<Players>,
+    selected_game: Query<(&SelectedGame, &SelectableGames), Changed<SelectedGame>>,
     mut selectable_games: Query<(
         &SelectableGame,
         &Handle<ColorMaterial>,
         &mut Transform,
         &Children,
     )>,
-    mut text_query: Query<&mut Text>,
+    mut text_query: Query<&mut Text, Without<DescriptionText>>,
+    mut description_text_query: Query<&mut Text, With<DescriptionText>>,
 ) {
-    let new_selected_idx = if let Ok(SelectedGame(idx)) = selected_game.single() {
-        *idx
-    } else {
-        // True if no changed done to `SelectedGame`.
-        return;
+    let (new_selected_idx, selectables) =
+        if let Ok((SelectedGame(idx), SelectableGames(selectables))) = selected_game.single() {
+            (*idx, selectables)
+        } else {
+            // True if no changed done to `SelectedGame`.
+            return;
+        };
+
+    let description = match selectables.get(new_selected_idx) {
+        Some(Selectable::Game(game_idx)) => {
+            let game = &games[*game_idx];
+            format!(
+                "{}\n{}-{} players",
+                game.description, game.min_players, game.max_players
+            )
+        }
+        Some(Selectable::Tournament(playlist)) => {
+            let game_names = playlist
+                .iter()
+                .map(|game| game.name)
+                .collect::<Vec<_>>()
+                .join(", ");
+            format!(
+                "Play {} games in a row: {}\nEarn tournament points for your placing in every game",
+                playlist.len(),
+                game_names
+            )
+        }
+        None => format!("No games available for {} players", players.len()),
     };
+    if let Some(text_section) = description_text_query
+        .single_mut()
+        .unwrap()
+        .sections
+        .first_mut()
+    {
+        text_section.value = description;
+    }
 
     for (selectable_game, color_handle, mut transform, children) in selectable_games.iter_mut() {
         let color = &mut materials.get_mut(color_handle).unwrap().color;
@@ -296,7 +388,7 @@ fn setup_menu(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>)
     let white_color = Color::WHITE;
 
     let empty_player_vote = PlayerVote::default();
-    let required_amount = (players.len() / 2) + 1;
+    let required_amount = (players.human_count() / 2) + 1;
 
     let header_text = Text::with_section(
         HEADER_TEXT,
@@ -409,14 +501,31 @@ fn setup_menu(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>)
         .insert(GameSelectionPlugin);
 }
 
-fn setup_selectable_games(mut commands: Commands, fonts: Res<Fonts>, games: Res<Games>) {
-    let selected_idx = 0;
-    commands
-        .spawn()
-        .insert(SelectedGame(selected_idx))
-        .insert(GameSelectionPlugin);
+fn setup_selectable_games(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    fonts: Res<Fonts>,
+    games: Res<Games>,
+    games_config: Res<GamesConfig>,
+    tournament_config: Res<TournamentConfig>,
+    players: Res<Players>,
+) {
+    let mut selectables = games
+        .iter()
+        .enumerate()
+        .filter(|(_, game)| {
+            games_config.is_enabled(game) && game.supports_player_count(players.len())
+        })
+        .map(|(idx, _)| Selectable::Game(idx))
+        .collect::<Vec<_>>();
+
+    let playlist = tournament_config.playlist(&games, &games_config, players.len());
+    if !playlist.is_empty() {
+        selectables.push(Selectable::Tournament(playlist));
+    }
 
     let bold_font = fonts.bold.clone();
+    let regular_font = fonts.regular.clone();
     let white_color = Color::WHITE;
 
     let arrow_shape = Shape::polygon(30.0, Vec2::ZERO, 3);
@@ -450,23 +559,71 @@ fn setup_selectable_games(mut commands: Commands, fonts: Res<Fonts>, games: Res<
         .spawn_bundle(right_arrow_bundle)
         .insert(GameSelectionPlugin);
 
-    for (idx, game) in games.iter().enumerate() {
+    for (idx, selectable) in selectables.iter().enumerate() {
         let pos = Vec2::ZERO;
-        spawn_selected_game(&mut commands, game, idx, pos, bold_font.clone());
+        let (material, name) = match selectable {
+            Selectable::Game(game_idx) => {
+                let game = &games[*game_idx];
+                (game.screenshot.clone(), game.name)
+            }
+            // The material is modified when the selected game changes, so
+            // every tournament entry needs its own material.
+            Selectable::Tournament(_) => (
+                materials.add(Color::rgb(0.2, 0.2, 0.3).into()),
+                "Tournament",
+            ),
+        };
+        spawn_selected_game(&mut commands, material, name, idx, pos, bold_font.clone());
     }
+
+    let selected_idx = 0;
+    commands
+        .spawn()
+        .insert(SelectedGame(selected_idx))
+        .insert(SelectableGames(selectables))
+        .insert(GameSelectionPlugin);
+
+    // The text is set when the selected game changes (see `handle_changed_game`).
+    let description_text = Text::with_section(
+        "",
+        TextStyle {
+            font: regular_font,
+            font_size: 24.0,
+            color: white_color,
+        },
+        TextAlignment {
+            vertical: VerticalAlign::Bottom,
+            horizontal: HorizontalAlign::Center,
+        },
+    );
+
+    let description_text_bundle = Text2dBundle {
+        text: description_text,
+        transform: Transform::from_xyz(0.0, -GAME_HEIGHT * 0.35, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(description_text_bundle)
+        .insert(DescriptionText)
+        .insert(GameSelectionPlugin);
 }
 
 fn spawn_selected_game(
     commands: &mut Commands,
-    game: &Game,
+    material: Handle<ColorMaterial>,
+    name: &str,
     idx: usize,
     pos: Vec2,
     font: Handle<Font>,
 ) {
     let white_color = Color::WHITE;
 
+    // The size is set explicitly since not all materials have a texture, it is
+    // the same as the size of the screenshots.
     let sprite_bundle = SpriteBundle {
-        material: game.screenshot.clone(),
+        material,
+        sprite: Sprite::new(Vec2::new(GAME_WIDTH, GAME_HEIGHT)),
         transform: Transform {
             translation: Vec3::new(pos.x, pos.y, 0.0),
             scale: Vec3::new(0.25, 0.25, 0.0),
@@ -488,7 +645,7 @@ fn spawn_selected_game(
                     },
                 },
                 TextSection {
-                    value: game.name.into(),
+                    value: name.into(),
                     style: TextStyle {
                         font,
                         font_size: 64.0,
@@ -1,29 +1,55 @@
-use std::ops::{Deref, DerefMut};
+mod qr;
+
+use std::{
+    collections::HashSet,
+    ops::{Deref, DerefMut},
+};
 
 use bevy::{
     math::Vec2,
     prelude::{
-        Assets, BuildChildren, Children, Color, Commands, Entity, EventReader, EventWriter, Handle,
-        HorizontalAlign, IntoSystem, Local, Mesh, ParallelSystemDescriptorCoercion, Plugin, Query,
-        RenderPipelines, Res, ResMut, State, SystemSet, Transform, VerticalAlign,
+        Assets, BuildChildren, Children, Color, ColorMaterial, Commands, Entity, EventReader,
+        EventWriter, Handle, HorizontalAlign, Input, IntoSystem, KeyCode, Local, Mesh,
+        ParallelSystemDescriptorCoercion, Plugin, Query, RenderPipelines, Res, ResMut,
+        SpriteBundle, State, SystemSet, Texture, Transform, VerticalAlign,
     },
     render::mesh::VertexAttributeValues,
     text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
-use bevy_rapier2d::prelude::{ColliderFlags, ColliderType};
+use bevy_prototype_lyon::prelude::{DrawMode, GeometryBuilder, ShapeColors, StrokeOptions};
+use bevy_rapier2d::prelude::{ColliderFlags, ColliderType, RigidBodyPosition};
 
 use skitspel::{
-    ActionEvent, ConnectedPlayers, DisconnectedPlayers, GameState, Player, PlayerId, Players,
-    COLORS, GAME_HEIGHT, PLAYER_RADIUS,
+    ActionEvent, BotEvent, ConnectedPlayers, DisconnectedPlayers, GameState, JoinUrl, Latencies,
+    Latency, Player, PlayerId, Players, COLORS, GAME_HEIGHT, GAME_WIDTH, PLAYER_RADIUS,
+    RAPIER_SCALE_FACTOR,
 };
-use util_bevy::{despawn_entity, despawn_system, AsBevyColor, Fonts, PlayerVote, VoteEvent};
-use util_rapier::{move_players, spawn_border_walls, spawn_player};
+use util_bevy::{
+    create_vote_text_sections, despawn_entity, despawn_system, AsBevyColor, Fonts, Games,
+    PlayerVote, Shape, VoteEvent,
+};
+use util_rapier::{move_players, spawn_border_walls, spawn_player, MovementConfig};
 
 const GAME_STATE: GameState = GameState::StartMenu;
 
 const HEADER_TEXT: &str = "SKITSPEL";
 const READY_TEXT: &str = "Ready";
 const NOT_READY_TEXT: &str = "Not ready";
+const LEADERBOARD_TEXT: &str = "\nStand here to view";
+
+/// The size of the area in the bottom right corner that the players stand in
+/// to vote for showing the leaderboard.
+const LEADERBOARD_ZONE_WIDTH: f32 = 400.0;
+const LEADERBOARD_ZONE_HEIGHT: f32 = 250.0;
+
+/// Round-trip times (in ms) below these values are shown in green/yellow. Any
+/// round-trip time above them is shown in red.
+const GOOD_LATENCY_MS: u128 = 80;
+const OK_LATENCY_MS: u128 = 150;
+
+/// The horizontal distance (in pixels) between the center of the header and the
+/// left edge of the QR code.
+const QR_CODE_HEADER_OFFSET: f32 = 240.0;
 
 /// Event triggered when a player changes color.
 struct ColorChangeEvent(PlayerId);
@@ -42,6 +68,26 @@ impl DerefMut for ColorChangeEvent {
     }
 }
 
+/// Event triggered when a player enters or leaves the leaderboard zone.
+struct LeaderboardVoteEvent(VoteEvent);
+
+impl Deref for LeaderboardVoteEvent {
+    type Target = VoteEvent;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl DerefMut for LeaderboardVoteEvent {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+/// Tag used on the text inside the leaderboard zone.
+struct LeaderboardText;
+
 /// Will be used as a tag/component added to all entities related to the menu.
 ///
 /// The movable player entities in the menu are tagged with the `PlayerId`, so
@@ -53,6 +99,8 @@ pub struct MenuPlugin;
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut bevy::prelude::AppBuilder) {
         app.add_event::<ColorChangeEvent>()
+            .add_event::<LeaderboardVoteEvent>()
+            .init_resource::<MovementConfig>()
             .add_system_set(
                 SystemSet::on_enter(GAME_STATE)
                     .with_system(reset_votes.system())
@@ -64,7 +112,17 @@ impl Plugin for MenuPlugin {
                     .with_system(handle_disconnect.system().label("network"))
                     .with_system(handle_player_input.system().label("vote").after("network"))
                     .with_system(handle_color_change.system().after("vote"))
-                    .with_system(handle_ready_event.system().after("vote"))
+                    .with_system(handle_ready_event.system().label("ready").after("vote"))
+                    .with_system(handle_leaderboard_zone.system().label("zone"))
+                    .with_system(
+                        handle_leaderboard_event
+                            .system()
+                            .after("zone")
+                            .after("network")
+                            .after("ready"),
+                    )
+                    .with_system(update_latency_text.system().after("network"))
+                    .with_system(handle_bot_keys.system())
                     .with_system(move_players.system()),
             )
             .add_system_set(
@@ -76,13 +134,24 @@ impl Plugin for MenuPlugin {
 fn handle_connect(
     mut commands: Commands,
     fonts: Res<Fonts>,
+    games: Res<Games>,
+    latencies: Res<Latencies>,
     connected_players: Res<ConnectedPlayers>,
 ) {
     if !connected_players.is_empty() {
         for player in connected_players.values() {
             // TODO: Randomize position?
             let pos = Vec2::new(0.0, 0.0);
-            spawn_player_with_text(&mut commands, &fonts, player, pos, PLAYER_RADIUS);
+            let latency = latencies.get(&player.id());
+            spawn_player_with_text(
+                &mut commands,
+                &fonts,
+                &games,
+                player,
+                latency,
+                pos,
+                PLAYER_RADIUS,
+            );
         }
     }
 }
@@ -92,11 +161,14 @@ fn handle_disconnect(
     disconnected_players: Res<DisconnectedPlayers>,
     players_alive_query: Query<(Entity, &PlayerId)>,
     mut ready_event_writer: EventWriter<VoteEvent>,
+    mut leaderboard_event_writer: EventWriter<LeaderboardVoteEvent>,
 ) {
     if !disconnected_players.is_empty() {
         for (entity, player_id) in players_alive_query.iter() {
             if disconnected_players.contains(player_id) {
                 ready_event_writer.send(VoteEvent::Value(*player_id, false));
+                leaderboard_event_writer
+                    .send(LeaderboardVoteEvent(VoteEvent::Value(*player_id, false)));
                 despawn_entity(&mut commands, entity);
             }
         }
@@ -110,7 +182,7 @@ fn handle_player_input(
 ) {
     if players.is_changed() {
         for player in players.values_mut() {
-            if let Some(prev_action) = player.previous_action_once() {
+            for prev_action in player.previous_actions_once() {
                 if let ActionEvent::APressed = prev_action {
                     ready_event_writer.send(VoteEvent::Flip(player.id()));
                 } else if let ActionEvent::BPressed = prev_action {
@@ -209,29 +281,186 @@ fn handle_ready_event(
         .iter()
         .for_each(|vote| player_ready_vote.register_vote(vote));
 
+    // Bots never vote, so only the human players needs to be ready. There
+    // still needs to be at least two players (humans or bots) to start a game.
     let voted_amount_after = player_ready_vote.len();
-    let total_amount_after = players.len();
+    let total_amount_after = players.human_count();
 
     if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
         player_ready_vote.set_total_amount(total_amount_after);
 
-        let required_amount = players.len();
-        if total_amount_after >= 2 && voted_amount_after >= required_amount {
+        let required_amount = players.human_count();
+        if players.len() >= 2 && required_amount >= 1 && voted_amount_after >= required_amount {
             game_state.set(GameState::GameSelectionMenu).unwrap();
         }
     }
 }
 
+/// The human players that are standing inside the leaderboard zone are voting
+/// for showing the leaderboard.
+fn handle_leaderboard_zone(
+    players: Res<Players>,
+    player_characters: Query<(&PlayerId, &RigidBodyPosition)>,
+    mut players_inside: Local<HashSet<PlayerId>>,
+    mut leaderboard_event_writer: EventWriter<LeaderboardVoteEvent>,
+) {
+    let zone_center = leaderboard_zone_center();
+    for (player_id, pos) in player_characters.iter() {
+        if players
+            .get(player_id)
+            .map_or(true, |player| player.is_bot())
+        {
+            continue;
+        }
+
+        let x = pos.position.translation.x * RAPIER_SCALE_FACTOR - zone_center.x;
+        let y = pos.position.translation.y * RAPIER_SCALE_FACTOR - zone_center.y;
+        let is_inside =
+            x.abs() <= LEADERBOARD_ZONE_WIDTH / 2.0 && y.abs() <= LEADERBOARD_ZONE_HEIGHT / 2.0;
+
+        if is_inside != players_inside.contains(player_id) {
+            if is_inside {
+                players_inside.insert(*player_id);
+            } else {
+                players_inside.remove(player_id);
+            }
+            leaderboard_event_writer.send(LeaderboardVoteEvent(VoteEvent::Value(
+                *player_id, is_inside,
+            )));
+        }
+    }
+}
+
+/// If a majority of the players are standing in the leaderboard zone, we
+/// should show the leaderboard.
+fn handle_leaderboard_event(
+    players: Res<Players>,
+    fonts: Res<Fonts>,
+    mut game_state: ResMut<State<GameState>>,
+    mut leaderboard_text: Query<&mut Text, With<LeaderboardText>>,
+    mut player_leaderboard_vote: Local<PlayerVote>,
+    mut leaderboard_event_reader: EventReader<LeaderboardVoteEvent>,
+) {
+    let voted_amount_before = player_leaderboard_vote.voted_amount();
+    let total_amount_before = player_leaderboard_vote.total_amount();
+
+    leaderboard_event_reader
+        .iter()
+        .for_each(|vote| player_leaderboard_vote.register_vote(vote));
+
+    let voted_amount_after = player_leaderboard_vote.len();
+    let total_amount_after = players.human_count();
+
+    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
+        player_leaderboard_vote.set_total_amount(total_amount_after);
+
+        let required_amount = (player_leaderboard_vote.total_amount() / 2) + 1;
+        if voted_amount_after >= required_amount {
+            // All players might have readied up during the same frame, the
+            // game selection have precedence over the leaderboard.
+            let _ = game_state.set(GameState::Leaderboard);
+        } else if let Ok(mut text) = leaderboard_text.single_mut() {
+            let font = fonts.regular.clone();
+            let font_size = 24.0;
+            text.sections.truncate(1);
+            text.sections.extend(create_vote_text_sections(
+                LEADERBOARD_TEXT.into(),
+                &players,
+                &player_leaderboard_vote,
+                required_amount,
+                font,
+                font_size,
+            ));
+        }
+    }
+}
+
+fn leaderboard_zone_center() -> Vec2 {
+    let margin = 50.0;
+    Vec2::new(
+        GAME_WIDTH / 2.0 - LEADERBOARD_ZONE_WIDTH / 2.0 - margin,
+        -GAME_HEIGHT / 2.0 + LEADERBOARD_ZONE_HEIGHT / 2.0 + margin,
+    )
+}
+
+/// Adds a bot when pressing plus and removes the most recently added bot when
+/// pressing minus on the keyboard of the host machine.
+fn handle_bot_keys(keys: Res<Input<KeyCode>>, mut bot_event_writer: EventWriter<BotEvent>) {
+    let add_keys = [KeyCode::NumpadAdd, KeyCode::Plus, KeyCode::Equals];
+    let remove_keys = [KeyCode::NumpadSubtract, KeyCode::Minus];
+    if add_keys.iter().any(|key| keys.just_pressed(*key)) {
+        bot_event_writer.send(BotEvent::Add);
+    }
+    if remove_keys.iter().any(|key| keys.just_pressed(*key)) {
+        bot_event_writer.send(BotEvent::Remove);
+    }
+}
+
+/// Updates the latency shown next to the ready text of the players.
+fn update_latency_text(
+    latencies: Res<Latencies>,
+    fonts: Res<Fonts>,
+    player_characters: Query<(&PlayerId, &Children)>,
+    mut player_latency_text: Query<&mut Text>,
+) {
+    if !latencies.is_changed() {
+        return;
+    }
+
+    for (player_id, children) in player_characters.iter() {
+        for child_entity in children.iter() {
+            if let Ok(mut text) = player_latency_text.get_mut(*child_entity) {
+                if let Some(latency_section) = text.sections.get_mut(3) {
+                    let font = fonts.regular.clone();
+                    let font_size = 24.0;
+                    let latency = latencies.get(player_id);
+                    *latency_section = latency_text_section(latency, font, font_size);
+                }
+            }
+        }
+    }
+}
+
 fn spawn_player_with_text(
     commands: &mut Commands,
     fonts: &Fonts,
+    games: &Games,
     player: &Player,
+    latency: Option<&Latency>,
     pos: Vec2,
     radius: f32,
 ) {
     let font = fonts.regular.clone();
     let font_size = 24.0;
 
+    // The session score is shown together with the points scored in every
+    // game, ex. "Score: 7 (Push 3, Pong 4)".
+    let mut score_text = format!("\nScore: {}", player.total_score());
+    if player.total_score() > 0 {
+        let game_scores = player
+            .scores()
+            .iter()
+            .filter(|(_, score)| *score > 0)
+            .map(|(game_id, score)| {
+                let name = games.get_by_id(game_id).map_or(game_id, |game| game.name);
+                format!("{} {}", name, score)
+            })
+            .collect::<Vec<_>>();
+        score_text.push_str(&format!(" ({})", game_scores.join(", ")));
+    }
+
+    // The all-time statistics are loaded from the profile of the player when
+    // it connects, so they are only shown for the human players.
+    if !player.is_bot() {
+        let stats = player.stats();
+        score_text.push_str(&format!(
+            "\nAll-time: {} wins, {} matches",
+            stats.match_wins(),
+            stats.matches_played()
+        ));
+    }
+    score_text.push('\n');
+
     let text_bundle = Text2dBundle {
         text: Text {
             sections: vec![
@@ -244,14 +473,15 @@ fn spawn_player_with_text(
                     },
                 },
                 TextSection {
-                    value: format!("\nScore: {}\n", player.score()),
+                    value: score_text,
                     style: TextStyle {
                         font: font.clone(),
                         font_size,
                         color: Color::WHITE,
                     },
                 },
-                ready_text_section(false, font, font_size),
+                ready_text_section(false, font.clone(), font_size),
+                latency_text_section(latency, font, font_size),
             ],
             alignment: TextAlignment {
                 vertical: VerticalAlign::Bottom,
@@ -284,16 +514,57 @@ fn ready_text_section(is_ready: bool, font: Handle<Font>, font_size: f32) -> Tex
     }
 }
 
-fn reset_votes(mut ready_event_writer: EventWriter<VoteEvent>) {
+/// Creates the text showing the round-trip time (and jitter) of a player. The
+/// color indicates if the latency is good (green), ok (yellow) or bad (red).
+fn latency_text_section(
+    latency: Option<&Latency>,
+    font: Handle<Font>,
+    font_size: f32,
+) -> TextSection {
+    let (value, color) = if let Some(latency) = latency {
+        let rtt_ms = latency.rtt().as_millis();
+        let jitter_ms = latency.jitter().as_millis();
+        let color = if rtt_ms < GOOD_LATENCY_MS {
+            Color::GREEN
+        } else if rtt_ms < OK_LATENCY_MS {
+            Color::YELLOW
+        } else {
+            Color::RED
+        };
+        (format!("  {}\u{b1}{} ms", rtt_ms, jitter_ms), color)
+    } else {
+        (String::new(), Color::WHITE)
+    };
+    TextSection {
+        value,
+        style: TextStyle {
+            font,
+            font_size,
+            color,
+        },
+    }
+}
+
+fn reset_votes(
+    mut ready_event_writer: EventWriter<VoteEvent>,
+    mut leaderboard_event_writer: EventWriter<LeaderboardVoteEvent>,
+) {
     ready_event_writer.send(VoteEvent::Reset);
+    leaderboard_event_writer.send(LeaderboardVoteEvent(VoteEvent::Reset));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn setup_menu(
     mut commands: Commands,
     mut players: ResMut<Players>,
+    latencies: Res<Latencies>,
     mut meshes: ResMut<Assets<Mesh>>,
     render_pipelines: Res<RenderPipelines>,
     fonts: Res<Fonts>,
+    games: Res<Games>,
+    join_url: Option<Res<JoinUrl>>,
+    mut textures: ResMut<Assets<Texture>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
     let bold_font = fonts.bold.clone();
     let bold_font_size = 64.0;
@@ -317,7 +588,16 @@ fn setup_menu(
     for player in players.values_mut() {
         // TODO: Randomize position?
         let pos = Vec2::new(0.0, 0.0);
-        spawn_player_with_text(&mut commands, &fonts, player, pos, PLAYER_RADIUS);
+        let latency = latencies.get(&player.id());
+        spawn_player_with_text(
+            &mut commands,
+            &fonts,
+            &games,
+            player,
+            latency,
+            pos,
+            PLAYER_RADIUS,
+        );
     }
 
     let text_sections = vec![
@@ -339,6 +619,14 @@ fn setup_menu(
         },
         TextSection {
             value: "\nPress B to change color".into(),
+            style: TextStyle {
+                font: regular_font.clone(),
+                font_size: regular_font_size,
+                color: font_color,
+            },
+        },
+        TextSection {
+            value: "\nPress +/- on the keyboard to add/remove bots".into(),
             style: TextStyle {
                 font: regular_font,
                 font_size: regular_font_size,
@@ -360,4 +648,119 @@ fn setup_menu(
     };
 
     commands.spawn_bundle(text_bundle).insert(MenuPlugin);
+
+    spawn_leaderboard_zone(&mut commands, &fonts, &players);
+
+    if let Some(join_url) = join_url {
+        spawn_join_qr_code(
+            &mut commands,
+            &fonts,
+            &mut textures,
+            &mut materials,
+            &join_url,
+        );
+    }
+}
+
+/// Spawns the outline of the leaderboard zone together with the text inside
+/// of it showing the vote.
+fn spawn_leaderboard_zone(commands: &mut Commands, fonts: &Fonts, players: &Players) {
+    let zone_center = leaderboard_zone_center();
+    let font_color = Color::WHITE;
+
+    let zone_shape = Shape::rectangle(LEADERBOARD_ZONE_WIDTH, LEADERBOARD_ZONE_HEIGHT, Vec2::ZERO);
+    let zone_bundle = GeometryBuilder::build_as(
+        &zone_shape,
+        ShapeColors::new(font_color),
+        DrawMode::Stroke(StrokeOptions::default().with_line_width(4.0)),
+        Transform::from_xyz(zone_center.x, zone_center.y, 0.0),
+    );
+
+    commands.spawn_bundle(zone_bundle).insert(MenuPlugin);
+
+    let empty_player_vote = PlayerVote::default();
+    let required_amount = (players.human_count() / 2) + 1;
+    let font_size = 24.0;
+
+    let mut text_sections = vec![TextSection {
+        value: "LEADERBOARD".into(),
+        style: TextStyle {
+            font: fonts.bold.clone(),
+            font_size: 32.0,
+            color: font_color,
+        },
+    }];
+    text_sections.extend(create_vote_text_sections(
+        LEADERBOARD_TEXT.into(),
+        players,
+        &empty_player_vote,
+        required_amount,
+        fonts.regular.clone(),
+        font_size,
+    ));
+
+    let text_bundle = Text2dBundle {
+        text: Text {
+            sections: text_sections,
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Center,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(zone_center.x, zone_center.y, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(text_bundle)
+        .insert(LeaderboardText)
+        .insert(MenuPlugin);
+}
+
+/// Spawns a QR code containing the URL that players can use to join the game
+/// to the right of the header. The URL is also written out in text
+/// underneath the QR code.
+fn spawn_join_qr_code(
+    commands: &mut Commands,
+    fonts: &Fonts,
+    textures: &mut Assets<Texture>,
+    materials: &mut Assets<ColorMaterial>,
+    join_url: &JoinUrl,
+) {
+    let texture = if let Some(texture) = qr::qr_code_texture(&join_url.0) {
+        texture
+    } else {
+        return;
+    };
+
+    let size = texture.size.width as f32;
+    let x = (QR_CODE_HEADER_OFFSET + size / 2.0).min(GAME_WIDTH / 2.0 - size / 2.0);
+    let y = GAME_HEIGHT / 4.0 + size / 2.0;
+
+    let texture_handle = textures.add(texture);
+    commands
+        .spawn_bundle(SpriteBundle {
+            material: materials.add(texture_handle.into()),
+            transform: Transform::from_xyz(x, y, 0.0),
+            ..Default::default()
+        })
+        .insert(MenuPlugin);
+
+    let text_bundle = Text2dBundle {
+        text: Text::with_section(
+            join_url.0.clone(),
+            TextStyle {
+                font: fonts.regular.clone(),
+                font_size: 20.0,
+                color: Color::WHITE,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Top,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform::from_xyz(x, y - size / 2.0 - 10.0, 0.0),
+        ..Default::default()
+    };
+    commands.spawn_bundle(text_bundle).insert(MenuPlugin);
 }
@@ -0,0 +1,58 @@
+use bevy::render::texture::{
+    Extent3d, FilterMode, SamplerDescriptor, Texture, TextureDimension, TextureFormat,
+};
+use qrcode::QrCode;
+
+/// The size (in pixels) of every module ("square") of the QR code.
+const MODULE_SIZE: usize = 6;
+
+/// The amount of light modules surrounding the QR code. The QR specification
+/// requires at least 4 for the code to be readable.
+const QUIET_ZONE: usize = 4;
+
+/// Creates a texture containing a QR code encoding the given `data`.
+///
+/// Every module of the QR code is upscaled to `MODULE_SIZE` pixels so that the
+/// texture can be rendered at scale 1.0 without becoming blurry.
+pub(crate) fn qr_code_texture(data: &str) -> Option<Texture> {
+    let qr_code = match QrCode::new(data.as_bytes()) {
+        Ok(qr_code) => qr_code,
+        Err(err) => {
+            eprintln!("Unable to create QR code for \"{}\": {:#?}", data, err);
+            return None;
+        }
+    };
+
+    let width = qr_code.width();
+    let modules = qr_code.to_colors();
+    let size = (width + 2 * QUIET_ZONE) * MODULE_SIZE;
+
+    let mut pixels = Vec::with_capacity(size * size * 4);
+    for y in 0..size {
+        for x in 0..size {
+            let module_x = (x / MODULE_SIZE) as isize - QUIET_ZONE as isize;
+            let module_y = (y / MODULE_SIZE) as isize - QUIET_ZONE as isize;
+            let is_dark = module_x >= 0
+                && module_y >= 0
+                && (module_x as usize) < width
+                && (module_y as usize) < width
+                && modules[module_y as usize * width + module_x as usize] == qrcode::Color::Dark;
+
+            let value = if is_dark { 0 } else { 255 };
+            pixels.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+
+    let mut texture = Texture::new(
+        Extent3d::new(size as u32, size as u32, 1),
+        TextureDimension::D2,
+        pixels,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    texture.sampler = SamplerDescriptor {
+        mag_filter: FilterMode::Nearest,
+        min_filter: FilterMode::Nearest,
+        ..Default::default()
+    };
+    Some(texture)
+}
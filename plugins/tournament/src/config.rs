@@ -0,0 +1,129 @@
+use serde::Deserialize;
+
+use util_bevy::{Config, ConfigError, Games, GamesConfig};
+
+use crate::{Target, TournamentGame, DEFAULT_POINTS, PLACING_POINTS, PODIUM_TIME, STANDINGS_TIME};
+
+/// The settings of the tournament mode. Read from the `[tournament]` section
+/// of the config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TournamentConfig {
+    /// The games that are played in the tournament, in order. If empty, all
+    /// enabled games that supports the amount of players are played with a
+    /// target of `DEFAULT_POINTS` points.
+    pub games: Vec<TournamentGameConfig>,
+    /// The tournament points awarded for the placings in a game. The first
+    /// value is given to the winner of the game, the second value to the
+    /// runner-up etc. Players placed after the last value gets no points.
+    pub placing_points: Vec<usize>,
+    /// How long the standings are shown between games (seconds).
+    pub standings_time: usize,
+    /// How long the podium is shown after the last game (seconds).
+    pub podium_time: usize,
+}
+
+/// A game in the playlist of the tournament. The game is played until one
+/// of the players reaches `points` points in the game or until `rounds` rounds
+/// have been played. At most one of them can be specified, the game is played
+/// until `DEFAULT_POINTS` points if none of them are specified.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TournamentGameConfig {
+    /// The ID of the game (ex. "push").
+    pub game: String,
+    pub points: Option<usize>,
+    pub rounds: Option<usize>,
+}
+
+impl TournamentGameConfig {
+    pub fn target(&self) -> Target {
+        match (self.points, self.rounds) {
+            (_, Some(rounds)) => Target::Rounds(rounds),
+            (Some(points), None) => Target::Points(points),
+            (None, None) => Target::Points(DEFAULT_POINTS),
+        }
+    }
+}
+
+impl TournamentConfig {
+    /// Returns the games that should be played in a tournament with
+    /// `player_count` players. Games in the config that aren't registered, are
+    /// disabled in `games_config` or don't support `player_count` players are
+    /// skipped.
+    pub fn playlist(
+        &self,
+        games: &Games,
+        games_config: &GamesConfig,
+        player_count: usize,
+    ) -> Vec<TournamentGame> {
+        if self.games.is_empty() {
+            games
+                .iter()
+                .filter(|game| {
+                    games_config.is_enabled(game) && game.supports_player_count(player_count)
+                })
+                .map(|game| TournamentGame::new(game, Target::Points(DEFAULT_POINTS)))
+                .collect()
+        } else {
+            self.games
+                .iter()
+                .filter_map(|game_config| {
+                    let game = games.get_by_id(&game_config.game)?;
+                    if games_config.is_enabled(game) && game.supports_player_count(player_count) {
+                        Some(TournamentGame::new(game, game_config.target()))
+                    } else {
+                        None
+                    }
+                })
+                .collect()
+        }
+    }
+}
+
+impl Default for TournamentConfig {
+    fn default() -> Self {
+        Self {
+            games: Vec::default(),
+            placing_points: PLACING_POINTS.to_vec(),
+            standings_time: STANDINGS_TIME,
+            podium_time: PODIUM_TIME,
+        }
+    }
+}
+
+impl Config for TournamentConfig {
+    const SECTION: &'static str = "tournament";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        for game in &self.games {
+            let reason = match (game.points, game.rounds) {
+                (Some(_), Some(_)) => Some(format!(
+                    "only one of `points` & `rounds` can be specified for game \"{}\"",
+                    game.game
+                )),
+                (Some(0), _) | (_, Some(0)) => Some(format!(
+                    "the target of game \"{}\" must be greater than 0",
+                    game.game
+                )),
+                _ => None,
+            };
+            if let Some(reason) = reason {
+                return Err(ConfigError::new(Self::SECTION, "games", reason));
+            }
+        }
+
+        if self.placing_points.is_empty() {
+            let reason = "must contain at least one value".into();
+            Err(ConfigError::new(Self::SECTION, "placing_points", reason))
+        } else if self.standings_time == 0 {
+            let reason = "must be greater than 0, got 0".into();
+            Err(ConfigError::new(Self::SECTION, "standings_time", reason))
+        } else if self.podium_time == 0 {
+            let reason = "must be greater than 0, got 0".into();
+            Err(ConfigError::new(Self::SECTION, "podium_time", reason))
+        } else {
+            Ok(())
+        }
+    }
+}
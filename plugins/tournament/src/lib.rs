@@ -0,0 +1,413 @@
+use bevy::{
+    core::Time,
+    math::Vec2,
+    prelude::{
+        Color, Commands, EventWriter, Handle, HorizontalAlign, IntoSystem,
+        ParallelSystemDescriptorCoercion, Plugin, Query, Res, ResMut, State, SystemSet, Transform,
+        VerticalAlign, With,
+    },
+    text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
+};
+use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
+
+use skitspel::{GameState, Players, GAME_HEIGHT};
+use util_bevy::{
+    despawn_system, ignore_player_input, spawn_countdown, tick_countdown, AsBevyColor, Countdown,
+    CountdownText, EndMatchEvent, Fonts, Games, GamesConfig, MatchResult, Shape,
+};
+
+pub use config::{TournamentConfig, TournamentGameConfig};
+pub use tournament::{Target, Tournament, TournamentGame};
+
+mod config;
+mod tournament;
+
+/// The target of the games in a tournament if nothing else is specified in the
+/// config.
+pub const DEFAULT_POINTS: usize = 5;
+
+/// The default tournament points given to the players depending on their
+/// placing in a game.
+pub const PLACING_POINTS: [usize; 5] = [10, 7, 5, 3, 1];
+
+/// The default amount of seconds that the standings are shown between games.
+pub const STANDINGS_TIME: usize = 10;
+
+/// The default amount of seconds that the podium is shown after the last game.
+pub const PODIUM_TIME: usize = 15;
+
+const STANDINGS_HEADER_TEXT: &str = "Tournament Standings";
+const PODIUM_HEADER_TEXT: &str = "Tournament Results";
+
+/// The width of the podium blocks & the height of the blocks for the first,
+/// second & third placing.
+const PODIUM_BLOCK_WIDTH: f32 = 250.0;
+const PODIUM_BLOCK_HEIGHTS: [f32; 3] = [300.0, 200.0, 125.0];
+/// The x-coordinates of the podium blocks for the first, second & third placing.
+const PODIUM_BLOCK_XS: [f32; 3] = [0.0, -PODIUM_BLOCK_WIDTH, PODIUM_BLOCK_WIDTH];
+/// The y-coordinate of the bottom of the podium blocks.
+const PODIUM_BOTTOM_Y: f32 = -GAME_HEIGHT / 4.0;
+
+/// Will be used as a tag/component added to all entities related to the
+/// tournament screens.
+#[derive(Clone, Default)]
+pub struct TournamentPlugin;
+
+/// Tag used on the text containing the standings.
+struct StandingsText;
+
+impl Plugin for TournamentPlugin {
+    fn build(&self, app: &mut bevy::prelude::AppBuilder) {
+        app.init_resource::<GamesConfig>()
+            .init_resource::<TournamentConfig>()
+            .init_resource::<Tournament>()
+            .add_system(track_tournament_game.system())
+            .add_system_set(
+                SystemSet::on_enter(GameState::StartMenu).with_system(end_tournament.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::MatchResults)
+                    .with_system(finish_tournament_game.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::TournamentStandings)
+                    .with_system(start_tournament.system().label("tournament_start"))
+                    .with_system(setup_standings.system().after("tournament_start")),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::TournamentStandings)
+                    .with_system(update_standings_text.system())
+                    .with_system(handle_standings_timer.system())
+                    .with_system(ignore_player_input.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::TournamentStandings)
+                    .with_system(despawn_system::<TournamentPlugin>.system()),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::TournamentPodium).with_system(setup_podium.system()),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::TournamentPodium)
+                    .with_system(handle_podium_timer.system())
+                    .with_system(ignore_player_input.system()),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::TournamentPodium)
+                    .with_system(despawn_system::<TournamentPlugin>.system()),
+            );
+    }
+}
+
+/// Creates a new tournament when the standings are entered from the game
+/// selection menu. The standings are shown before the first game as well.
+fn start_tournament(
+    config: Res<TournamentConfig>,
+    games: Res<Games>,
+    games_config: Res<GamesConfig>,
+    players: Res<Players>,
+    mut tournament: ResMut<Tournament>,
+) {
+    if !tournament.is_running() {
+        *tournament = Tournament::new(config.playlist(&games, &games_config, players.len()));
+    }
+}
+
+/// The tournament ends when returning to the start menu, either because the
+/// podium have been shown or because the players voted to exit a game.
+fn end_tournament(mut tournament: ResMut<Tournament>) {
+    if tournament.is_running() {
+        *tournament = Tournament::default();
+    }
+}
+
+/// Keeps track of the score in the game that is currently played in the
+/// tournament. When the target of the game is reached, the match is ended.
+fn track_tournament_game(
+    players: Res<Players>,
+    game_state: Res<State<GameState>>,
+    mut tournament: ResMut<Tournament>,
+    mut end_match_event_writer: EventWriter<EndMatchEvent>,
+) {
+    let is_playing = matches!(
+        tournament.current_game(),
+        Some(game) if &game.game_state == game_state.current()
+    );
+    if !is_playing || !players.is_changed() {
+        return;
+    }
+
+    if tournament.update(&players) {
+        end_match_event_writer.send(EndMatchEvent);
+    }
+}
+
+/// Awards the tournament points when a match of the current game is over,
+/// either because the target of the game was reached or because the match
+/// ended according to the rules of the game. The standings (or the podium if
+/// it was the last game) are shown after the match results.
+fn finish_tournament_game(
+    config: Res<TournamentConfig>,
+    players: Res<Players>,
+    match_result: Res<MatchResult>,
+    mut tournament: ResMut<Tournament>,
+) {
+    let is_current_game = matches!(
+        tournament.current_game(),
+        Some(game) if game.game_state == match_result.game_state
+    );
+    if is_current_game {
+        tournament.finish_game(&players, &config.placing_points);
+    }
+}
+
+fn setup_standings(
+    mut commands: Commands,
+    config: Res<TournamentConfig>,
+    fonts: Res<Fonts>,
+    players: Res<Players>,
+    tournament: Res<Tournament>,
+) {
+    spawn_header_text(&mut commands, STANDINGS_HEADER_TEXT, fonts.bold.clone());
+
+    let standings_text_bundle = Text2dBundle {
+        text: Text {
+            sections: standings_text_sections(&tournament, &players, fonts.regular.clone()),
+            alignment: TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        },
+        transform: Transform::from_xyz(0.0, GAME_HEIGHT * 0.35 - 64.0, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(standings_text_bundle)
+        .insert(StandingsText)
+        .insert(TournamentPlugin);
+
+    let next_game = if let Some(game) = tournament.current_game() {
+        format!(
+            "Next game ({}/{}): {}, {}",
+            tournament.current_idx() + 1,
+            tournament.games().len(),
+            game.name,
+            game.target
+        )
+    } else {
+        String::default()
+    };
+
+    let next_game_text_bundle = Text2dBundle {
+        text: Text::with_section(
+            next_game,
+            TextStyle {
+                font: fonts.bold.clone(),
+                font_size: 32.0,
+                color: Color::WHITE,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform::from_xyz(0.0, -GAME_HEIGHT / 4.0, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(next_game_text_bundle)
+        .insert(TournamentPlugin);
+
+    spawn_countdown::<TournamentPlugin>(
+        &mut commands,
+        config.standings_time,
+        fonts.regular.clone(),
+    );
+}
+
+/// Updates the standings if a player connects or disconnects.
+fn update_standings_text(
+    fonts: Res<Fonts>,
+    players: Res<Players>,
+    tournament: Res<Tournament>,
+    mut standings_text_query: Query<&mut Text, With<StandingsText>>,
+) {
+    if players.is_changed() {
+        standings_text_query.single_mut().unwrap().sections =
+            standings_text_sections(&tournament, &players, fonts.regular.clone());
+    }
+}
+
+/// Starts the next game of the tournament when the standings have been shown
+/// long enough. Games that doesn't support the current amount of players are
+/// skipped.
+fn handle_standings_timer(
+    time: Res<Time>,
+    players: Res<Players>,
+    mut tournament: ResMut<Tournament>,
+    mut game_state: ResMut<State<GameState>>,
+    mut countdown_query: Query<&mut Countdown>,
+    mut countdown_text_query: Query<&mut Text, With<CountdownText>>,
+) {
+    let mut countdown = countdown_query.single_mut().unwrap();
+    let mut countdown_text = countdown_text_query.single_mut().unwrap();
+    if !tick_countdown(&time, &mut countdown, &mut countdown_text, "Starting in") {
+        return;
+    }
+
+    while let Some(game) = tournament.current_game() {
+        if game.supports_player_count(players.len()) {
+            break;
+        }
+        tournament.skip_game();
+    }
+
+    if let Some(next_game_state) = tournament.current_game().map(|game| game.game_state) {
+        tournament.start_game(&players);
+        game_state.set(next_game_state).unwrap();
+    } else {
+        game_state.set(GameState::TournamentPodium).unwrap();
+    }
+}
+
+fn setup_podium(
+    mut commands: Commands,
+    config: Res<TournamentConfig>,
+    fonts: Res<Fonts>,
+    players: Res<Players>,
+    tournament: Res<Tournament>,
+) {
+    spawn_header_text(&mut commands, PODIUM_HEADER_TEXT, fonts.bold.clone());
+
+    let standings = tournament.standings(&players);
+    let block_colors = [
+        Color::rgb(1.0, 0.84, 0.0),
+        Color::rgb(0.75, 0.75, 0.75),
+        Color::rgb(0.8, 0.5, 0.2),
+    ];
+
+    for (placing, color) in block_colors.iter().enumerate() {
+        let x = PODIUM_BLOCK_XS[placing];
+        let height = PODIUM_BLOCK_HEIGHTS[placing];
+
+        let block_shape = Shape::rectangle(
+            PODIUM_BLOCK_WIDTH,
+            height,
+            Vec2::new(x, PODIUM_BOTTOM_Y + height / 2.0),
+        );
+        let block_bundle = GeometryBuilder::build_as(
+            &block_shape,
+            ShapeColors::new(*color),
+            DrawMode::Fill(FillOptions::DEFAULT),
+            Transform::default(),
+        );
+
+        commands.spawn_bundle(block_bundle).insert(TournamentPlugin);
+
+        // Players with the same amount of points share the same placing and
+        // are shown on top of each other.
+        let font_size = 32.0;
+        let name_text_sections = standings
+            .iter()
+            .filter(|(player_placing, _, _)| *player_placing == placing)
+            .filter_map(|(_, player_id, points)| players.get(player_id).map(|p| (p, points)))
+            .map(|(player, points)| TextSection {
+                value: format!("{} ({})\n", player.name(), points),
+                style: TextStyle {
+                    font: fonts.bold.clone(),
+                    font_size,
+                    color: player.color().as_bevy(),
+                },
+            })
+            .collect::<Vec<_>>();
+        let line_count = name_text_sections.len() as f32;
+
+        let name_text_bundle = Text2dBundle {
+            text: Text {
+                sections: name_text_sections,
+                alignment: TextAlignment {
+                    vertical: VerticalAlign::Bottom,
+                    horizontal: HorizontalAlign::Center,
+                },
+            },
+            transform: Transform::from_xyz(
+                x,
+                PODIUM_BOTTOM_Y + height + line_count * font_size,
+                0.0,
+            ),
+            ..Default::default()
+        };
+
+        commands
+            .spawn_bundle(name_text_bundle)
+            .insert(TournamentPlugin);
+    }
+
+    spawn_countdown::<TournamentPlugin>(&mut commands, config.podium_time, fonts.regular.clone());
+}
+
+/// Returns to the start menu when the podium have been shown long enough.
+fn handle_podium_timer(
+    time: Res<Time>,
+    mut game_state: ResMut<State<GameState>>,
+    mut countdown_query: Query<&mut Countdown>,
+    mut countdown_text_query: Query<&mut Text, With<CountdownText>>,
+) {
+    let mut countdown = countdown_query.single_mut().unwrap();
+    let mut countdown_text = countdown_text_query.single_mut().unwrap();
+    if tick_countdown(
+        &time,
+        &mut countdown,
+        &mut countdown_text,
+        "Back to main menu in",
+    ) {
+        game_state.set(GameState::StartMenu).unwrap();
+    }
+}
+
+fn spawn_header_text(commands: &mut Commands, header: &str, font: Handle<Font>) {
+    let header_text_bundle = Text2dBundle {
+        text: Text::with_section(
+            header,
+            TextStyle {
+                font,
+                font_size: 64.0,
+                color: Color::WHITE,
+            },
+            TextAlignment {
+                vertical: VerticalAlign::Bottom,
+                horizontal: HorizontalAlign::Center,
+            },
+        ),
+        transform: Transform::from_xyz(0.0, GAME_HEIGHT * 0.35, 0.0),
+        ..Default::default()
+    };
+
+    commands
+        .spawn_bundle(header_text_bundle)
+        .insert(TournamentPlugin);
+}
+
+fn standings_text_sections(
+    tournament: &Tournament,
+    players: &Players,
+    font: Handle<Font>,
+) -> Vec<TextSection> {
+    tournament
+        .standings(players)
+        .into_iter()
+        .filter_map(|(placing, player_id, points)| {
+            let player = players.get(&player_id)?;
+            Some(TextSection {
+                value: format!("\n{}. {}: {} points", placing + 1, player.name(), points),
+                style: TextStyle {
+                    font: font.clone(),
+                    font_size: 32.0,
+                    color: player.color().as_bevy(),
+                },
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,201 @@
+use std::{cmp::Reverse, collections::HashMap, fmt::Display};
+
+use skitspel::{GameState, Player, PlayerId, Players};
+use util_bevy::Game;
+
+/// Decides when a game in the tournament is over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The game is over when a player have gained this amount of points.
+    Points(usize),
+    /// The game is over when this amount of rounds have been played. A round
+    /// is counted every time that any player is awarded points.
+    Rounds(usize),
+}
+
+impl Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Points(points) => write!(f, "first to {} points", points),
+            Target::Rounds(rounds) => write!(f, "{} rounds", rounds),
+        }
+    }
+}
+
+/// A game in the playlist of a running tournament.
+#[derive(Debug, Clone)]
+pub struct TournamentGame {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub game_state: GameState,
+    pub min_players: usize,
+    pub max_players: usize,
+    pub target: Target,
+}
+
+impl TournamentGame {
+    pub fn new(game: &Game, target: Target) -> Self {
+        Self {
+            id: game.id,
+            name: game.name,
+            game_state: game.game_state,
+            min_players: game.min_players,
+            max_players: game.max_players,
+            target,
+        }
+    }
+
+    /// Returns true if the game can be played by `player_count` players.
+    pub fn supports_player_count(&self, player_count: usize) -> bool {
+        (self.min_players..=self.max_players).contains(&player_count)
+    }
+}
+
+/// Resource containing the state of the running tournament. No tournament is
+/// running if the playlist is empty.
+#[derive(Debug, Default)]
+pub struct Tournament {
+    games: Vec<TournamentGame>,
+    /// The index of the game that is currently played or that is played next.
+    current_idx: usize,
+    /// The tournament points of every player that have taken part in the
+    /// tournament.
+    points: HashMap<PlayerId, usize>,
+    /// The scores of the players in the current game when it started. Used to
+    /// calculate the points gained in the current game since the game scores
+    /// are accumulated across all matches of the game during the session.
+    start_scores: HashMap<PlayerId, usize>,
+    /// The scores of the players the last time that the tournament was
+    /// updated, used to count rounds.
+    prev_scores: HashMap<PlayerId, usize>,
+    /// The amount of rounds played in the current game.
+    rounds: usize,
+}
+
+impl Tournament {
+    pub fn new(games: Vec<TournamentGame>) -> Self {
+        Self {
+            games,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.games.is_empty()
+    }
+
+    /// Returns true if all games of the playlist have been played.
+    pub fn is_finished(&self) -> bool {
+        self.current_idx >= self.games.len()
+    }
+
+    pub fn games(&self) -> &[TournamentGame] {
+        &self.games
+    }
+
+    /// Returns the index of the game that is currently played or played next.
+    pub fn current_idx(&self) -> usize {
+        self.current_idx
+    }
+
+    pub fn current_game(&self) -> Option<&TournamentGame> {
+        self.games.get(self.current_idx)
+    }
+
+    /// Should be called when the current game starts.
+    pub fn start_game(&mut self, players: &Players) {
+        self.start_scores = self.scores(players);
+        self.prev_scores = self.start_scores.clone();
+        self.rounds = 0;
+    }
+
+    /// Skips the current game without awarding any points. Used if the current
+    /// amount of players isn't supported by the game.
+    pub fn skip_game(&mut self) {
+        self.current_idx += 1;
+    }
+
+    /// Counts the rounds played in the current game and returns true if the
+    /// target of the game have been reached.
+    pub fn update(&mut self, players: &Players) -> bool {
+        let cur_scores = self.scores(players);
+        let points_awarded = cur_scores
+            .iter()
+            .any(|(player_id, score)| self.prev_scores.get(player_id).unwrap_or(&0) < score);
+        if points_awarded {
+            self.rounds += 1;
+        }
+        self.prev_scores = cur_scores;
+
+        match self.current_game().map(|game| game.target) {
+            Some(Target::Points(points)) => players
+                .values()
+                .any(|player| self.game_score(player) >= points),
+            Some(Target::Rounds(rounds)) => self.rounds >= rounds,
+            None => false,
+        }
+    }
+
+    /// Awards the players tournament points according to their placing in the
+    /// current game and moves on to the next game.
+    pub fn finish_game(&mut self, players: &Players, placing_points: &[usize]) {
+        let game_scores = players
+            .values()
+            .map(|player| (player.id(), self.game_score(player)))
+            .collect::<Vec<_>>();
+
+        for (placing, player_id, _) in placings(game_scores) {
+            let points = placing_points.get(placing).cloned().unwrap_or(0);
+            *self.points.entry(player_id).or_default() += points;
+        }
+
+        self.current_idx += 1;
+    }
+
+    /// Returns the placing (starting at 0), ID & tournament points of the
+    /// players in `players` sorted by their tournament points.
+    pub fn standings(&self, players: &Players) -> Vec<(usize, PlayerId, usize)> {
+        let points = players
+            .keys()
+            .map(|player_id| (*player_id, self.points.get(player_id).cloned().unwrap_or(0)))
+            .collect::<Vec<_>>();
+        placings(points)
+    }
+
+    /// Returns the amount of points that `player` have gained in the current
+    /// game.
+    fn game_score(&self, player: &Player) -> usize {
+        let start_score = self.start_scores.get(&player.id()).cloned().unwrap_or(0);
+        self.score(player).saturating_sub(start_score)
+    }
+
+    /// Returns the score of `player` in the current game.
+    fn score(&self, player: &Player) -> usize {
+        self.current_game()
+            .map_or(0, |game| player.game_score(game.id))
+    }
+
+    fn scores(&self, players: &Players) -> HashMap<PlayerId, usize> {
+        players
+            .values()
+            .map(|player| (player.id(), self.score(player)))
+            .collect()
+    }
+}
+
+/// Sorts the given `scores` with the highest score first and returns them
+/// together with the placing of the players. Players with the same score share
+/// the same placing.
+fn placings(mut scores: Vec<(PlayerId, usize)>) -> Vec<(usize, PlayerId, usize)> {
+    scores.sort_unstable_by_key(|(player_id, score)| (Reverse(*score), *player_id));
+
+    let mut result: Vec<(usize, PlayerId, usize)> = Vec::with_capacity(scores.len());
+    for (idx, (player_id, score)) in scores.into_iter().enumerate() {
+        let placing = match result.last() {
+            Some((prev_placing, _, prev_score)) if *prev_score == score => *prev_placing,
+            _ => idx,
+        };
+        result.push((placing, player_id, score));
+    }
+    result
+}
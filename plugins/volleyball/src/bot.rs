@@ -0,0 +1,90 @@
+use bevy::{
+    math::Vec2,
+    prelude::{Query, ResMut, With},
+};
+use bevy_rapier2d::prelude::RigidBodyPosition;
+
+use skitspel::{BotInput, PlayerId, Players, Team, GAME_WIDTH, PLAYER_RADIUS, RAPIER_SCALE_FACTOR};
+use util_bevy::apply_bot_input;
+
+use crate::{Ball, PushTimer, BALL_RADIUS};
+
+/// The bots start to slow down when they are closer than this (in pixels,
+/// horizontally) to the position that they are moving towards.
+const SLOW_DISTANCE: f32 = 50.0;
+
+/// How far (in pixels) behind the ball, seen from the net, the bots position
+/// themselves. This makes the bots push the ball towards the net.
+const BEHIND_BALL_DISTANCE: f32 = 20.0;
+
+/// A bot jumps if the ball is above it and horizontally closer than this (in
+/// pixels).
+const JUMP_DISTANCE: f32 = PLAYER_RADIUS * 2.0;
+
+/// A bot pushes if the ball is closer than this (in pixels).
+const PUSH_DISTANCE: f32 = PLAYER_RADIUS + BALL_RADIUS + 60.0;
+
+/// Moves the bots towards the ball when it is on their side of the net and
+/// pushes it when it gets close. When the ball is on the other side of the
+/// net, the bots move back to the middle of their side.
+pub(crate) fn handle_bots(
+    mut players: ResMut<Players>,
+    player_query: Query<(&PlayerId, &Team, &RigidBodyPosition, &PushTimer)>,
+    ball_query: Query<&RigidBodyPosition, With<Ball>>,
+) {
+    let ball_pos = if let Ok(ball_pos) = ball_query.single() {
+        rapier_to_pixels(ball_pos)
+    } else {
+        return;
+    };
+
+    for (player_id, team, pos, push_timer) in player_query.iter() {
+        if !players.get(player_id).map_or(false, |p| p.is_bot()) {
+            continue;
+        }
+
+        let input = bot_input(
+            rapier_to_pixels(pos),
+            ball_pos,
+            *team,
+            push_timer.finished(),
+        );
+        apply_bot_input(&mut players, *player_id, &input);
+    }
+}
+
+fn bot_input(bot_pos: Vec2, ball_pos: Vec2, team: Team, can_push: bool) -> BotInput {
+    // The net is located at x = 0. The left team plays on the negative side.
+    let side = match team {
+        Team::Left => -1.0,
+        Team::Right => 1.0,
+    };
+    let ball_on_own_side = ball_pos.x * side > 0.0;
+
+    let target_x = if ball_on_own_side {
+        ball_pos.x + side * BEHIND_BALL_DISTANCE
+    } else {
+        side * GAME_WIDTH / 4.0
+    };
+    let movement_x = ((target_x - bot_pos.x) / SLOW_DISTANCE).clamp(-1.0, 1.0);
+
+    let ball_is_above = ball_pos.y > bot_pos.y && (ball_pos.x - bot_pos.x).abs() < JUMP_DISTANCE;
+    let movement_y = if ball_on_own_side && ball_is_above {
+        1.0
+    } else {
+        0.0
+    };
+
+    BotInput {
+        movement: (movement_x, movement_y),
+        a: can_push && ball_pos.distance(bot_pos) < PUSH_DISTANCE,
+        ..Default::default()
+    }
+}
+
+fn rapier_to_pixels(pos: &RigidBodyPosition) -> Vec2 {
+    Vec2::new(
+        pos.position.translation.x * RAPIER_SCALE_FACTOR,
+        pos.position.translation.y * RAPIER_SCALE_FACTOR,
+    )
+}
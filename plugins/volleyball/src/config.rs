@@ -0,0 +1,60 @@
+use serde::Deserialize;
+
+use util_bevy::{ensure_positive, Config, ConfigError, MatchConfig, MatchRules, StartTimerConfig};
+
+use crate::{MATCH_POINTS, MATCH_WIN_BY, PUSH_TIME, START_TIMER_TIME};
+
+/// The tuning of the "Volleyball" game. Read from the `[volleyball]` section of
+/// the config file.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct VolleyBallConfig {
+    /// How long the timer between rounds are in seconds.
+    pub start_timer_time: usize,
+    /// The rules deciding when a match is over (the `[volleyball.match]` table).
+    #[serde(rename = "match")]
+    pub match_rules: MatchRules,
+    /// The time a push from a player is active (seconds). The player can't
+    /// start another push during this period.
+    pub push_time: f32,
+}
+
+impl Default for VolleyBallConfig {
+    fn default() -> Self {
+        Self {
+            start_timer_time: START_TIMER_TIME,
+            match_rules: MatchRules {
+                points: Some(MATCH_POINTS),
+                win_by: MATCH_WIN_BY,
+                ..Default::default()
+            },
+            push_time: PUSH_TIME,
+        }
+    }
+}
+
+impl Config for VolleyBallConfig {
+    const SECTION: &'static str = "volleyball";
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        ensure_positive(
+            Self::SECTION,
+            "start_timer_time",
+            self.start_timer_time as f32,
+        )?;
+        self.match_rules.validate(Self::SECTION)?;
+        ensure_positive(Self::SECTION, "push_time", self.push_time)
+    }
+}
+
+impl StartTimerConfig for VolleyBallConfig {
+    fn start_timer_time(&self) -> usize {
+        self.start_timer_time
+    }
+}
+
+impl MatchConfig for VolleyBallConfig {
+    fn match_rules(&self) -> &MatchRules {
+        &self.match_rules
+    }
+}
@@ -7,13 +7,11 @@ use bevy::{
     core::{Time, Timer},
     math::Vec2,
     prelude::{
-        AppBuilder, Assets, BuildChildren, Changed, Color, Commands, Entity, EventReader,
-        EventWriter, Handle, HorizontalAlign, IntoSystem, Local, Mesh, MeshBundle,
-        ParallelSystemDescriptorCoercion, Plugin, Query, QuerySet, RenderPipelines, Res, ResMut,
-        State, SystemSet, Transform, VerticalAlign, With, Without,
+        AppBuilder, Assets, BuildChildren, Color, Commands, Entity, EventReader, Handle,
+        IntoSystem, Mesh, MeshBundle, ParallelSystemDescriptorCoercion, Plugin, Query,
+        RenderPipelines, Res, ResMut, SystemSet, Transform, With, Without,
     },
     render::mesh::VertexAttributeValues,
-    text::{Font, Text, Text2dBundle, TextAlignment, TextSection, TextStyle},
 };
 use bevy_prototype_lyon::prelude::{DrawMode, FillOptions, GeometryBuilder, ShapeColors};
 use bevy_rapier2d::{
@@ -27,29 +25,34 @@ use bevy_rapier2d::{
         RigidBodyDamping, RigidBodyMassProps, RigidBodyMassPropsFlags, RigidBodyPosition,
         RigidBodyType, RigidBodyVelocity,
     },
-    render::RapierRenderPlugin,
 };
 use rand::{prelude::SliceRandom, Rng};
 
 use skitspel::{
-    ActionEvent, ConnectedPlayers, DisconnectedPlayers, GameState, Player, PlayerId, Players,
-    GAME_HEIGHT, GAME_WIDTH, PLAYER_RADIUS, RAPIER_SCALE_FACTOR, VERTEX_AMOUNT,
+    ConnectedPlayers, GameState, Player, PlayerId, Players, Team, GAME_HEIGHT, GAME_WIDTH,
+    PLAYER_RADIUS, RAPIER_SCALE_FACTOR, VERTEX_AMOUNT,
 };
 use util_bevy::{
-    create_vote_text_sections, despawn_entity, despawn_system, handle_start_timer,
-    setup_start_timer, AsBevyColor, Fonts, PlayerVote, Shape, StartTimer, VoteEvent,
+    balanced_teams, despawn_entity, handle_start_timer, reshuffled_teams, setup_start_timer,
+    team_for_new_player, ActionPressedEvent, AsBevyColor, MiniGame, MiniGamePlugin, PendingConfig,
+    ReshuffleTeamsEvent, ScoreCount, Scoring, Shape, StartTimer, SwitchTeamEvent,
 };
 use util_rapier::{
     create_path_with_thickness, create_polygon_points_with_angle, move_players, spawn_border_walls,
-    spawn_player_with_vertex_amount,
+    spawn_player_with_vertex_amount, MovementConfig,
 };
 
+pub use config::VolleyBallConfig;
+
+mod bot;
+mod config;
+
 // Regarding collisions between the invisible wall to prevent players from jumping
 // over to the other side: The ball will be only assigned to collision group 0.
 // The collider for the invisible wall over the net will then have its collision
 // with group 0 disabled.
 
-const GAME_STATE: GameState = GameState::VolleyBallGame;
+const GAME_STATE: GameState = VolleyBallGamePlugin::GAME_STATE;
 
 const BOTTOM_SPAWN_POS_Y: f32 = -GAME_HEIGHT / 2.0 + PLAYER_RADIUS / 2.0;
 
@@ -69,33 +72,21 @@ const SPAWN_POSITIONS_RIGHT: [(f32, f32); 5] = [
     (GAME_WIDTH * 0.416, BOTTOM_SPAWN_POS_Y),
 ];
 
-/// Component used to tag the text containing the score.
-struct LeftScoreText;
-struct RightScoreText;
-
-const PUSH_TEXT: &str = "Press A to push\n";
-const EXIT_TEXT: &str = "Press B to go back to main menu";
+/// The radius of the ball.
+const BALL_RADIUS: f32 = 50.0;
 
-/// How long the timer between rounds are in seconds.
+/// The default time of the timer between rounds in seconds.
 const START_TIMER_TIME: usize = 3;
 
-/// The time a push from a player is active. The player can't start another
-/// push event during this period.
-const PUSH_TIME: f32 = 0.5;
-
-/// Used to tag which team a player belongs to.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Team {
-    Left,
-    Right,
-}
+/// The default amount of points needed to win a match.
+const MATCH_POINTS: usize = 15;
 
-/// Component used to tag the text containing which players belongs to which
-/// team.
-struct TeamText;
+/// The default amount of points that the winning team must lead with.
+const MATCH_WIN_BY: usize = 2;
 
-/// Tag used on the exit text.
-struct ExitText;
+/// The default time a push from a player is active. The player can't start
+/// another push event during this period.
+const PUSH_TIME: f32 = 0.5;
 
 /// Tag used on the ball.
 struct Ball;
@@ -103,12 +94,8 @@ struct Ball;
 /// Tag used on the goals (the floor in this case).
 struct Goal;
 
-/// Component used to keep track of the current score. The left usize is the
-/// score of the left team and the right usize is the score of the right team.
-struct ScoreCount(usize, usize);
-
-/// Event created when player with ID `PlayerId` presses the push key.
-struct PushEvent(PlayerId);
+/// Event created when a player presses the push key.
+type PushEvent = ActionPressedEvent<VolleyBallGamePlugin>;
 
 /// Timer used to restrict how often a player can push.
 struct PushTimer(Timer);
@@ -127,11 +114,11 @@ impl DerefMut for PushTimer {
     }
 }
 
-impl Default for PushTimer {
-    fn default() -> Self {
+impl PushTimer {
+    fn new(push_time: f32) -> Self {
         // The timer should start as finished.
-        let mut timer = Timer::from_seconds(PUSH_TIME, false);
-        timer.tick(Duration::from_secs_f32(PUSH_TIME));
+        let mut timer = Timer::from_seconds(push_time, false);
+        timer.tick(Duration::from_secs_f32(push_time));
         Self(timer)
     }
 }
@@ -154,32 +141,41 @@ pub struct VolleyBallGamePlugin;
 
 impl Plugin for VolleyBallGamePlugin {
     fn build(&self, app: &mut AppBuilder) {
-        app.add_event::<PushEvent>()
-            .add_plugin(RapierRenderPlugin)
+        app.add_plugin(MiniGamePlugin::<VolleyBallGamePlugin>::default())
+            .init_resource::<VolleyBallConfig>()
+            .init_resource::<PendingConfig<VolleyBallConfig>>()
+            .init_resource::<MovementConfig>()
+            .init_resource::<PendingConfig<MovementConfig>>()
             .add_system_set(
                 SystemSet::on_enter(GAME_STATE)
-                    .with_system(reset_votes.system())
                     .with_system(setup_gravity.system())
                     .with_system(setup_map.system())
                     .with_system(
-                        setup_start_timer::<VolleyBallGamePlugin, START_TIMER_TIME>.system(),
+                        setup_start_timer::<VolleyBallGamePlugin, VolleyBallConfig>.system(),
                     )
-                    .with_system(setup_score.system())
-                    .with_system(setup_players.system().label("players"))
-                    .with_system(setup_screen_text.system().after("players")),
+                    .with_system(setup_players.system()),
             )
             .add_system_set(
                 SystemSet::on_update(GAME_STATE)
-                    .with_system(handle_disconnect.system().label("vote"))
                     .with_system(handle_connect.system().label("vote"))
-                    .with_system(handle_player_input.system().label("vote").label("push"))
-                    .with_system(handle_exit_event.system().after("vote"))
                     .with_system(handle_goal.system().label("goal"))
+                    .with_system(
+                        handle_switch_team
+                            .system()
+                            .after("team_selection")
+                            .before("goal"),
+                    )
+                    .with_system(
+                        handle_reshuffle
+                            .system()
+                            .after("team_selection")
+                            .before("goal"),
+                    )
                     .with_system(handle_start_timer.system().label("start").after("goal"))
-                    .with_system(update_scoreboard.system())
-                    .with_system(move_players.system())
+                    .with_system(bot::handle_bots.system().label("bots").after("vote"))
+                    .with_system(move_players.system().after("bots"))
                     .with_system(handle_ball_fall.system().after("start"))
-                    .with_system(update_push_timers.system().label("timer").after("push"))
+                    .with_system(update_push_timers.system().label("timer").after("vote"))
                     .with_system(
                         handle_spawn_push
                             .system()
@@ -189,14 +185,22 @@ impl Plugin for VolleyBallGamePlugin {
                     )
                     .with_system(handle_push.system().after("spawn_push")),
             )
-            .add_system_set(
-                SystemSet::on_exit(GAME_STATE)
-                    .with_system(despawn_system::<VolleyBallGamePlugin>.system())
-                    .with_system(teardown_gravity.system()),
-            );
+            .add_system_set(SystemSet::on_exit(GAME_STATE).with_system(teardown_gravity.system()));
     }
 }
 
+impl MiniGame for VolleyBallGamePlugin {
+    type Config = VolleyBallConfig;
+
+    const ID: &'static str = "volleyball";
+    const NAME: &'static str = "Volleyball";
+    const DESCRIPTION: &'static str =
+        "Two teams, one ball. Push the ball so that it hits the floor on the other side.";
+    const SCREENSHOT: &'static [u8] = include_bytes!("..\\assets\\volleyball.png");
+    const SCORING: Scoring = Scoring::Team;
+    const ACTION_TEXT: Option<&'static str> = Some("Press A to push");
+}
+
 fn setup_gravity(mut configuration: ResMut<RapierConfiguration>) {
     configuration.gravity = [0.0, -9.81].into();
 }
@@ -207,90 +211,82 @@ fn teardown_gravity(mut configuration: ResMut<RapierConfiguration>) {
 
 fn handle_connect(
     mut commands: Commands,
+    config: Res<VolleyBallConfig>,
+    players: Res<Players>,
     connected_players: Res<ConnectedPlayers>,
-    players_playing: Query<&Team, With<PlayerId>>,
+    players_playing: Query<(&PlayerId, &Team)>,
 ) {
-    for player in connected_players.values() {
-        let mut left_team_count = 0;
-        let mut right_team_count = 0;
-        for team in players_playing.iter() {
-            match team {
-                Team::Left => left_team_count += 1,
-                Team::Right => right_team_count += 1,
-            }
-        }
-
-        let (team, spawn_pos) = if left_team_count < right_team_count {
-            let idx = rand::thread_rng().gen_range(0..SPAWN_POSITIONS_LEFT.len());
-            (Team::Left, SPAWN_POSITIONS_LEFT[idx])
-        } else {
-            let idx = rand::thread_rng().gen_range(0..SPAWN_POSITIONS_RIGHT.len());
-            (Team::Right, SPAWN_POSITIONS_RIGHT[idx])
-        };
+    let mut teams = players_playing
+        .iter()
+        .map(|(player_id, team)| (*player_id, *team))
+        .collect::<Vec<_>>();
 
-        spawn_volleyball_player(&mut commands, player, spawn_pos.into(), team);
+    for player in connected_players.values() {
+        let team = team_for_new_player(&players, &teams);
+        teams.push((player.id(), team));
+        spawn_volleyball_player(&mut commands, &config, player, random_spawn_pos(team), team);
     }
 }
 
-fn handle_disconnect(
+/// Respawns the players that have picked the other team during the team
+/// selection on the side of their new team.
+fn handle_switch_team(
     mut commands: Commands,
-    disconnected_players: Res<DisconnectedPlayers>,
+    config: Res<VolleyBallConfig>,
+    players: Res<Players>,
     players_playing: Query<(Entity, &PlayerId)>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
+    mut switch_team_event_reader: EventReader<SwitchTeamEvent<VolleyBallGamePlugin>>,
 ) {
-    if !disconnected_players.is_empty() {
-        for (entity, player_id) in players_playing.iter() {
-            if disconnected_players.contains(player_id) {
-                exit_event_writer.send(VoteEvent::Value(*player_id, false));
-                despawn_entity(&mut commands, entity);
-            }
+    for switch_team_event in switch_team_event_reader.iter() {
+        let player_id = switch_team_event.player_id;
+        let player = match players.get(&player_id) {
+            Some(player) => player,
+            None => continue,
+        };
+
+        for (entity, _) in players_playing.iter().filter(|(_, id)| **id == player_id) {
+            despawn_entity(&mut commands, entity);
         }
+
+        let team = switch_team_event.team;
+        spawn_volleyball_player(&mut commands, &config, player, random_spawn_pos(team), team);
     }
 }
 
-#[allow(clippy::type_complexity, clippy::too_many_arguments)]
-fn update_scoreboard(
+/// Returns a random spawn position on the side of team `team`.
+fn random_spawn_pos(team: Team) -> Vec2 {
+    let spawn_positions = match team {
+        Team::Left => &SPAWN_POSITIONS_LEFT,
+        Team::Right => &SPAWN_POSITIONS_RIGHT,
+    };
+    let idx = rand::thread_rng().gen_range(0..spawn_positions.len());
+    spawn_positions[idx].into()
+}
+
+/// Splits the players into new teams when the players have voted to reshuffle
+/// the teams during the team selection. The players are respawned on the side
+/// of their new team.
+fn handle_reshuffle(
     mut commands: Commands,
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    score_change: Query<&ScoreCount, Changed<ScoreCount>>,
-    mut score_text_query: QuerySet<(
-        Query<&mut Text, With<LeftScoreText>>,
-        Query<&mut Text, With<RightScoreText>>,
-    )>,
-    players_playing: Query<(&PlayerId, &Team)>,
-    old_team_text: Query<Entity, With<TeamText>>,
-    mut players_playing_count: Local<usize>,
+    config: Res<VolleyBallConfig>,
+    mut players: ResMut<Players>,
+    players_playing: Query<(Entity, &PlayerId, &Team)>,
+    mut reshuffle_event_reader: EventReader<ReshuffleTeamsEvent<VolleyBallGamePlugin>>,
 ) {
-    for new_score_count in score_change.iter() {
-        let ScoreCount(left_score, right_score) = new_score_count;
-
-        let mut left_score_text = score_text_query.q0_mut().single_mut().unwrap();
-        if let Some(text_section) = left_score_text.sections.first_mut() {
-            text_section.value = left_score.to_string();
-        }
-
-        let mut right_score_text = score_text_query.q1_mut().single_mut().unwrap();
-        if let Some(text_section) = right_score_text.sections.first_mut() {
-            text_section.value = right_score.to_string();
-        }
+    if reshuffle_event_reader.iter().count() == 0 {
+        return;
     }
 
-    let cur_players_playing_count = players_playing.iter().count();
-    if *players_playing_count != cur_players_playing_count || score_change.iter().count() > 0 {
-        *players_playing_count = cur_players_playing_count;
-
-        let font = fonts.bold.clone();
-        let font_size = 128.0;
-        update_team_text(
-            &mut commands,
-            &players,
-            players_playing,
-            old_team_text,
-            font,
-            font_size,
-        );
+    let current_teams = players_playing
+        .iter()
+        .map(|(_, player_id, team)| (*player_id, *team))
+        .collect::<Vec<_>>();
+    for (entity, ..) in players_playing.iter() {
+        despawn_entity(&mut commands, entity);
     }
+
+    let teams = reshuffled_teams(&players, &current_teams);
+    spawn_teams(&mut commands, &config, &mut players, &teams);
 }
 
 /// Slows down the speed in which the ball is falling. A force will be applied
@@ -336,6 +332,10 @@ fn handle_ball_fall(
 #[allow(clippy::too_many_arguments)]
 fn handle_goal(
     mut commands: Commands,
+    mut config: ResMut<VolleyBallConfig>,
+    mut pending_config: ResMut<PendingConfig<VolleyBallConfig>>,
+    mut movement_config: ResMut<MovementConfig>,
+    mut pending_movement_config: ResMut<PendingConfig<MovementConfig>>,
     mut intersection_event: EventReader<IntersectionEvent>,
     mut players: ResMut<Players>,
     players_playing: Query<(Entity, &PlayerId, &Team), Without<Push>>,
@@ -373,6 +373,9 @@ fn handle_goal(
                 }
             };
 
+            pending_config.apply(&mut config);
+            pending_movement_config.apply(&mut movement_config);
+
             // TODO: Can there be a panic if we remove it here and in the same
             //       frame the PushTimer finishes and the `handle_push`
             //       despawn the entity as well?
@@ -395,7 +398,11 @@ fn handle_goal(
             for (_, player_id, team) in players_playing.iter() {
                 if let Some(player) = players.get_mut(player_id) {
                     if *team == scoring_team {
-                        player.increment_score();
+                        player.increment_score(VolleyBallGamePlugin::ID);
+                        player.record_stats(VolleyBallGamePlugin::ID, |stats| {
+                            stats.round_wins += 1;
+                            stats.goals += 1;
+                        });
                     }
 
                     let spawn_pos = match team {
@@ -404,38 +411,23 @@ fn handle_goal(
                     };
 
                     player.reset_action();
-                    spawn_volleyball_player(&mut commands, player, spawn_pos.into(), *team);
+                    spawn_volleyball_player(
+                        &mut commands,
+                        &config,
+                        player,
+                        spawn_pos.into(),
+                        *team,
+                    );
                 }
             }
 
             let (mut ball_pos, mut ball_velocity) = ball_query.single_mut().unwrap();
             reset_ball(&mut ball_pos, &mut ball_velocity);
 
-            start_timer_query.single_mut().unwrap().reset();
-        }
-    }
-}
-
-fn handle_player_input(
-    mut players: ResMut<Players>,
-    mut push_event_writer: EventWriter<PushEvent>,
-    mut exit_event_writer: EventWriter<VoteEvent>,
-) {
-    if players.is_changed() {
-        for player in players.values_mut() {
-            if let Some(prev_action) = player.previous_action_once() {
-                match prev_action {
-                    ActionEvent::APressed => {
-                        push_event_writer.send(PushEvent(player.id()));
-                    }
-
-                    ActionEvent::BPressed => {
-                        exit_event_writer.send(VoteEvent::Flip(player.id()));
-                    }
-
-                    _ => (),
-                }
-            }
+            start_timer_query
+                .single_mut()
+                .unwrap()
+                .restart(config.start_timer_time);
         }
     }
 }
@@ -475,7 +467,11 @@ fn handle_spawn_push(
         return;
     }
 
-    for PushEvent(event_player_id) in push_event_reader.iter() {
+    for PushEvent {
+        player_id: event_player_id,
+        ..
+    } in push_event_reader.iter()
+    {
         // TODO: More performant way to get player_query from player_id?
         for (player_id, mut push_timer, player_vel, player_pos, player_shape) in
             player_query.iter_mut()
@@ -558,140 +554,13 @@ fn remove_push(
     }
 }
 
-/// If a majority of the players wants to exit, we should return back to the menu.
-fn handle_exit_event(
-    players: Res<Players>,
-    fonts: Res<Fonts>,
-    mut game_state: ResMut<State<GameState>>,
-    mut exit_text: Query<&mut Text, With<ExitText>>,
-    mut player_exit_vote: Local<PlayerVote>,
-    mut exit_event_reader: EventReader<VoteEvent>,
-) {
-    let voted_amount_before = player_exit_vote.voted_amount();
-    let total_amount_before = player_exit_vote.total_amount();
-
-    exit_event_reader
-        .iter()
-        .for_each(|vote| player_exit_vote.register_vote(vote));
-
-    let voted_amount_after = player_exit_vote.len();
-    let total_amount_after = players.len();
-
-    if voted_amount_before != voted_amount_after || total_amount_before != total_amount_after {
-        player_exit_vote.set_total_amount(total_amount_after);
-
-        let required_amount = (player_exit_vote.total_amount() / 2) + 1;
-        if voted_amount_after >= required_amount {
-            game_state.set(GameState::StartMenu).unwrap();
-        } else {
-            let font = fonts.regular.clone();
-            let font_size = 24.0;
-            exit_text.single_mut().unwrap().sections = create_vote_text_sections(
-                EXIT_TEXT.into(),
-                &players,
-                &player_exit_vote,
-                required_amount,
-                font,
-                font_size,
-            );
-        }
-    }
-}
-
-fn update_team_text(
+fn spawn_volleyball_player(
     commands: &mut Commands,
-    players: &Players,
-    players_playing: Query<(&PlayerId, &Team)>,
-    old_team_text: Query<Entity, With<TeamText>>,
-    font: Handle<Font>,
-    font_size: f32,
+    config: &VolleyBallConfig,
+    player: &Player,
+    spawn_pos: Vec2,
+    team: Team,
 ) {
-    for entity in old_team_text.iter() {
-        despawn_entity(commands, entity);
-    }
-
-    let mut left_team_player_ids = players_playing
-        .iter()
-        .filter(|(_, team)| matches!(team, Team::Left))
-        .map(|(id, _)| *id)
-        .collect::<Vec<_>>();
-    left_team_player_ids.sort_unstable();
-
-    let left_team_text_sections =
-        create_team_text_sections(&left_team_player_ids, players, font.clone(), font_size);
-
-    let left_team_text_bundle = Text2dBundle {
-        text: Text {
-            sections: left_team_text_sections,
-            alignment: TextAlignment {
-                vertical: VerticalAlign::Bottom,
-                horizontal: HorizontalAlign::Center,
-            },
-        },
-        transform: Transform::from_xyz(-GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0 - font_size / 2.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(left_team_text_bundle)
-        .insert(TeamText)
-        .insert(VolleyBallGamePlugin);
-
-    let right_team_player_ids = players_playing
-        .iter()
-        .filter(|(_, team)| matches!(team, Team::Right))
-        .map(|(id, _)| *id)
-        .collect::<Vec<_>>();
-    left_team_player_ids.sort_unstable();
-
-    let right_team_text_sections =
-        create_team_text_sections(&right_team_player_ids, players, font, font_size);
-
-    let right_team_text_bundle = Text2dBundle {
-        text: Text {
-            sections: right_team_text_sections,
-            alignment: TextAlignment {
-                vertical: VerticalAlign::Bottom,
-                horizontal: HorizontalAlign::Center,
-            },
-        },
-        transform: Transform::from_xyz(GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0 - font_size / 2.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(right_team_text_bundle)
-        .insert(TeamText)
-        .insert(VolleyBallGamePlugin);
-}
-
-fn create_team_text_sections(
-    team_player_ids: &[PlayerId],
-    players: &Players,
-    font: Handle<Font>,
-    font_size: f32,
-) -> Vec<TextSection> {
-    let mut text_sections = Vec::with_capacity(team_player_ids.len());
-    for player_id in team_player_ids {
-        if let Some(player) = players.get(player_id) {
-            text_sections.push(TextSection {
-                value: "•".into(),
-                style: TextStyle {
-                    font: font.clone(),
-                    font_size,
-                    color: player.color().as_bevy(),
-                },
-            });
-        }
-    }
-    text_sections
-}
-
-fn reset_votes(mut exit_event_writer: EventWriter<VoteEvent>) {
-    exit_event_writer.send(VoteEvent::Reset);
-}
-
-fn spawn_volleyball_player(commands: &mut Commands, player: &Player, spawn_pos: Vec2, team: Team) {
     // Prevent round shape.
     let vertex_idx = rand::thread_rng().gen_range(0..VERTEX_AMOUNT.len() - 1);
     let vertex_amount = VERTEX_AMOUNT[vertex_idx + 1];
@@ -717,7 +586,7 @@ fn spawn_volleyball_player(commands: &mut Commands, player: &Player, spawn_pos:
         radius: PLAYER_RADIUS,
     })
     .insert(team)
-    .insert(PushTimer::default())
+    .insert(PushTimer::new(config.push_time))
     .insert(VolleyBallGamePlugin);
 }
 
@@ -806,127 +675,39 @@ fn spawn_push(
     }
 }
 
-fn setup_screen_text(mut commands: Commands, players: Res<Players>, fonts: Res<Fonts>) {
-    let bold_font = fonts.bold.clone();
-    let bold_font_size = 128.0;
-    let regular_font = fonts.regular.clone();
-    let regular_font_size = 24.0;
-    let font_color = Color::WHITE;
-
-    let empty_player_vote = PlayerVote::default();
-    let required_amount = (players.len() / 2) + 1;
-
-    let expand_text = Text::with_section(
-        PUSH_TEXT,
-        TextStyle {
-            font: regular_font.clone(),
-            font_size: regular_font_size,
-            color: font_color,
-        },
-        TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    );
-
-    let expand_text_bundle = Text2dBundle {
-        text: expand_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0 - regular_font_size, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(expand_text_bundle)
-        .insert(VolleyBallGamePlugin);
-
-    let exit_text = Text {
-        sections: create_vote_text_sections(
-            EXIT_TEXT.into(),
-            &players,
-            &empty_player_vote,
-            required_amount,
-            regular_font,
-            regular_font_size,
-        ),
-        alignment: TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    };
-
-    let exit_text_bundle = Text2dBundle {
-        text: exit_text,
-        transform: Transform::from_xyz(0.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(exit_text_bundle)
-        .insert(ExitText)
-        .insert(VolleyBallGamePlugin);
-
-    let zero_score_text = Text::with_section(
-        "0",
-        TextStyle {
-            font: bold_font,
-            font_size: bold_font_size,
-            color: Color::WHITE,
-        },
-        TextAlignment {
-            vertical: VerticalAlign::Bottom,
-            horizontal: HorizontalAlign::Center,
-        },
-    );
-
-    let left_score_text_bundle = Text2dBundle {
-        text: zero_score_text.clone(),
-        transform: Transform::from_xyz(-GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(left_score_text_bundle)
-        .insert(LeftScoreText)
-        .insert(VolleyBallGamePlugin);
-
-    let right_score_text_bundle = Text2dBundle {
-        text: zero_score_text,
-        transform: Transform::from_xyz(GAME_WIDTH / 4.0, GAME_HEIGHT / 4.0, 0.0),
-        ..Default::default()
-    };
-
-    commands
-        .spawn_bundle(right_score_text_bundle)
-        .insert(RightScoreText)
-        .insert(VolleyBallGamePlugin);
-}
-
-fn setup_score(mut commands: Commands) {
-    commands
-        .spawn()
-        .insert(ScoreCount(0, 0))
-        .insert(VolleyBallGamePlugin);
+/// Splits the players into two teams with ratings as even as possible.
+fn setup_players(
+    mut commands: Commands,
+    config: Res<VolleyBallConfig>,
+    mut players: ResMut<Players>,
+) {
+    let player_ids = players.keys().cloned().collect::<Vec<_>>();
+    let teams = balanced_teams(&players, &player_ids);
+    spawn_teams(&mut commands, &config, &mut players, &teams);
 }
 
-fn setup_players(mut commands: Commands, mut players: ResMut<Players>) {
+/// Spawns the players in `teams` on random positions on the side of their team.
+fn spawn_teams(
+    commands: &mut Commands,
+    config: &VolleyBallConfig,
+    players: &mut Players,
+    teams: &[(PlayerId, Team)],
+) {
     let mut left_spawn_positions = SPAWN_POSITIONS_LEFT.to_vec();
     left_spawn_positions.shuffle(&mut rand::thread_rng());
     let mut right_spawn_positions = SPAWN_POSITIONS_RIGHT.to_vec();
     right_spawn_positions.shuffle(&mut rand::thread_rng());
 
-    let mut players_shuffled = players.values_mut().collect::<Vec<_>>();
-    players_shuffled.shuffle(&mut rand::thread_rng());
-
-    let half_idx = players_shuffled.len() / 2;
-    for (i, player) in players_shuffled.into_iter().enumerate() {
-        let (team, spawn_pos) = if i < half_idx {
-            (Team::Left, left_spawn_positions.pop().unwrap())
-        } else {
-            (Team::Right, right_spawn_positions.pop().unwrap())
-        };
+    for (player_id, team) in teams {
+        if let Some(player) = players.get_mut(player_id) {
+            let spawn_pos = match team {
+                Team::Left => left_spawn_positions.pop().unwrap(),
+                Team::Right => right_spawn_positions.pop().unwrap(),
+            };
 
-        player.reset_action();
-        spawn_volleyball_player(&mut commands, player, spawn_pos.into(), team);
+            player.reset_action();
+            spawn_volleyball_player(commands, config, player, spawn_pos.into(), *team);
+        }
     }
 }
 
@@ -1041,7 +822,7 @@ fn setup_map(
         Team::Right,
     );
 
-    spawn_ball(&mut commands, 50.0, white_color);
+    spawn_ball(&mut commands, BALL_RADIUS, white_color);
 }
 
 fn spawn_goal(
@@ -0,0 +1,239 @@
+use std::{
+    fs,
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use bevy::{
+    core::{Time, Timer},
+    prelude::{AppBuilder, State, World},
+};
+use serde::Deserialize;
+
+use achtung::AchtungConfig;
+use hockey::HockeyConfig;
+use pong::PongConfig;
+use push::PushConfig;
+use skitspel::GameState;
+use tournament::TournamentConfig;
+use util_bevy::{Config, ConfigError, Games, GamesConfig, PendingConfig};
+use util_rapier::MovementConfig;
+use volleyball::VolleyBallConfig;
+
+/// How often (in seconds) the config file is checked for changes.
+const CONFIG_POLL_TIME: f32 = 1.0;
+
+/// The config file of the server. Contains one section per game and any value
+/// that isn't specified in the file gets its default value.
+///
+/// Example of a config file:
+/// ```toml
+/// [movement]
+/// accel_amount = 20.0
+///
+/// [pong]
+/// ball_start_speed = 200.0
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    games: GamesConfig,
+    movement: MovementConfig,
+    push: PushConfig,
+    hockey: HockeyConfig,
+    volleyball: VolleyBallConfig,
+    achtung: AchtungConfig,
+    pong: PongConfig,
+    tournament: TournamentConfig,
+}
+
+impl ServerConfig {
+    /// Reads and validates the config file found at `path`. Unknown sections,
+    /// unknown keys and invalid values are reported as errors.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let content = fs::read_to_string(path).map_err(|e| {
+            io::Error::new(
+                e.kind(),
+                format!("Unable to read config file {:?}: {}", path, e),
+            )
+        })?;
+
+        let config: ServerConfig = toml::from_str(&content).map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Unable to parse config file {:?}: {}", path, e),
+            )
+        })?;
+
+        config.validate().map_err(|e| {
+            io::Error::new(
+                ErrorKind::InvalidData,
+                format!("Invalid config file {:?}: {}", path, e),
+            )
+        })?;
+
+        Ok(config)
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        self.games.validate()?;
+        self.movement.validate()?;
+        self.push.validate()?;
+        self.hockey.validate()?;
+        self.volleyball.validate()?;
+        self.achtung.validate()?;
+        self.pong.validate()?;
+        self.tournament.validate()
+    }
+
+    /// Inserts all configs as resources. This should be done before the game
+    /// plugins are added since they will insert the default configs otherwise.
+    pub fn insert_resources(self, app: &mut AppBuilder) {
+        app.insert_resource(self.games)
+            .insert_resource(self.movement)
+            .insert_resource(self.push)
+            .insert_resource(self.hockey)
+            .insert_resource(self.volleyball)
+            .insert_resource(self.achtung)
+            .insert_resource(self.pong)
+            .insert_resource(self.tournament);
+    }
+
+    /// Sets all configs as pending. The games will apply them at the start of
+    /// their next round. Configs that aren't used during a round (ex. the
+    /// `GamesConfig` & `TournamentConfig`) have no `PendingConfig` and are
+    /// applied directly.
+    fn set_pending(self, world: &mut World) {
+        set_pending(world, self.games);
+        set_pending(world, self.movement);
+        set_pending(world, self.push);
+        set_pending(world, self.hockey);
+        set_pending(world, self.volleyball);
+        set_pending(world, self.achtung);
+        set_pending(world, self.pong);
+        set_pending(world, self.tournament);
+    }
+
+    /// Applies all pending configs directly. Should only be done when no game
+    /// is running.
+    fn apply_pending(world: &mut World) {
+        apply_pending::<MovementConfig>(world);
+        apply_pending::<PushConfig>(world);
+        apply_pending::<HockeyConfig>(world);
+        apply_pending::<VolleyBallConfig>(world);
+        apply_pending::<AchtungConfig>(world);
+        apply_pending::<PongConfig>(world);
+    }
+}
+
+/// Prints a warning for every game ID in the `GamesConfig` & `TournamentConfig`
+/// that doesn't match the ID of a registered game, since it is most likely a
+/// typo.
+pub fn check_game_ids(world: &World) {
+    let games = world.get_resource::<Games>().unwrap();
+    let games_config = world.get_resource::<GamesConfig>().unwrap();
+    for id in &games_config.disabled {
+        if games.get_by_id(id).is_none() {
+            eprintln!("Unknown game \"{}\" in `games.disabled`, ignoring it.", id);
+        }
+    }
+
+    let tournament_config = world.get_resource::<TournamentConfig>().unwrap();
+    for game_config in &tournament_config.games {
+        if games.get_by_id(&game_config.game).is_none() {
+            eprintln!(
+                "Unknown game \"{}\" in `tournament.games`, ignoring it.",
+                game_config.game
+            );
+        }
+    }
+}
+
+fn set_pending<C: Config>(world: &mut World, config: C) {
+    if let Some(mut pending_config) = world.get_resource_mut::<PendingConfig<C>>() {
+        pending_config.set(config);
+    } else {
+        world.insert_resource(config);
+    }
+}
+
+fn apply_pending<C: Config>(world: &mut World) {
+    let config = world
+        .get_resource_mut::<PendingConfig<C>>()
+        .and_then(|mut pending_config| pending_config.take());
+    if let Some(config) = config {
+        world.insert_resource(config);
+    }
+}
+
+/// Keeps track of the config file so that it can be reloaded when it is
+/// changed on disk.
+pub struct ConfigWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    timer: Timer,
+}
+
+impl ConfigWatcher {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            modified: modified_time(&path),
+            path,
+            timer: Timer::from_seconds(CONFIG_POLL_TIME, true),
+        }
+    }
+
+    /// Returns the reloaded config if the config file have been modified since
+    /// the last time it was loaded. If the new config is invalid, an error is
+    /// printed and the current config is kept.
+    fn poll(&mut self, delta: Duration) -> Option<ServerConfig> {
+        if !self.timer.tick(delta).just_finished() {
+            return None;
+        }
+
+        let modified = modified_time(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+
+        match ServerConfig::load(&self.path) {
+            Ok(config) => {
+                println!("Reloaded config file {:?}.", self.path);
+                Some(config)
+            }
+            Err(err) => {
+                eprintln!("Unable to reload config, keeping current config: {}", err);
+                None
+            }
+        }
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Reloads the config file when it is changed on disk.
+///
+/// The reloaded configs are set as pending and the running game applies them
+/// when it resets its next round, so the values never changes in the middle of
+/// a round. When no game is running, the pending configs are applied directly.
+pub fn reload_config(world: &mut World) {
+    let delta = world.get_resource::<Time>().unwrap().delta();
+    let config = world
+        .get_resource_mut::<ConfigWatcher>()
+        .unwrap()
+        .poll(delta);
+    if let Some(config) = config {
+        config.set_pending(world);
+        check_game_ids(world);
+    }
+
+    let game_state = world.get_resource::<State<GameState>>().unwrap().current();
+    let is_game_running = matches!(game_state, GameState::Game(_));
+    if !is_game_running {
+        ServerConfig::apply_pending(world);
+    }
+}
@@ -1,21 +1,30 @@
 use std::{
+    collections::HashMap,
     io::ErrorKind,
+    path::PathBuf,
     process::exit,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
 use bevy::{
-    app::Events,
-    input::{keyboard::KeyboardInput, ElementState},
+    app::{Events, ScheduleRunnerSettings},
+    asset::AssetPlugin,
+    input::{keyboard::KeyboardInput, ElementState, InputPlugin},
+    log::LogPlugin,
     prelude::*,
     render::{
         camera::{Camera, OrthographicProjection, ScalingMode},
         pipeline::{PipelineDescriptor, RenderPipeline},
+        renderer::{HeadlessRenderResourceContext, RenderResourceContext},
         shader::{ShaderStage, ShaderStages},
-        texture::ImageType,
+        RenderPlugin,
     },
-    window::{WindowMode, WindowResized},
-    DefaultPlugins,
+    sprite::SpritePlugin,
+    text::TextPlugin,
+    transform::TransformPlugin,
+    window::{WindowMode, WindowPlugin, WindowResized},
+    DefaultPlugins, MinimalPlugins,
 };
 use bevy_prototype_lyon::plugin::ShapePlugin;
 use bevy_rapier2d::prelude::*;
@@ -25,22 +34,40 @@ use rpassword::prompt_password_stdout;
 use smol::io;
 
 use skitspel::{
-    ActionEvent, ConnectedPlayers, DisconnectedPlayers, GameState, Player, Players, Port,
-    TLSCertificate, COLORS, GAME_HEIGHT, GAME_WIDTH, RAPIER_SCALE_FACTOR,
+    BotEvent, ConnectedPlayers, ConnectedSpectators, DisconnectedPlayers, GameState, Latencies,
+    Player, PlayerId, Players, Port, Profiles, ReconnectedPlayers, ServerMessage,
+    ServerMessageEvent, TLSCertificate, COLORS, GAME_HEIGHT, GAME_WIDTH, RAPIER_SCALE_FACTOR,
 };
-use util_bevy::{Fonts, Game, Games, VoteEvent};
+use util_bevy::{EndMatchEvent, Fonts, Games, VoteEvent};
 
 use achtung::AchtungGamePlugin;
 use hockey::HockeyGamePlugin;
-use network::{
-    EventMessage, EventTimer, GeneralEvent, NetworkContext, NetworkEvent, NetworkPlugin,
-};
+use leaderboard::LeaderboardPlugin;
+use local_input::LocalInputPlugin;
+use match_results::MatchResultsPlugin;
+use network::{EventMessage, GeneralEvent, NetworkContext, NetworkEvent, NetworkPlugin};
 use pong::PongGamePlugin;
 use push::PushGamePlugin;
 use selection_menu::GameSelectionPlugin;
 use start_menu::MenuPlugin;
+use tournament::TournamentPlugin;
 use volleyball::VolleyBallGamePlugin;
 
+use crate::{
+    config::{check_game_ids, reload_config, ConfigWatcher, ServerConfig},
+    profiles::{sync_profiles, ProfilesFile},
+};
+
+mod config;
+mod profiles;
+
+/// The amount of ticks per second when running in headless mode. When running
+/// with a window, the tick rate is decided by the refresh rate of the screen.
+const HEADLESS_TICK_RATE: f64 = 60.0;
+
+/// The amount of bots to add when the server starts.
+struct InitialBots(usize);
+
 fn main() -> io::Result<()> {
     std::env::set_var("SMOL_THREADS", num_cpus::get().to_string());
 
@@ -71,6 +98,42 @@ fn main() -> io::Result<()> {
                 .takes_value(true)
                 .required(false),
         )
+        .arg(
+            Arg::new("config")
+                .short('C')
+                .long("config")
+                .value_name("PATH")
+                .help("Path to a TOML config file containing tuning of the games.")
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("profiles")
+                .short('P')
+                .long("profiles")
+                .value_name("PATH")
+                .help(
+                    "Path to a JSON file where the all-time statistics of the players are stored.",
+                )
+                .takes_value(true)
+                .required(false),
+        )
+        .arg(
+            Arg::new("headless")
+                .long("headless")
+                .help("Run without a window and without rendering anything.")
+                .takes_value(false)
+                .required(false),
+        )
+        .arg(
+            Arg::new("bots")
+                .short('b')
+                .long("bots")
+                .value_name("AMOUNT")
+                .help("The amount of bots to add when the server starts.")
+                .takes_value(true)
+                .required(false),
+        )
         .get_matches();
 
     let port_nr = matches.value_of("port").unwrap().parse().map_err(|_| {
@@ -83,6 +146,35 @@ fn main() -> io::Result<()> {
         )
     })?;
 
+    let initial_bots = if let Some(bots) = matches.value_of("bots") {
+        bots.parse().map_err(|_| {
+            io::Error::new(
+                ErrorKind::Other,
+                format!("Unable to parse specified bots as usize: {:?}", bots),
+            )
+        })?
+    } else {
+        0
+    };
+
+    let config_path = matches.value_of("config").map(PathBuf::from);
+    let config = if let Some(path) = &config_path {
+        ServerConfig::load(path)?
+    } else {
+        ServerConfig::default()
+    };
+
+    let profiles_file = matches
+        .value_of("profiles")
+        .map(|path| ProfilesFile::new(path.into()));
+    let profiles = if let Some(profiles_file) = &profiles_file {
+        profiles_file.load()?
+    } else {
+        Profiles::default()
+    };
+
+    let headless = matches.is_present("headless");
+
     let tls_cert = if !matches.is_present("nocert") {
         if let Some(path) = matches.value_of("cert") {
             let password = prompt_password_stdout("Password for certificate:")?;
@@ -104,60 +196,115 @@ fn main() -> io::Result<()> {
     smol::block_on(async {
         let mut app = App::build();
 
-        app.insert_resource(WindowDescriptor {
-            title: "skitspel".to_string(),
-            width: GAME_WIDTH,
-            height: GAME_HEIGHT,
-            ..Default::default()
-        })
-        .insert_resource(ClearColor(Color::rgb(0.1, 0.1, 0.1)))
-        .insert_resource(Msaa { samples: 4 })
-        .insert_resource(Port(port_nr))
-        .init_resource::<Players>()
-        .init_resource::<ConnectedPlayers>()
-        .init_resource::<DisconnectedPlayers>()
-        .init_resource::<Games>()
-        .init_resource::<Fonts>()
-        .add_event::<VoteEvent>()
-        .add_plugins(DefaultPlugins)
-        .add_plugin(ShapePlugin)
-        .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
-        .add_plugin(RapierRenderPlugin)
-        .add_plugin(NetworkPlugin)
-        .add_plugin(MenuPlugin)
-        .add_plugin(GameSelectionPlugin)
-        .add_plugin(PushGamePlugin)
-        .add_plugin(HockeyGamePlugin)
-        .add_plugin(VolleyBallGamePlugin)
-        .add_plugin(AchtungGamePlugin)
-        .add_plugin(PongGamePlugin)
-        .add_state(GameState::StartMenu)
-        .add_startup_system(common_setup.system())
-        .add_system(camera_scaling_fix.system())
-        .add_system(handle_general_message.system())
-        .add_system(handle_action_message.system())
-        .add_system(handle_fullscreen.system());
+        if headless {
+            // Only the plugins needed by the game states, physics & network are
+            // added (no winit, wgpu, audio, gilrs, UI etc.). The render, sprite
+            // & text plugins are still added since the games creates meshes,
+            // materials & texts. The render resources are only created in a
+            // "headless" context and nothing is ever drawn, so no window or GPU
+            // is needed.
+            app.insert_resource(ScheduleRunnerSettings::run_loop(Duration::from_secs_f64(
+                1.0 / HEADLESS_TICK_RATE,
+            )))
+            .insert_resource::<Box<dyn RenderResourceContext>>(Box::new(
+                HeadlessRenderResourceContext::default(),
+            ))
+            .add_plugin(LogPlugin::default())
+            .add_plugins(MinimalPlugins)
+            .add_plugin(TransformPlugin::default())
+            .add_plugin(InputPlugin::default())
+            .add_plugin(WindowPlugin::default())
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(RenderPlugin::default())
+            .add_plugin(SpritePlugin::default())
+            .add_plugin(TextPlugin::default());
+        } else {
+            app.insert_resource(WindowDescriptor {
+                title: "skitspel".to_string(),
+                width: GAME_WIDTH,
+                height: GAME_HEIGHT,
+                ..Default::default()
+            })
+            .insert_resource(ClearColor(Color::rgb(0.1, 0.1, 0.1)))
+            .insert_resource(Msaa { samples: 4 })
+            .add_plugins(DefaultPlugins)
+            .add_plugin(RapierRenderPlugin)
+            .add_system(camera_scaling_fix.system())
+            .add_system(handle_fullscreen.system());
+        }
+
+        // Needs to be inserted before the game plugins are added, otherwise
+        // the plugins inserts their default configs.
+        config.insert_resources(&mut app);
+
+        app.insert_resource(Port(port_nr))
+            .insert_resource(InitialBots(initial_bots))
+            .insert_resource(profiles)
+            .init_resource::<Players>()
+            .init_resource::<ConnectedPlayers>()
+            .init_resource::<DisconnectedPlayers>()
+            .init_resource::<ReconnectedPlayers>()
+            .init_resource::<ConnectedSpectators>()
+            .init_resource::<Games>()
+            .init_resource::<Fonts>()
+            .add_event::<VoteEvent>()
+            .add_event::<EndMatchEvent>()
+            .add_event::<BotEvent>()
+            .add_plugin(ShapePlugin)
+            .add_plugin(RapierPhysicsPlugin::<NoUserData>::default())
+            .add_plugin(NetworkPlugin)
+            .add_plugin(LocalInputPlugin)
+            .add_plugin(MenuPlugin)
+            .add_plugin(GameSelectionPlugin)
+            .add_plugin(TournamentPlugin)
+            .add_plugin(MatchResultsPlugin)
+            .add_plugin(LeaderboardPlugin)
+            .add_plugin(PushGamePlugin)
+            .add_plugin(HockeyGamePlugin)
+            .add_plugin(VolleyBallGamePlugin)
+            .add_plugin(AchtungGamePlugin)
+            .add_plugin(PongGamePlugin)
+            .add_state(GameState::StartMenu)
+            .add_startup_system(common_setup.system())
+            .add_startup_system(add_initial_bots.system())
+            .add_system(handle_general_message.system().label("general_message"))
+            .add_system(handle_action_message.system())
+            .add_system(handle_bot_event.system())
+            .add_system(send_game_state.system().after("general_message"))
+            .add_system(send_score.system().after("general_message"))
+            // Runs in the last stage so that an `AppExit` sent during this frame
+            // is seen before the app exits.
+            .add_system_to_stage(CoreStage::Last, sync_profiles.system());
 
         if let Some(tls_cert) = tls_cert {
             app.insert_resource(tls_cert);
         }
 
+        // The games are registered when their plugins are added above.
+        check_game_ids(app.world());
+        add_clear_actions_systems(&mut app);
+
+        if let Some(profiles_file) = profiles_file {
+            app.insert_resource(profiles_file);
+        }
+
+        if let Some(config_path) = config_path {
+            app.insert_resource(ConfigWatcher::new(config_path))
+                .add_system(reload_config.exclusive_system());
+        }
+
         app.run();
         Ok(())
     })
 }
 
-#[allow(clippy::too_many_arguments)]
 pub fn common_setup(
     mut commands: Commands,
     mut font_assets: ResMut<Assets<Font>>,
-    mut textures: ResMut<Assets<Texture>>,
     mut pipelines: ResMut<Assets<PipelineDescriptor>>,
     mut shaders: ResMut<Assets<Shader>>,
-    mut materials: ResMut<Assets<ColorMaterial>>,
     mut configuration: ResMut<RapierConfiguration>,
     mut fonts: ResMut<Fonts>,
-    mut games: ResMut<Games>,
 ) {
     fonts.bold = font_assets.add(
         Font::try_from_bytes(include_bytes!("..\\assets\\FiraSans-Bold.ttf").to_vec()).unwrap(),
@@ -166,82 +313,6 @@ pub fn common_setup(
         Font::try_from_bytes(include_bytes!("..\\assets\\FiraMono-Medium.ttf").to_vec()).unwrap(),
     );
 
-    games.push(Game {
-        name: "Push",
-        game_state: GameState::PushGame,
-        screenshot: materials.add(
-            textures
-                .add(
-                    Texture::from_buffer(
-                        include_bytes!("..\\assets\\push.png"),
-                        ImageType::Extension("png"),
-                    )
-                    .unwrap(),
-                )
-                .into(),
-        ),
-    });
-    games.push(Game {
-        name: "Hockey",
-        game_state: GameState::HockeyGame,
-        screenshot: materials.add(
-            textures
-                .add(
-                    Texture::from_buffer(
-                        include_bytes!("..\\assets\\hockey.png"),
-                        ImageType::Extension("png"),
-                    )
-                    .unwrap(),
-                )
-                .into(),
-        ),
-    });
-    games.push(Game {
-        name: "Volleyball",
-        game_state: GameState::VolleyBallGame,
-        screenshot: materials.add(
-            textures
-                .add(
-                    Texture::from_buffer(
-                        include_bytes!("..\\assets\\volleyball.png"),
-                        ImageType::Extension("png"),
-                    )
-                    .unwrap(),
-                )
-                .into(),
-        ),
-    });
-    games.push(Game {
-        name: "Achtung die Kurve",
-        game_state: GameState::AchtungGame,
-        screenshot: materials.add(
-            textures
-                .add(
-                    Texture::from_buffer(
-                        include_bytes!("..\\assets\\achtung.png"),
-                        ImageType::Extension("png"),
-                    )
-                    .unwrap(),
-                )
-                .into(),
-        ),
-    });
-    games.push(Game {
-        name: "Pong",
-        game_state: GameState::PongGame,
-        screenshot: materials.add(
-            textures
-                .add(
-                    Texture::from_buffer(
-                        include_bytes!("..\\assets\\pong.png"),
-                        ImageType::Extension("png"),
-                    )
-                    .unwrap(),
-                )
-                .into(),
-        ),
-    });
-
     let pipeline_handle = pipelines.add(PipelineDescriptor::default_config(ShaderStages {
         vertex: shaders.add(Shader::from_glsl(ShaderStage::Vertex, VERTEX_SHADER)),
         fragment: Some(shaders.add(Shader::from_glsl(ShaderStage::Fragment, FRAGMENT_SHADER))),
@@ -264,50 +335,152 @@ pub fn common_setup(
 /// specific  players input action. Example of messages that this function
 /// handles are:
 ///  - Player connect.
+///  - Bot connect.
+///  - Player reconnect (resumed session).
+///  - Player connection dropped.
 ///  - Player disconnect.
+///  - Spectator connect/disconnect.
+///  - Player latency measurements.
 ///
 /// Player will be added/removed from the `Players` resource. Newly connected/
-/// disconnected players will be temporary stored in `ConnectedPlayers` &
-/// `DisconnectedPlayers` respectively for one tick. This will allow any game
-/// running to easily see the changes and handle them if they want to.
+/// reconnected/disconnected players will be temporary stored in `ConnectedPlayers`,
+/// `ReconnectedPlayers` & `DisconnectedPlayers` respectively for one tick. This
+/// will allow any game running to easily see the changes and handle them if they
+/// want to.
+///
+/// A player whose connection dropped is kept in `Players` until the grace
+/// period of its session runs out, so that it keeps its score, color etc. if
+/// it reconnects.
+///
+/// Spectators are never added to `Players`, newly connected spectators are
+/// only stored in `ConnectedSpectators` for one tick.
+///
+/// Bots are added to `Players` like any other player, but the game goes back
+/// to the start menu if there are no human players left.
+///
+/// The all-time statistics of a newly connected player are loaded from the
+/// profile with the name that the player specified (see `Profiles`). A profile
+/// is only used by one player at a time, so if another connected player already
+/// uses the profile, the unique name of the new player (ex. "Anna (2)") is used
+/// as its profile instead. The client is told about that name when connecting.
+#[allow(clippy::too_many_arguments)]
 fn handle_general_message(
     event_ctx: Res<Arc<Mutex<NetworkContext>>>,
     mut players: ResMut<Players>,
     mut connected_players: ResMut<ConnectedPlayers>,
+    mut reconnected_players: ResMut<ReconnectedPlayers>,
     mut disconnected_players: ResMut<DisconnectedPlayers>,
+    mut connected_spectators: ResMut<ConnectedSpectators>,
+    mut latencies: ResMut<Latencies>,
     mut game_state: ResMut<State<GameState>>,
+    profiles: Res<Profiles>,
 ) {
     // The structures containing newly connected/disconnected players are cleared
     // after every tick.
     connected_players.clear();
+    reconnected_players.clear();
     disconnected_players.clear();
+    connected_spectators.clear();
 
     let mut event_ctx_guard = event_ctx.lock().unwrap();
     for EventMessage { player_id, event } in event_ctx_guard.iter_common() {
         match event {
-            NetworkEvent::General(GeneralEvent::Connected(name, _)) => {
+            NetworkEvent::General(GeneralEvent::Connected(name, requested_name, _)) => {
                 let color_idx = rand::thread_rng().gen_range(0..COLORS.len());
                 let color = COLORS[color_idx];
 
-                let new_player = Player::new(player_id, name, color);
+                let profile_in_use = |profile_name: &str| {
+                    players
+                        .values()
+                        .any(|player| player.profile_name() == Some(profile_name))
+                };
+                let profile_name = if !profile_in_use(&requested_name) {
+                    Some(requested_name)
+                } else if !profile_in_use(&name) {
+                    Some(name.clone())
+                } else {
+                    eprintln!(
+                        "No free profile for player with ID {}, statistics won't be saved.",
+                        player_id
+                    );
+                    None
+                };
+
+                let mut new_player = Player::new(player_id, name, color);
+                if let Some(stats) = profile_name
+                    .as_ref()
+                    .and_then(|profile| profiles.get(profile))
+                {
+                    new_player.set_stats(stats.clone());
+                }
+                new_player.set_profile_name(profile_name);
                 players.insert(player_id, new_player.clone());
                 connected_players.insert(player_id, new_player);
 
                 println!("Added new player with ID: {}", player_id);
             }
 
+            NetworkEvent::General(GeneralEvent::BotConnected(name)) => {
+                let color_idx = rand::thread_rng().gen_range(0..COLORS.len());
+                let color = COLORS[color_idx];
+
+                let new_player = Player::new_bot(player_id, name, color);
+                players.insert(player_id, new_player.clone());
+                connected_players.insert(player_id, new_player);
+
+                println!("Added new bot with ID: {}", player_id);
+            }
+
+            NetworkEvent::General(GeneralEvent::Reconnected(..)) => {
+                reconnected_players.insert(player_id);
+
+                println!("Player with ID {} resumed its session.", player_id);
+            }
+
+            NetworkEvent::General(GeneralEvent::Dropped) => {
+                // Release all buttons so that the player doesn't ex. keep
+                // moving while it is gone.
+                if let Some(player) = players.get_mut(&player_id) {
+                    player.reset_action();
+                }
+
+                println!(
+                    "Connection dropped for player with ID {}, waiting for reconnect.",
+                    player_id
+                );
+            }
+
             NetworkEvent::General(GeneralEvent::Disconnected) => {
                 players.remove(&player_id);
+                latencies.remove(&player_id);
                 disconnected_players.insert(player_id);
 
                 println!("Removed player with ID: {}", player_id);
 
-                if players.len() < 2 && *game_state.current() != GameState::StartMenu {
-                    println!("Less than two people connected, go back to start menu!");
+                if (players.len() < 2 || players.human_count() == 0)
+                    && *game_state.current() != GameState::StartMenu
+                {
+                    println!(
+                        "Less than two players or no humans connected, go back to start menu!"
+                    );
                     game_state.set(GameState::StartMenu).unwrap();
                 }
             }
 
+            NetworkEvent::General(GeneralEvent::Latency(rtt)) => {
+                latencies.entry(player_id).or_default().update(rtt);
+            }
+
+            NetworkEvent::General(GeneralEvent::SpectatorConnected(_)) => {
+                connected_spectators.insert(player_id);
+
+                println!("Added new spectator with ID: {}", player_id);
+            }
+
+            NetworkEvent::General(GeneralEvent::SpectatorDisconnected) => {
+                println!("Removed spectator with ID: {}", player_id);
+            }
+
             NetworkEvent::Invalid(data) => {
                 println!(
                     "Received invalid message from player with ID {}: {:#?}",
@@ -326,27 +499,160 @@ fn handle_general_message(
 ///  - Button releases.
 ///
 /// This function parses these inputs from the players and updates the
-/// `PlayerAction` stored inside the `Players`.
-fn handle_action_message(
-    time: Res<Time>,
-    mut event_timer: ResMut<EventTimer>,
-    mut players: ResMut<Players>,
+/// `PlayerAction` stored inside the `Players`. All inputs received since the
+/// last tick are applied in the order that they were sent.
+fn handle_action_message(mut players: ResMut<Players>, event_ctx: Res<Arc<Mutex<NetworkContext>>>) {
+    let mut event_ctx = event_ctx.lock().unwrap();
+    for (player_id, action_event) in event_ctx.iter_action() {
+        // Accessing the player "mutably" will trigger a change event, so only
+        // do it if the player actually have performed an action.
+        if let Some(player) = players.get_mut(&player_id) {
+            player.update_action(&action_event);
+        }
+    }
+}
+
+/// Clears the unread actions of the players when a new state is entered. Not
+/// every state handles the input of the players (ex. the match results), so
+/// the actions performed in such a state would otherwise be handled when the
+/// next state is entered.
+fn add_clear_actions_systems(app: &mut AppBuilder) {
+    let games = app.world().get_resource::<Games>().unwrap();
+    let game_states = [
+        GameState::StartMenu,
+        GameState::GameSelectionMenu,
+        GameState::TournamentStandings,
+        GameState::TournamentPodium,
+        GameState::MatchResults,
+        GameState::Leaderboard,
+    ]
+    .iter()
+    .cloned()
+    .chain(games.iter().map(|game| game.game_state))
+    .collect::<Vec<_>>();
+
+    for game_state in game_states {
+        app.add_system_set(
+            SystemSet::on_enter(game_state).with_system(clear_previous_actions.system()),
+        );
+    }
+}
+
+fn clear_previous_actions(mut players: ResMut<Players>) {
+    for player in players.values_mut() {
+        player.clear_previous_actions();
+    }
+}
+
+fn add_initial_bots(initial_bots: Res<InitialBots>, mut bot_event_writer: EventWriter<BotEvent>) {
+    for _ in 0..initial_bots.0 {
+        bot_event_writer.send(BotEvent::Add);
+    }
+}
+
+/// Adds/removes bots. The bots are injected into the `NetworkContext` in the
+/// same way as local players, so they are handled exactly like the players
+/// connected over websockets (ex. they are only added if there is a free slot).
+fn handle_bot_event(
     event_ctx: Res<Arc<Mutex<NetworkContext>>>,
+    players: Res<Players>,
+    mut bot_event_reader: EventReader<BotEvent>,
+    mut bot_counter: Local<usize>,
 ) {
-    let mut event_ctx = event_ctx.lock().unwrap();
-    if let Some(action_iter) = event_ctx.iter_action(&time, &mut event_timer) {
-        for (player_id, action_event) in action_iter {
-            // Accessing the player "mutably" will trigger a change event.
-            // Prevent that to happen in the normal case when no action have
-            // been performed by the player two ticks in a row. Only access it
-            // mutually if something of possible interest have happened.
-            if let Some(player) = players.get(&player_id) {
-                if player.has_no_action() && matches!(action_event, ActionEvent::None) {
-                    continue;
+    let event_ctx = event_ctx.lock().unwrap();
+    for bot_event in bot_event_reader.iter() {
+        match bot_event {
+            BotEvent::Add => {
+                *bot_counter += 1;
+                let name = format!("Bot {}", *bot_counter);
+                event_ctx.inject(EventMessage {
+                    player_id: event_ctx.generate_player_id(),
+                    event: NetworkEvent::General(GeneralEvent::BotConnected(name)),
+                });
+            }
+            BotEvent::Remove => {
+                let newest_bot_id = players
+                    .values()
+                    .filter(|player| player.is_bot())
+                    .map(|player| player.id())
+                    .max();
+                if let Some(player_id) = newest_bot_id {
+                    event_ctx.inject(EventMessage {
+                        player_id,
+                        event: NetworkEvent::General(GeneralEvent::Disconnected),
+                    });
                 }
             }
-            if let Some(player) = players.get_mut(&player_id) {
-                player.update_action(&action_event);
+        }
+    }
+}
+
+/// Lets the clients know about the current state of the game (ex. which game
+/// that is being played). The state is broadcasted to all clients when it changes
+/// and sent to newly connected/reconnected players & spectators.
+fn send_game_state(
+    game_state: Res<State<GameState>>,
+    games: Res<Games>,
+    connected_players: Res<ConnectedPlayers>,
+    reconnected_players: Res<ReconnectedPlayers>,
+    connected_spectators: Res<ConnectedSpectators>,
+    mut prev_game_state: Local<Option<GameState>>,
+    mut server_message_writer: EventWriter<ServerMessageEvent>,
+) {
+    let cur_game_state = *game_state.current();
+    let name = game_state_name(cur_game_state, &games);
+    if *prev_game_state != Some(cur_game_state) {
+        *prev_game_state = Some(cur_game_state);
+        server_message_writer.send(ServerMessageEvent::Broadcast(ServerMessage::GameState(
+            cur_game_state,
+            name,
+        )));
+    } else {
+        let new_client_ids = connected_players
+            .keys()
+            .chain(reconnected_players.iter())
+            .chain(connected_spectators.iter());
+        for player_id in new_client_ids {
+            server_message_writer.send(ServerMessageEvent::Player(
+                *player_id,
+                ServerMessage::GameState(cur_game_state, name),
+            ));
+        }
+    }
+}
+
+/// Returns the name of the `game_state` that is shown to the players.
+fn game_state_name(game_state: GameState, games: &Games) -> &'static str {
+    match game_state {
+        GameState::StartMenu => "Start menu",
+        GameState::GameSelectionMenu => "Game selection",
+        GameState::TournamentStandings => "Tournament standings",
+        GameState::TournamentPodium => "Tournament podium",
+        GameState::MatchResults => "Match results",
+        GameState::Leaderboard => "Leaderboard",
+        GameState::Game(id) => games.get_by_id(id).map_or(id, |game| game.name),
+    }
+}
+
+/// Sends the score of the players to their clients every time that it changes.
+/// The score is also re-sent to players that have reconnected.
+fn send_score(
+    players: Res<Players>,
+    reconnected_players: Res<ReconnectedPlayers>,
+    mut prev_scores: Local<HashMap<PlayerId, usize>>,
+    mut server_message_writer: EventWriter<ServerMessageEvent>,
+) {
+    if players.is_changed() || !reconnected_players.is_empty() {
+        prev_scores.retain(|player_id, _| {
+            players.contains_key(player_id) && !reconnected_players.contains(player_id)
+        });
+        for player in players.values() {
+            if prev_scores.get(&player.id()) != Some(&player.total_score()) {
+                prev_scores.insert(player.id(), player.total_score());
+                server_message_writer.send(ServerMessageEvent::Player(
+                    player.id(),
+                    ServerMessage::Score(player.total_score()),
+                ));
             }
         }
     }
@@ -0,0 +1,123 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, ErrorKind},
+    path::PathBuf,
+};
+
+use bevy::{
+    app::AppExit,
+    core::{Time, Timer},
+    prelude::{EventReader, Res, ResMut},
+};
+
+use skitspel::{Players, Profiles};
+
+/// How often (in seconds) the profiles are written to disk if they have changed.
+const PROFILES_SAVE_TIME: f32 = 10.0;
+
+/// The file that the `Profiles` are stored in as JSON.
+///
+/// Example of a profiles file:
+/// ```json
+/// {
+///   "Alice": {
+///     "games": {
+///       "pong": { "round_wins": 3, "matches_played": 1, "match_wins": 0,
+///                 "goals": 0, "longest_survival": 0.0, "deaths": 7 }
+///     }
+///   }
+/// }
+/// ```
+pub struct ProfilesFile {
+    path: PathBuf,
+    /// Set to true if the profiles have changed since they were last saved.
+    dirty: bool,
+    save_timer: Timer,
+}
+
+impl ProfilesFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            dirty: false,
+            save_timer: Timer::from_seconds(PROFILES_SAVE_TIME, true),
+        }
+    }
+
+    /// Reads the profiles from the file. If the file doesn't exist, no profiles
+    /// are loaded and the file is created when the profiles are saved for the
+    /// first time.
+    pub fn load(&self) -> io::Result<Profiles> {
+        match fs::read_to_string(&self.path) {
+            Ok(content) => serde_json::from_str::<BTreeMap<_, _>>(&content)
+                .map(Profiles::from)
+                .map_err(|e| {
+                    io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!("Unable to parse profiles file {:?}: {}", self.path, e),
+                    )
+                }),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                println!(
+                    "No profiles file found at {:?}, creating a new one.",
+                    self.path
+                );
+                Ok(Profiles::default())
+            }
+            Err(e) => Err(io::Error::new(
+                e.kind(),
+                format!("Unable to read profiles file {:?}: {}", self.path, e),
+            )),
+        }
+    }
+
+    fn save(&self, profiles: &Profiles) -> io::Result<()> {
+        let content = serde_json::to_string_pretty(&**profiles)
+            .map_err(|e| io::Error::new(ErrorKind::InvalidData, e))?;
+        fs::write(&self.path, content)
+    }
+}
+
+/// Copies the all-time statistics of the connected players into the `Profiles`.
+/// If the server is using a `ProfilesFile`, the profiles are saved to it
+/// periodically when any of them have changed, and one last time when the
+/// server exits.
+///
+/// Bots have no profiles, their statistics are lost when they disconnect. The
+/// same goes for players that don't have a profile (see `Player::profile_name`).
+pub fn sync_profiles(
+    time: Res<Time>,
+    players: Res<Players>,
+    mut profiles: ResMut<Profiles>,
+    profiles_file: Option<ResMut<ProfilesFile>>,
+    mut app_exit_reader: EventReader<AppExit>,
+) {
+    let mut changed = false;
+    if players.is_changed() {
+        for player in players.values() {
+            if let Some(profile_name) = player.profile_name() {
+                if profiles.get(profile_name) != Some(player.stats()) {
+                    profiles.insert(profile_name.into(), player.stats().clone());
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    if let Some(mut profiles_file) = profiles_file {
+        profiles_file.dirty |= changed;
+        profiles_file.save_timer.tick(time.delta());
+        let exiting = app_exit_reader.iter().next().is_some();
+        if (profiles_file.save_timer.just_finished() || exiting) && profiles_file.dirty {
+            if let Err(e) = profiles_file.save(&profiles) {
+                eprintln!(
+                    "Unable to save profiles file {:?}: {}",
+                    profiles_file.path, e
+                );
+            } else {
+                profiles_file.dirty = false;
+            }
+        }
+    }
+}